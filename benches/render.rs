@@ -0,0 +1,101 @@
+// Benchmarks of laying out and rendering generated plans of increasing size, run with `just bench`
+use core::fmt::Arguments;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gantt_chart::{ChartData, GanttChartLog, GanttChartTool, OutputFormat, RenderOptions};
+use serde_json::json;
+use std::io;
+
+struct QuietLog;
+
+impl GanttChartLog for QuietLog {
+    fn output(&self, _args: Arguments) {}
+    fn warning(&self, _args: Arguments) {}
+    fn error(&self, _args: Arguments) {}
+}
+
+static SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+// A plan with the items shared between a few resources, each depending on the item two before it
+fn generated_chart(items: usize) -> ChartData {
+    let items: Vec<_> = (0..items)
+        .map(|i| {
+            let mut item = json!({
+                "title": format!("Task {}", i),
+                "id": format!("t{}", i),
+                "duration": 1 + i % 5,
+                "resource": i % 8,
+            });
+
+            if i == 0 {
+                item["startDate"] = json!("2024-1-1");
+            } else if i > 1 {
+                item["dependsOn"] = json!([format!("t{}", i - 2)]);
+            }
+
+            item
+        })
+        .collect();
+
+    serde_json::from_value(json!({
+        "title": "Generated Plan",
+        "resources": (0..8).map(|i| format!("Team {}", i)).collect::<Vec<_>>(),
+        "items": items,
+    }))
+    .expect("The generated chart should be valid")
+}
+
+fn layout(c: &mut Criterion) {
+    let log = QuietLog;
+    let tool = GanttChartTool::new(&log);
+    let options = RenderOptions::default();
+    let mut group = c.benchmark_group("layout");
+
+    for size in SIZES {
+        let chart_data = generated_chart(size);
+
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &chart_data,
+            |b, chart_data| b.iter(|| tool.process_chart_data(&options, chart_data).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+fn render(c: &mut Criterion) {
+    let log = QuietLog;
+    let tool = GanttChartTool::new(&log);
+    let mut group = c.benchmark_group("render");
+
+    group.sample_size(10);
+
+    for format in [OutputFormat::Svg, OutputFormat::Html] {
+        let options = RenderOptions {
+            format,
+            ..RenderOptions::default()
+        };
+
+        for size in SIZES {
+            let chart_data = generated_chart(size);
+
+            group.throughput(Throughput::Elements(size as u64));
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", format), size),
+                &chart_data,
+                |b, chart_data| {
+                    b.iter(|| {
+                        tool.render_to_writer(chart_data, &options, io::sink())
+                            .unwrap()
+                    })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, layout, render);
+criterion_main!(benches);
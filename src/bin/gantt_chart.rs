@@ -11,15 +11,24 @@ impl GanttChartLogger {
 }
 
 impl GanttChartLog for GanttChartLogger {
-    fn output(self: &Self, args: Arguments) {
+    fn output(&self, args: Arguments) {
         println!("{}", args);
     }
-    fn warning(self: &Self, args: Arguments) {
+    fn warning(&self, args: Arguments) {
         eprintln!("{}", format!("warning: {}", args).yellow());
     }
-    fn error(self: &Self, args: Arguments) {
+    fn error(&self, args: Arguments) {
         eprintln!("{}", format!("error: {}", args).red());
     }
+    fn info(&self, args: Arguments) {
+        eprintln!("{}", format!("info: {}", args).dimmed());
+    }
+    fn debug(&self, args: Arguments) {
+        eprintln!("{}", format!("debug: {}", args).dimmed());
+    }
+    fn trace(&self, args: Arguments) {
+        eprintln!("{}", format!("trace: {}", args).dimmed());
+    }
 }
 
 fn main() {
@@ -27,6 +36,6 @@ fn main() {
 
     if let Err(error) = GanttChartTool::new(&logger).run(std::env::args_os()) {
         error!(logger, "{}", error);
-        std::process::exit(1);
+        std::process::exit(error.exit_code());
     }
 }
@@ -0,0 +1,9 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BreakData {
+    pub start: NaiveDate,
+    // The last day of the break
+    pub end: NaiveDate,
+}
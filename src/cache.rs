@@ -0,0 +1,122 @@
+// Remember what charts were rendered from, so that charts that haven't changed aren't rendered again
+use crate::{sha1_hex, GanttError, RenderOptions};
+use chrono::NaiveDate;
+use sha1::{Digest, Sha1};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+// Without a .json extension, so it isn't taken for a chart when the charts are in the output directory too
+static BATCH_CACHE_FILE: &str = ".gantt-chart-cache";
+
+/// A hash of the render options and the version of the tool, worked out once for all the charts rendered with them
+pub(crate) fn options_hash(options: &RenderOptions) -> String {
+    let mut hasher = Sha1::new();
+    // The font to embed is hashed as it is, as formatting its bytes with the other options would be slow
+    let other_options = RenderOptions {
+        embed_font: None,
+        ..options.clone()
+    };
+
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update(format!("{:?}\0", other_options));
+
+    if let Some(ref font) = options.embed_font {
+        hasher.update(font);
+    }
+
+    sha1_hex(hasher)
+}
+
+/// A hash of everything that goes into a rendered chart, which is the chart file, the holidays added to it and what
+/// [`options_hash`] gives for the render options
+pub(crate) fn cache_key(content: &[u8], holidays: &[NaiveDate], options_hash: &str) -> String {
+    let mut hasher = Sha1::new();
+
+    hasher.update(options_hash);
+    hasher.update(format!("{:?}\0", holidays));
+    hasher.update(content);
+
+    sha1_hex(hasher)
+}
+
+/// The keys of the charts rendered into a batch output directory, by output file path, kept in a file in the
+/// directory between batches
+pub(crate) struct BatchCache {
+    path: PathBuf,
+    keys: HashMap<String, String>,
+}
+
+impl BatchCache {
+    /// Read the keys kept in the output directory, starting afresh if there aren't any or they can't be read
+    pub(crate) fn load(out_dir: &Path) -> BatchCache {
+        let path = out_dir.join(BATCH_CACHE_FILE);
+        let keys = fs::read(&path)
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default();
+
+        BatchCache { path, keys }
+    }
+
+    fn entry(output_file: &Path) -> String {
+        output_file.to_string_lossy().into_owned()
+    }
+
+    /// Whether the output file is still there and was rendered from a chart with the same key
+    pub(crate) fn is_current(&self, output_file: &Path, key: &str) -> bool {
+        output_file.is_file()
+            && self
+                .keys
+                .get(&Self::entry(output_file))
+                .is_some_and(|k| k == key)
+    }
+
+    pub(crate) fn insert(&mut self, output_file: &Path, key: String) {
+        self.keys.insert(Self::entry(output_file), key);
+    }
+
+    pub(crate) fn remove(&mut self, output_file: &Path) {
+        self.keys.remove(&Self::entry(output_file));
+    }
+
+    pub(crate) fn save(&self) -> Result<(), GanttError> {
+        serde_json::to_writer_pretty(File::create(&self.path)?, &self.keys)
+            .map_err(io::Error::from)?;
+
+        Ok(())
+    }
+}
+
+/// The last chart rendered and its key, for serving it again while nothing it was rendered from has changed
+#[derive(Default)]
+pub(crate) struct LastRender(Mutex<Option<(String, String)>>);
+
+impl LastRender {
+    /// The chart last rendered if it has the same key, or else the chart rendered again
+    ///
+    /// Requests for a chart while it is being rendered wait for it rather than rendering it as well.
+    pub(crate) fn get_or_render(
+        &self,
+        key: String,
+        render: impl FnOnce() -> Result<String, GanttError>,
+    ) -> Result<String, GanttError> {
+        let mut last = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some((last_key, rendered)) = last.as_ref() {
+            if *last_key == key {
+                return Ok(rendered.clone());
+            }
+        }
+
+        let rendered = render()?;
+
+        *last = Some((key, rendered.clone()));
+
+        Ok(rendered)
+    }
+}
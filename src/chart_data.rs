@@ -1,12 +1,350 @@
-use serde::{Deserialize, Serialize};
-use chrono::NaiveDate;
+use crate::break_data::BreakData;
+use crate::error::GanttError;
 use crate::item_data::ItemData;
+use crate::label_data::LabelData;
+use crate::phase_data::PhaseData;
+use crate::resource_data::{ResourceData, UNNAMED_RESOURCE};
+use crate::risk_data::RiskData;
+use crate::sign_off_data::SignOffData;
+use crate::sprint_data::SprintData;
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ChartData {
     pub title: String,
     #[serde(rename = "markedDate")]
     pub marked_date: Option<NaiveDate>,
-    pub resources: Vec<String>,
+    // Charts without resources have all their items unassigned
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resources: Vec<ResourceData>,
     pub items: Vec<ItemData>,
-}
\ No newline at end of file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sprints: Vec<SprintData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phases: Vec<PhaseData>,
+    // The days that aren't worked, Saturday and Sunday if not given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weekend: Option<Vec<Weekday>>,
+    // Days off for everyone, treated like weekends
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub holidays: Vec<NaiveDate>,
+    // Date ranges, such as a holiday shutdown, squeezed into a narrow gap in the chart
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub breaks: Vec<BreakData>,
+    // Risks to the items, for flagging them and the risk register
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub risks: Vec<RiskData>,
+    // The people who must approve the plan, with room for their signatures under the chart
+    #[serde(rename = "signOff", default, skip_serializing_if = "Vec::is_empty")]
+    pub sign_off: Vec<SignOffData>,
+    // Replacements for the chart's fixed text
+    #[serde(default, skip_serializing_if = "LabelData::is_default")]
+    pub labels: LabelData,
+    // The Git object hash of the file the chart was read from, for tracing rendered charts back to it
+    #[serde(skip)]
+    pub source_hash: Option<String>,
+}
+
+impl ChartData {
+    pub fn is_weekend(&self, date: NaiveDate) -> bool {
+        match self.weekend {
+            Some(ref weekend) => weekend.contains(&date.weekday()),
+            None => matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+        }
+    }
+
+    pub fn is_working_day(&self, date: NaiveDate) -> bool {
+        !self.is_weekend(date) && !self.holidays.contains(&date)
+    }
+
+    // The working days in a week, not counting holidays, for durations given in weeks
+    pub(crate) fn working_days_per_week(&self) -> f32 {
+        let weekend_days = match self.weekend {
+            Some(ref weekend) => (0..7)
+                .filter(|&d| weekend.iter().any(|day| day.num_days_from_monday() == d))
+                .count(),
+            None => 2,
+        };
+
+        (7 - weekend_days).max(1) as f32
+    }
+
+    // Whether any of the items aren't on a resource
+    pub(crate) fn has_unassigned(&self) -> bool {
+        self.items.iter().any(|item| item.resource_index.is_none())
+    }
+
+    // The resources the items are on, with an unnamed one after them for the items without a resource
+    pub(crate) fn item_resources(&self) -> impl Iterator<Item = &ResourceData> {
+        self.resources
+            .iter()
+            .chain(self.has_unassigned().then_some(&UNNAMED_RESOURCE))
+    }
+
+    // The resource of an item, where those without one are after the chart's resources
+    pub(crate) fn item_resource(&self, index: usize) -> &ResourceData {
+        self.resources.get(index).unwrap_or(&UNNAMED_RESOURCE)
+    }
+
+    // The name of the resource of an item, or the unassigned label for those without one
+    pub(crate) fn resource_name(&self, index: usize) -> &str {
+        self.resources
+            .get(index)
+            .map_or(&self.labels.unassigned, |resource| &resource.name)
+    }
+
+    // The index of the item with an ID, or failing that a title, such as one given on the command line
+    pub(crate) fn find_item(&self, id_or_title: &str) -> Option<usize> {
+        self.items
+            .iter()
+            .position(|item| item.id.as_deref() == Some(id_or_title))
+            .or_else(|| self.items.iter().position(|item| item.title == id_or_title))
+    }
+
+    /// Add the resources, items, sprints, phases, breaks, risks and holidays of another chart to this one
+    ///
+    /// Resources with the same name are combined, and sprints that are the same in both charts are only added once.
+    /// The title, marked date, weekend and labels of this chart are kept, unless it doesn't have a marked date or
+    /// weekend of its own. Fails without changing this chart if an item of the other chart is on a resource it
+    /// doesn't have.
+    pub fn merge(&mut self, other: ChartData) -> Result<(), GanttError> {
+        self.merge_items(other, None)
+    }
+
+    /// Add another chart to this one as with [`merge`](Self::merge), putting its items in a group
+    ///
+    /// Items that are already in a group are kept in it. The item ids of the other chart are prefixed with the group
+    /// name, such as `Mobile/design`, so that charts using the same ids can be merged, and its `dependsOn` and risks
+    /// naming them are changed to match. Ids of items in this chart and the prefixed ids of charts merged before can
+    /// be named from the other chart as they are.
+    pub fn merge_group(&mut self, other: ChartData, group: &str) -> Result<(), GanttError> {
+        self.merge_items(other, Some(group))
+    }
+
+    fn merge_items(&mut self, mut other: ChartData, group: Option<&str>) -> Result<(), GanttError> {
+        if let Some((index, item)) = other.items.iter().enumerate().find(|(_, item)| {
+            item.resource_index
+                .is_some_and(|index| index >= other.resources.len())
+        }) {
+            return Err(GanttError::validation(
+                Some(index),
+                "resource",
+                format!(
+                    "Item '{}' of the chart being merged is on resource {}, which that chart doesn't have",
+                    item.title,
+                    item.resource_index.unwrap_or_default()
+                ),
+            ));
+        }
+
+        if let Some(group) = group {
+            other.prefix_ids(group);
+        }
+
+        let mut resource_indices = vec![];
+
+        // The chart no longer comes from a single file
+        self.source_hash = None;
+
+        for resource in other.resources {
+            match self.resources.iter().position(|r| r.name == resource.name) {
+                Some(index) => {
+                    let existing = &mut self.resources[index];
+
+                    existing.capacity = existing.capacity.or(resource.capacity);
+                    existing.absences.extend(resource.absences);
+                    resource_indices.push(index);
+                }
+                None => {
+                    resource_indices.push(self.resources.len());
+                    self.resources.push(resource);
+                }
+            }
+        }
+
+        for mut item in other.items {
+            item.resource_index = item.resource_index.map(|index| resource_indices[index]);
+
+            if item.group.is_none() {
+                item.group = group.map(str::to_owned);
+            }
+
+            self.items.push(item);
+        }
+
+        for sprint in other.sprints {
+            if !self
+                .sprints
+                .iter()
+                .any(|s| s.name == sprint.name && s.start == sprint.start && s.end == sprint.end)
+            {
+                self.sprints.push(sprint);
+            }
+        }
+
+        self.phases.extend(other.phases);
+        self.breaks.extend(other.breaks);
+        self.risks.extend(other.risks);
+        self.holidays.extend(other.holidays);
+        self.holidays.sort();
+        self.holidays.dedup();
+        self.marked_date = self.marked_date.or(other.marked_date);
+
+        if self.weekend.is_none() {
+            self.weekend = other.weekend;
+        }
+
+        Ok(())
+    }
+
+    // Put a prefix on the ids of the items, and on the ids in the dependencies and risks that name them
+    fn prefix_ids(&mut self, prefix: &str) {
+        let ids: HashSet<String> = self
+            .items
+            .iter()
+            .filter_map(|item| item.id.clone())
+            .collect();
+        let prefixed = |id: &mut String| {
+            if ids.contains(id.as_str()) {
+                *id = format!("{}/{}", prefix, id);
+            }
+        };
+
+        for item in &mut self.items {
+            item.id.iter_mut().for_each(prefixed);
+            item.depends_on.iter_mut().flatten().for_each(prefixed);
+        }
+
+        for risk in &mut self.risks {
+            risk.tasks.iter_mut().for_each(prefixed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(json: &str) -> ChartData {
+        json5::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn merged_items_keep_their_resources() {
+        let mut chart_data = chart(
+            r#"{title: "Web", resources: ["Alex", "Sam"], items: [
+                {title: "Design", startDate: "2024-03-04", duration: 2, resource: 1},
+            ]}"#,
+        );
+        let other = chart(
+            r#"{title: "Mobile", resources: ["Sam", {name: "Priya", capacity: 0.5}], items: [
+                {title: "Build", startDate: "2024-03-04", duration: 3, resource: 1},
+                {title: "Test", duration: 1, resource: 0},
+            ]}"#,
+        );
+
+        chart_data.merge(other).unwrap();
+
+        assert_eq!(
+            chart_data
+                .resources
+                .iter()
+                .map(|resource| resource.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Alex", "Sam", "Priya"]
+        );
+        assert_eq!(chart_data.resources[2].capacity, Some(0.5));
+        assert_eq!(
+            chart_data
+                .items
+                .iter()
+                .map(|item| item.resource_index)
+                .collect::<Vec<_>>(),
+            [Some(1), Some(2), Some(1)]
+        );
+        assert_eq!(chart_data.title, "Web");
+    }
+
+    #[test]
+    fn merged_sprints_and_holidays_are_only_added_once() {
+        let mut chart_data = chart(
+            r#"{title: "Web", holidays: ["2024-03-29"], items: [],
+                sprints: [{name: "1", start: "2024-03-04", end: "2024-03-15"}]}"#,
+        );
+        let other = chart(
+            r#"{title: "Mobile", markedDate: "2024-03-11", weekend: ["Fri", "Sat"],
+                holidays: ["2024-04-01", "2024-03-29"], items: [],
+                sprints: [
+                    {name: "1", start: "2024-03-04", end: "2024-03-15"},
+                    {name: "2", start: "2024-03-18", end: "2024-03-29"},
+                ]}"#,
+        );
+
+        chart_data.merge(other).unwrap();
+
+        assert_eq!(chart_data.sprints.len(), 2);
+        assert_eq!(
+            chart_data.holidays,
+            [
+                NaiveDate::from_ymd_opt(2024, 3, 29).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()
+            ]
+        );
+        assert_eq!(chart_data.marked_date, NaiveDate::from_ymd_opt(2024, 3, 11));
+        assert_eq!(chart_data.weekend, Some(vec![Weekday::Fri, Weekday::Sat]));
+    }
+
+    #[test]
+    fn merging_an_item_on_a_missing_resource_fails_without_changing_the_chart() {
+        let mut chart_data = chart(
+            r#"{title: "Web", resources: ["Alex"], items: [
+                {title: "Design", startDate: "2024-03-04", duration: 2, resource: 0},
+            ]}"#,
+        );
+        let other = chart(
+            r#"{title: "Mobile", resources: ["Sam"], items: [
+                {title: "Build", startDate: "2024-03-04", duration: 3, resource: 1},
+            ]}"#,
+        );
+
+        assert!(chart_data.merge(other).is_err());
+        assert_eq!(chart_data.resources.len(), 1);
+        assert_eq!(chart_data.items.len(), 1);
+    }
+
+    #[test]
+    fn merged_groups_have_their_ids_prefixed() {
+        let mut chart_data = chart(
+            r#"{title: "Plan", items: [
+                {title: "Design", id: "design", startDate: "2024-03-04", duration: 2},
+            ]}"#,
+        );
+        let other = chart(
+            r#"{title: "Mobile", items: [
+                {title: "Design", id: "design", startDate: "2024-03-04", duration: 2},
+                {title: "Build", id: "build", duration: 3, dependsOn: ["design"]},
+                {title: "Release", duration: 1, dependsOn: ["build", "Web/api"], group: "Release"},
+            ], risks: [{title: "Late", tasks: ["build"]}]}"#,
+        );
+
+        chart_data.merge_group(other, "Mobile").unwrap();
+
+        let items = &chart_data.items;
+
+        assert_eq!(items[0].id.as_deref(), Some("design"));
+        assert_eq!(items[1].id.as_deref(), Some("Mobile/design"));
+        assert_eq!(items[2].id.as_deref(), Some("Mobile/build"));
+        assert_eq!(items[2].depends_on, Some(vec!["Mobile/design".to_owned()]));
+        // Ids the merged chart doesn't have are left for items merged before it
+        assert_eq!(
+            items[3].depends_on,
+            Some(vec!["Mobile/build".to_owned(), "Web/api".to_owned()])
+        );
+        assert_eq!(items[0].group, None);
+        assert_eq!(items[1].group.as_deref(), Some("Mobile"));
+        assert_eq!(items[3].group.as_deref(), Some("Release"));
+        assert_eq!(chart_data.risks[0].tasks, ["Mobile/build"]);
+    }
+}
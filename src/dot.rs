@@ -0,0 +1,43 @@
+use crate::{GanttError, RenderData};
+use std::{fmt::Write as _, io::Write};
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Write the task dependency network as a Graphviz DOT digraph, colored like the bars
+pub(crate) fn write_dot_file(mut writer: impl Write, rd: &RenderData) -> Result<(), GanttError> {
+    let mut dot = String::new();
+
+    let _ = writeln!(dot, "digraph \"{}\" {{", escape_dot(&rd.title));
+    let _ = writeln!(dot, "  rankdir=LR;");
+    let _ = writeln!(dot, "  node [shape=box, fontname=Arial];");
+
+    for (i, row) in rd.rows.iter().enumerate() {
+        let color = rd.colors[row.color_index];
+
+        let _ = writeln!(
+            dot,
+            "  n{} [label=\"{}\", shape={}, style=\"{}\", color=\"#{:06x}\", fillcolor=\"#{:06x}\", fontcolor=\"{}\"];",
+            i,
+            escape_dot(&row.title),
+            if row.length.is_some() { "box" } else { "diamond" },
+            if row.open { "rounded" } else { "rounded,filled" },
+            color,
+            color,
+            if row.open { "black" } else { "white" }
+        );
+    }
+
+    for (i, row) in rd.rows.iter().enumerate() {
+        for j in &row.predecessors {
+            let _ = writeln!(dot, "  n{} -> n{};", j, i);
+        }
+    }
+
+    let _ = writeln!(dot, "}}");
+
+    writer.write_all(dot.as_bytes())?;
+
+    Ok(())
+}
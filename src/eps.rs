@@ -0,0 +1,166 @@
+use crate::{raster::parse_tree, GanttError, RenderOptions};
+use resvg::{
+    tiny_skia::{PathSegment, Transform},
+    usvg::{self, FillRule, Group, LineCap, LineJoin, Node, Paint},
+};
+use std::{fmt::Write as _, io::Write};
+
+fn set_paint(ps: &mut String, paint: &Paint) {
+    // PostScript has no gradients or patterns, so fall back to a neutral grey for those
+    let color = match paint {
+        Paint::Color(color) => *color,
+        _ => usvg::Color::new_rgb(0x88, 0x88, 0x88),
+    };
+
+    let _ = writeln!(
+        ps,
+        "{:.3} {:.3} {:.3} setrgbcolor",
+        color.red as f32 / 255.0,
+        color.green as f32 / 255.0,
+        color.blue as f32 / 255.0
+    );
+}
+
+fn write_path(ps: &mut String, path: &usvg::Path) {
+    if !path.is_visible() {
+        return;
+    }
+
+    let Transform {
+        sx,
+        ky,
+        kx,
+        sy,
+        tx,
+        ty,
+    } = path.abs_transform();
+
+    ps.push_str("gsave\n");
+    let _ = writeln!(ps, "[{} {} {} {} {} {}] concat", sx, ky, kx, sy, tx, ty);
+    ps.push_str("newpath\n");
+
+    let mut last = (0.0, 0.0);
+
+    for segment in path.data().segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                let _ = writeln!(ps, "{} {} moveto", p.x, p.y);
+                last = (p.x, p.y);
+            }
+            PathSegment::LineTo(p) => {
+                let _ = writeln!(ps, "{} {} lineto", p.x, p.y);
+                last = (p.x, p.y);
+            }
+            PathSegment::QuadTo(p1, p) => {
+                // Raise the quadratic to a cubic curve
+                let c1 = (
+                    last.0 + 2.0 / 3.0 * (p1.x - last.0),
+                    last.1 + 2.0 / 3.0 * (p1.y - last.1),
+                );
+                let c2 = (
+                    p.x + 2.0 / 3.0 * (p1.x - p.x),
+                    p.y + 2.0 / 3.0 * (p1.y - p.y),
+                );
+
+                let _ = writeln!(
+                    ps,
+                    "{} {} {} {} {} {} curveto",
+                    c1.0, c1.1, c2.0, c2.1, p.x, p.y
+                );
+                last = (p.x, p.y);
+            }
+            PathSegment::CubicTo(p1, p2, p) => {
+                let _ = writeln!(
+                    ps,
+                    "{} {} {} {} {} {} curveto",
+                    p1.x, p1.y, p2.x, p2.y, p.x, p.y
+                );
+                last = (p.x, p.y);
+            }
+            PathSegment::Close => ps.push_str("closepath\n"),
+        }
+    }
+
+    if let Some(fill) = path.fill() {
+        ps.push_str("gsave\n");
+        set_paint(ps, fill.paint());
+        ps.push_str(match fill.rule() {
+            FillRule::EvenOdd => "eofill\n",
+            FillRule::NonZero => "fill\n",
+        });
+        ps.push_str("grestore\n");
+    }
+
+    if let Some(stroke) = path.stroke() {
+        set_paint(ps, stroke.paint());
+        let _ = writeln!(ps, "{} setlinewidth", stroke.width().get());
+        let _ = writeln!(
+            ps,
+            "{} setlinecap {} setlinejoin",
+            match stroke.linecap() {
+                LineCap::Butt => 0,
+                LineCap::Round => 1,
+                LineCap::Square => 2,
+            },
+            match stroke.linejoin() {
+                LineJoin::Miter | LineJoin::MiterClip => 0,
+                LineJoin::Round => 1,
+                LineJoin::Bevel => 2,
+            }
+        );
+
+        if let Some(dashes) = stroke.dasharray() {
+            let dashes: Vec<String> = dashes.iter().map(|d| d.to_string()).collect();
+
+            let _ = writeln!(ps, "[{}] {} setdash", dashes.join(" "), stroke.dashoffset());
+        }
+
+        ps.push_str("stroke\n");
+    }
+
+    ps.push_str("grestore\n");
+}
+
+fn write_group(ps: &mut String, group: &Group) {
+    for node in group.children() {
+        match node {
+            Node::Group(group) => write_group(ps, group),
+            Node::Path(path) => write_path(ps, path),
+            // Text has already been converted into paths by the parser
+            Node::Text(text) => write_group(ps, text.flattened()),
+            Node::Image(_) => {}
+        }
+    }
+}
+
+/// Write the chart as Encapsulated PostScript, with all text converted to outlines
+pub(crate) fn write_eps_file(
+    mut writer: impl Write,
+    title: &str,
+    svg: &str,
+    options: &RenderOptions,
+) -> Result<(), GanttError> {
+    let tree = parse_tree(svg, options.embed_font.as_deref())?;
+    let size = tree.size();
+    let mut ps = String::new();
+
+    let _ = writeln!(ps, "%!PS-Adobe-3.0 EPSF-3.0");
+    let _ = writeln!(
+        ps,
+        "%%BoundingBox: 0 0 {} {}",
+        size.width().ceil(),
+        size.height().ceil()
+    );
+    let _ = writeln!(ps, "%%Title: ({})", title.replace(['(', ')', '\\'], ""));
+    let _ = writeln!(ps, "%%Creator: gantt-chart {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(ps, "%%EndComments");
+    // Flip the coordinate system so that SVG user space can be used directly
+    let _ = writeln!(ps, "0 {} translate 1 -1 scale", size.height());
+    write_group(&mut ps, tree.root());
+    let _ = writeln!(ps, "showpage");
+    let _ = writeln!(ps, "%%EOF");
+
+    writer.write_all(ps.as_bytes())?;
+
+    Ok(())
+}
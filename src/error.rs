@@ -0,0 +1,94 @@
+use std::{error::Error, fmt, io};
+
+/// The ways in which generating a chart can fail
+#[derive(Debug)]
+pub enum GanttError {
+    /// The command line arguments are invalid
+    UsageError(String),
+    /// The chart data is not valid JSON5 or doesn't match the expected structure
+    ParseError(String),
+    /// A value in the chart data is invalid
+    ValidationError {
+        /// The index of the offending item, if the problem is with an item
+        item_index: Option<usize>,
+        /// The name of the offending field
+        field: String,
+        message: String,
+    },
+    /// The chart could not be laid out or rendered
+    LayoutError(String),
+    /// Reading the chart data or writing the output failed
+    IoError(io::Error),
+    /// Some of the charts in a batch failed, each having been logged as it happened
+    BatchError { failed: usize, total: usize },
+}
+
+impl GanttError {
+    /// The process exit code for the error, following the BSD `sysexits.h` conventions
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GanttError::UsageError(_) => 64,
+            GanttError::ParseError(_)
+            | GanttError::ValidationError { .. }
+            | GanttError::BatchError { .. } => 65,
+            GanttError::LayoutError(_) => 70,
+            GanttError::IoError(_) => 74,
+        }
+    }
+
+    pub(crate) fn validation(item_index: Option<usize>, field: &str, message: String) -> Self {
+        GanttError::ValidationError {
+            item_index,
+            field: field.to_owned(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for GanttError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Clap's messages come with their own prefix
+            GanttError::UsageError(message) => {
+                write!(f, "{}", message.trim_start_matches("error: ").trim_end())
+            }
+            GanttError::ParseError(message) => write!(f, "Unable to parse chart data: {}", message),
+            GanttError::ValidationError {
+                item_index: Some(item_index),
+                field,
+                message,
+            } => write!(f, "Item {} '{}': {}", item_index, field, message),
+            GanttError::ValidationError {
+                item_index: None,
+                field,
+                message,
+            } => write!(f, "'{}': {}", field, message),
+            GanttError::LayoutError(message) => write!(f, "{}", message),
+            GanttError::IoError(err) => write!(f, "{}", err),
+            GanttError::BatchError { failed, total } => {
+                write!(f, "{} of {} charts could not be rendered", failed, total)
+            }
+        }
+    }
+}
+
+impl Error for GanttError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GanttError::IoError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GanttError {
+    fn from(err: io::Error) -> Self {
+        GanttError::IoError(err)
+    }
+}
+
+impl From<json5::Error> for GanttError {
+    fn from(err: json5::Error) -> Self {
+        GanttError::ParseError(err.to_string())
+    }
+}
@@ -0,0 +1,40 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use resvg::usvg::fontdb::Database;
+
+// Used for fonts that can't be read here, such as WOFF fonts, which only browsers will show
+static EMBEDDED_FONT_FAMILY: &str = "gantt-chart-font";
+
+/// The family name of a TrueType or OpenType font, or a made up name for other fonts
+pub(crate) fn font_family(data: &[u8]) -> String {
+    let mut fontdb = Database::new();
+
+    fontdb.load_font_data(data.to_vec());
+
+    let family = fontdb
+        .faces()
+        .next()
+        .and_then(|face| face.families.first())
+        .map_or(EMBEDDED_FONT_FAMILY.to_owned(), |(family, _)| {
+            family.to_owned()
+        });
+
+    family
+}
+
+/// A CSS `@font-face` rule with the font as a data URI
+pub(crate) fn font_face_style(family: &str, data: &[u8]) -> String {
+    let (mime_type, format) = match data.get(0..4) {
+        Some(b"wOFF") => ("font/woff", "woff"),
+        Some(b"wOF2") => ("font/woff2", "woff2"),
+        Some(b"OTTO") => ("font/otf", "opentype"),
+        _ => ("font/ttf", "truetype"),
+    };
+
+    format!(
+        "@font-face{{font-family:'{}';src:url(data:{};base64,{}) format('{}');}}",
+        family,
+        mime_type,
+        STANDARD.encode(data),
+        format
+    )
+}
@@ -0,0 +1,70 @@
+use crate::GanttError;
+use chrono::NaiveDate;
+use std::io::Read;
+
+/// Read a list of holiday dates, either as a JSON array or one per line
+///
+/// Blank lines and lines starting with `#` are ignored in the line format.
+pub(crate) fn read_holiday_file(mut reader: impl Read) -> Result<Vec<NaiveDate>, GanttError> {
+    let mut content = String::new();
+
+    reader.read_to_string(&mut content)?;
+
+    if content.trim_start().starts_with('[') {
+        return Ok(json5::from_str(&content)?);
+    }
+
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            line.parse::<NaiveDate>().map_err(|e| {
+                GanttError::ParseError(format!(
+                    "Bad holiday date '{}' on line {}: {}",
+                    line,
+                    i + 1,
+                    e
+                ))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn holidays_are_read_a_line_at_a_time() {
+        let holidays = read_holiday_file(
+            "# Company holidays\n2024-03-29\n\n  2024-04-01  \n# Bank holiday\n2024-05-06\n"
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            holidays,
+            [date(2024, 3, 29), date(2024, 4, 1), date(2024, 5, 6)]
+        );
+    }
+
+    #[test]
+    fn holidays_are_read_as_a_json_array() {
+        let holidays = read_holiday_file("[\"2024-03-29\", \"2024-04-01\",]".as_bytes()).unwrap();
+
+        assert_eq!(holidays, [date(2024, 3, 29), date(2024, 4, 1)]);
+    }
+
+    #[test]
+    fn bad_holidays_give_the_line() {
+        let error = read_holiday_file("2024-03-29\n# Easter\n2024-04-31\n".as_bytes()).unwrap_err();
+
+        assert!(error.to_string().contains("line 3"));
+    }
+}
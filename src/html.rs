@@ -0,0 +1,264 @@
+use crate::GanttError;
+use std::io::Write;
+
+static HTML_STYLE: &str = r#"
+body{margin:0;}
+.row-highlight{fill:transparent;}
+.row.hover .row-highlight{fill:#fff3c4;}
+.heading.hover{font-weight:bold;fill:#cc7a00;}
+.row>rect[class^="resource-"],.row>.milestone{cursor:pointer;}
+.chart-scroll{overflow:auto;max-height:100vh;}
+.sticky{pointer-events:none;}
+.sticky-background{fill:white;}
+.scroll-hint{margin:4px 10px;font-family:Arial;font-size:10pt;color:#888888;}
+.chart-index{margin:4px 10px;font-family:Arial;font-size:10pt;}
+.chart-index p{margin:2px 0;}
+.chart-index-heading{font-weight:bold;margin-right:8px;}
+.chart-index a{margin-right:8px;}
+"#;
+
+static HTML_SCRIPT: &str = r#"
+(function () {
+  var rows = document.querySelectorAll(".row");
+  var arrows = document.querySelectorAll(".dependency");
+  var pinned = document.querySelector(".row.selected");
+  var scroll = document.querySelector(".chart-scroll");
+  var hint = document.querySelector(".scroll-hint");
+
+  // Only suggest scrolling when the chart doesn't fit
+  function updateHint() {
+    hint.hidden = scroll.scrollWidth <= scroll.clientWidth;
+  }
+
+  if (hint) {
+    updateHint();
+    window.addEventListener("resize", updateHint);
+  }
+
+  // Keep the header and title column in view by moving copies of them with the scrolling
+  var stickies = document.querySelectorAll(".sticky");
+
+  scroll.addEventListener("scroll", function () {
+    stickies.forEach(function (sticky) {
+      var x = sticky.classList.contains("sticky-left") ? scroll.scrollLeft : 0;
+      var y = sticky.classList.contains("sticky-top") ? scroll.scrollTop : 0;
+
+      sticky.setAttribute("transform", "translate(" + x + " " + y + ")");
+    });
+  });
+
+  // Each row by its index, to walk the dependencies between them
+  var rowsByIndex = {};
+
+  rows.forEach(function (row) {
+    rowsByIndex[row.dataset.row] = row;
+  });
+
+  function neighbours(row, name) {
+    var value = row.dataset[name];
+
+    return value ? value.split(" ") : [];
+  }
+
+  // The row with all the rows it depends on and all the rows that depend on it, by index
+  function chainOf(row) {
+    var chain = {};
+
+    chain[row.dataset.row] = true;
+
+    ["predecessors", "successors"].forEach(function (name) {
+      var stack = [row];
+
+      while (stack.length > 0) {
+        neighbours(stack.pop(), name).forEach(function (index) {
+          if (!chain[index]) {
+            chain[index] = true;
+            stack.push(rowsByIndex[index]);
+          }
+        });
+      }
+    });
+
+    return chain;
+  }
+
+  // Fade out the rows and arrows outside a dependency chain, or none of them when there's no chain
+  function dim(chain) {
+    rows.forEach(function (other) {
+      var inChain = !!chain && !!chain[other.dataset.row];
+
+      other.classList.toggle("dimmed", !!chain && !inChain);
+      other.classList.toggle("highlighted", inChain);
+    });
+
+    arrows.forEach(function (arrow) {
+      var ends = arrow.dataset.rows.split(" ");
+
+      arrow.classList.toggle("dimmed", !!chain && (!chain[ends[0]] || !chain[ends[1]]));
+    });
+  }
+
+  function highlight(row, on) {
+    row.classList.toggle("hover", on);
+
+    var heading = document.querySelector('.heading[data-col="' + row.dataset.col + '"]');
+
+    if (heading) {
+      heading.classList.toggle("hover", on);
+    }
+
+    // Only dim other rows when the hovered task is part of a dependency chain, and no chain is pinned
+    if (!pinned) {
+      var chain = chainOf(row);
+
+      if (Object.keys(chain).length > 1) {
+        dim(on ? chain : null);
+      }
+    }
+  }
+
+  // Clicking a task keeps its dependency chain highlighted until it's clicked again
+  function pin(row) {
+    if (pinned) {
+      pinned.classList.remove("selected");
+    }
+
+    pinned = pinned === row ? null : row;
+
+    if (pinned) {
+      pinned.classList.add("selected");
+    }
+
+    dim(pinned ? chainOf(pinned) : null);
+  }
+
+  // Jump to the phases and groups in the index, clear of the header and title column kept in view
+  var header = document.querySelector('#header-clip rect');
+  var titles = document.querySelector('#titles-clip rect');
+
+  function jump(link) {
+    var target = document.getElementById(link.hash.slice(1));
+
+    if (!target) {
+      return false;
+    }
+
+    var box = target.getBoundingClientRect();
+    var scrollBox = scroll.getBoundingClientRect();
+
+    // Phases are found across the chart, and groups down it
+    if (target.classList.contains("phase")) {
+      scroll.scrollTo({ left: scroll.scrollLeft + box.left - scrollBox.left - titles.getAttribute("width"), behavior: "smooth" });
+    } else {
+      scroll.scrollTo({ top: scroll.scrollTop + box.top - scrollBox.top - header.getAttribute("height"), behavior: "smooth" });
+    }
+
+    return true;
+  }
+
+  document.querySelectorAll(".chart-index a").forEach(function (link) {
+    link.addEventListener("click", function (event) {
+      if (jump(link)) {
+        event.preventDefault();
+        history.replaceState(null, "", link.hash);
+      }
+    });
+
+    // Links to a section of the page go straight to it
+    if (link.hash === location.hash) {
+      jump(link);
+    }
+  });
+
+  rows.forEach(function (row) {
+    var bar = row.querySelector('rect[class^="resource-"], .milestone');
+
+    if (bar) {
+      bar.addEventListener("mouseenter", function () { highlight(row, true); });
+      bar.addEventListener("mouseleave", function () { highlight(row, false); });
+      bar.addEventListener("click", function () { pin(row); });
+    }
+  });
+})();
+"#;
+
+/// The ID of the element a section of the chart starts at, such as "group-design" for the group "Design", for linking
+/// to from the index
+pub(crate) fn anchor_id(kind: &str, name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+
+    format!(
+        "{}-{}",
+        kind,
+        slug.split('-')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    )
+}
+
+pub(crate) fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Write an HTML page containing the chart and the scripts that make it interactive
+///
+/// The chart is put in a box that scrolls, keeping its header and title column in view.  A `scroll_hint` is shown
+/// when the chart doesn't fit the window.  Above the chart is an `index` of links to its sections under each heading,
+/// given as the IDs of the elements they start at and their names.
+pub(crate) fn write_html_file(
+    mut writer: impl Write,
+    title: &str,
+    svg: &str,
+    scroll_hint: Option<&str>,
+    index: &[(&str, Vec<(String, &str)>)],
+) -> Result<(), GanttError> {
+    let index: String = index
+        .iter()
+        .filter(|(_, links)| !links.is_empty())
+        .map(|(heading, links)| {
+            format!(
+                "<p><span class=\"chart-index-heading\">{}</span>{}</p>\n",
+                escape_html(heading),
+                links
+                    .iter()
+                    .map(|(id, name)| format!("<a href=\"#{}\">{}</a>", id, escape_html(name)))
+                    .collect::<String>()
+            )
+        })
+        .collect();
+    let index = if index.is_empty() {
+        index
+    } else {
+        format!("<nav class=\"chart-index\">\n{}</nav>\n", index)
+    };
+    let hint = match scroll_hint {
+        Some(scroll_hint) => format!(
+            "<p class=\"scroll-hint\" hidden>{}</p>\n",
+            escape_html(scroll_hint)
+        ),
+        None => String::new(),
+    };
+    let chart = format!(
+        "{}{}<div class=\"chart-scroll\">\n{}\n</div>",
+        index, hint, svg
+    );
+
+    write!(
+        writer,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}\n<script>{}</script>\n</body>\n</html>\n",
+        escape_html(title),
+        HTML_STYLE,
+        chart,
+        HTML_SCRIPT
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,793 @@
+use crate::{ChartData, GanttError, ItemData, LabelData, ResourceData};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use resvg::usvg::roxmltree;
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+};
+
+fn parse_error(line: usize, message: String) -> GanttError {
+    GanttError::ParseError(format!("line {}: {}", line, message))
+}
+
+// The index of the resource with a name, adding it to the end of the resources if it's new
+fn resource_index(
+    resources: &mut Vec<ResourceData>,
+    resource_indices: &mut HashMap<String, usize>,
+    name: &str,
+) -> usize {
+    *resource_indices.entry(name.to_owned()).or_insert_with(|| {
+        resources.push(ResourceData {
+            name: name.to_owned(),
+            capacity: None,
+            absences: vec![],
+        });
+        resources.len() - 1
+    })
+}
+
+// A date (e.g. "2022-7-15") or a full date and time
+fn parse_start_date(line: usize, value: &str) -> Result<NaiveDateTime, GanttError> {
+    value
+        .parse::<NaiveDateTime>()
+        .or_else(|_| {
+            value
+                .parse::<NaiveDate>()
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map_err(|e| parse_error(line, format!("Bad start date '{}': {}", value, e)))
+}
+
+// A number of days, with or without a "d" suffix (e.g. "2.5d")
+fn parse_duration(line: usize, value: &str) -> Result<f32, GanttError> {
+    value
+        .trim_end_matches('d')
+        .parse::<f32>()
+        .map_err(|e| parse_error(line, format!("Bad duration '{}': {}", value, e)))
+}
+
+// A chart of only the imported resources and items
+fn imported_chart(title: String, resources: Vec<ResourceData>, items: Vec<ItemData>) -> ChartData {
+    ChartData {
+        title,
+        marked_date: None,
+        resources,
+        items,
+        sprints: vec![],
+        phases: vec![],
+        weekend: None,
+        holidays: vec![],
+        breaks: vec![],
+        risks: vec![],
+        sign_off: vec![],
+        labels: LabelData::default(),
+        source_hash: None,
+    }
+}
+
+/// Read a CSV task list into chart data
+///
+/// The header row names the columns, which are the same as the item fields in a chart file: `title`, `resource`,
+/// `startDate`, `duration`, `id`, `dependsOn` and `open`.  Only `title` is required.  Resources are given by name and
+/// added to the chart in the order they first appear, and `dependsOn` is a list of ids separated by semicolons.
+/// Rows without a duration are milestones.
+pub(crate) fn read_csv_file(reader: impl Read, title: String) -> Result<ChartData, GanttError> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers = csv_reader
+        .headers()
+        .map_err(|e| parse_error(1, e.to_string()))?
+        .clone();
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let title_column = column("title")
+        .ok_or_else(|| parse_error(1, "There must be a 'title' column".to_owned()))?;
+    let resource_column = column("resource");
+    let start_date_column = column("startDate");
+    let duration_column = column("duration");
+    let id_column = column("id");
+    let depends_on_column = column("dependsOn");
+    let open_column = column("open");
+    let mut resources: Vec<ResourceData> = vec![];
+    let mut resource_indices: HashMap<String, usize> = HashMap::new();
+    let mut items: Vec<ItemData> = vec![];
+
+    for (i, record) in csv_reader.records().enumerate() {
+        let line = i + 2;
+        let record = record.map_err(|e| parse_error(line, e.to_string()))?;
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|c| record.get(c))
+                .filter(|value| !value.is_empty())
+        };
+        let resource_index = field(resource_column)
+            .map(|name| resource_index(&mut resources, &mut resource_indices, name));
+        let start_date = field(start_date_column)
+            .map(|value| parse_start_date(line, value))
+            .transpose()?;
+        let duration = field(duration_column)
+            .map(|value| parse_duration(line, value))
+            .transpose()?;
+        let open = match field(open_column) {
+            Some(value) => Some(
+                value
+                    .parse::<bool>()
+                    .map_err(|e| parse_error(line, format!("Bad open flag '{}': {}", value, e)))?,
+            ),
+            None => None,
+        };
+
+        items.push(ItemData {
+            title: record.get(title_column).unwrap_or("").to_owned(),
+            id: field(id_column).map(|id| id.to_owned()),
+            depends_on: field(depends_on_column).map(|ids| {
+                ids.split(';')
+                    .map(|id| id.trim().to_owned())
+                    .filter(|id| !id.is_empty())
+                    .collect()
+            }),
+            milestone: duration.is_none(),
+            duration,
+            start_date,
+            resource_index,
+            open,
+            ..Default::default()
+        });
+    }
+
+    Ok(imported_chart(title, resources, items))
+}
+
+// Add the tasks of the outlines under a node, with the outlines that have outlines under them becoming groups
+fn read_outlines(
+    document: &roxmltree::Document,
+    node: roxmltree::Node,
+    group: Option<&str>,
+    resources: &mut Vec<ResourceData>,
+    resource_indices: &mut HashMap<String, usize>,
+    items: &mut Vec<ItemData>,
+) -> Result<(), GanttError> {
+    for outline in node.children().filter(|n| n.has_tag_name("outline")) {
+        let line = document.text_pos_at(outline.range().start).row as usize;
+        let attribute = |name: &str| outline.attribute(name).filter(|value| !value.is_empty());
+        let title = attribute("text")
+            .or_else(|| attribute("title"))
+            .unwrap_or("");
+
+        if outline.children().any(|n| n.has_tag_name("outline")) {
+            // Nested groups are named after the groups they are in, e.g. "Build / Backend"
+            let group = match group {
+                Some(group) => format!("{} / {}", group, title),
+                None => title.to_owned(),
+            };
+
+            read_outlines(
+                document,
+                outline,
+                Some(&group),
+                resources,
+                resource_indices,
+                items,
+            )?;
+            continue;
+        }
+
+        let duration = attribute("duration")
+            .map(|value| parse_duration(line, value))
+            .transpose()?;
+
+        items.push(ItemData {
+            title: title.to_owned(),
+            id: attribute("id").map(|id| id.to_owned()),
+            depends_on: attribute("dependsOn").map(|ids| {
+                ids.split([';', ','])
+                    .map(|id| id.trim().to_owned())
+                    .filter(|id| !id.is_empty())
+                    .collect()
+            }),
+            milestone: duration.is_none(),
+            duration,
+            start_date: attribute("start")
+                .map(|value| parse_start_date(line, value))
+                .transpose()?,
+            resource_index: attribute("resource")
+                .map(|name| resource_index(resources, resource_indices, name)),
+            group: group.map(|group| group.to_owned()),
+            note: attribute("_note").map(|note| note.to_owned()),
+            ..Default::default()
+        });
+    }
+
+    Ok(())
+}
+
+/// Read an OPML outline, such as one exported from a mind mapping tool, into chart data
+///
+/// Outlines with outlines under them become groups, and the rest become tasks titled with their `text`.  Tasks take
+/// their `start` date, `duration`, `resource`, `id`, `dependsOn` ids separated by semicolons or commas and `_note`
+/// from the outline's attributes, and those without a duration are milestones.  The chart title is the given title,
+/// then the title in the OPML head, then the file title.
+pub(crate) fn read_opml_file(
+    mut reader: impl Read,
+    title: Option<String>,
+    file_title: String,
+) -> Result<ChartData, GanttError> {
+    let mut text = String::new();
+
+    reader.read_to_string(&mut text)?;
+
+    let document =
+        roxmltree::Document::parse(&text).map_err(|e| GanttError::ParseError(e.to_string()))?;
+    let root = document.root_element();
+    let body = root
+        .children()
+        .find(|n| n.has_tag_name("body"))
+        .filter(|_| root.has_tag_name("opml"))
+        .ok_or_else(|| {
+            GanttError::ParseError("The file must be OPML, with a <body> of outlines".to_owned())
+        })?;
+    let head_title = root
+        .children()
+        .find(|n| n.has_tag_name("head"))
+        .and_then(|head| head.children().find(|n| n.has_tag_name("title")))
+        .and_then(|title| title.text())
+        .map(|title| title.trim().to_owned())
+        .filter(|title| !title.is_empty());
+    let mut resources: Vec<ResourceData> = vec![];
+    let mut resource_indices: HashMap<String, usize> = HashMap::new();
+    let mut items: Vec<ItemData> = vec![];
+
+    read_outlines(
+        &document,
+        body,
+        None,
+        &mut resources,
+        &mut resource_indices,
+        &mut items,
+    )?;
+
+    Ok(imported_chart(
+        title.or(head_title).unwrap_or(file_title),
+        resources,
+        items,
+    ))
+}
+
+/// Combine charts, such as one for each epic, into one chart with the items of each in a group named for it
+///
+/// The item ids of each chart are prefixed with its group name, as with [`ChartData::merge_group`].  Items can depend
+/// on the items of other charts by naming them as `Group/id`, or by their plain id if only one other chart has it.
+/// Items can only depend on items before them, so the charts are combined in an order that puts each after the
+/// charts it depends on, and otherwise in the order given.
+pub(crate) fn combine_charts(
+    title: String,
+    mut charts: Vec<(String, ChartData)>,
+) -> Result<ChartData, GanttError> {
+    let groups: Vec<String> = charts.iter().map(|(group, _)| group.clone()).collect();
+    let ids: Vec<HashSet<String>> = charts
+        .iter()
+        .map(|(_, chart)| {
+            chart
+                .items
+                .iter()
+                .filter_map(|item| item.id.clone())
+                .collect()
+        })
+        .collect();
+    // The charts each chart has items depending on
+    let mut depends_on: Vec<HashSet<usize>> = vec![HashSet::new(); charts.len()];
+
+    for (i, (group, chart)) in charts.iter_mut().enumerate() {
+        for item in &mut chart.items {
+            for id in item.depends_on.iter_mut().flatten() {
+                if ids[i].contains(id.as_str()) {
+                    continue;
+                }
+
+                let qualified = (0..groups.len()).find(|&j| {
+                    id.strip_prefix(groups[j].as_str())
+                        .and_then(|rest| rest.strip_prefix('/'))
+                        .is_some_and(|rest| ids[j].contains(rest))
+                });
+                let plain: Vec<usize> = (0..ids.len())
+                    .filter(|&j| j != i && ids[j].contains(id.as_str()))
+                    .collect();
+
+                match (qualified, plain.as_slice()) {
+                    (Some(j), _) => {
+                        depends_on[i].insert(j);
+                    }
+                    (None, &[j]) => {
+                        *id = format!("{}/{}", groups[j], id);
+                        depends_on[i].insert(j);
+                    }
+                    (None, []) => {}
+                    (None, _) => {
+                        return Err(GanttError::validation(
+                            None,
+                            "dependsOn",
+                            format!(
+                                "Item '{}' of '{}' depends on '{}', which is an id in more than one chart, so give it as one of {}",
+                                item.title,
+                                group,
+                                id,
+                                plain
+                                    .iter()
+                                    .map(|&j| format!("'{}/{}'", groups[j], id))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    // Put each chart after the charts it depends on, keeping to the given order where there is a choice
+    let mut order = vec![];
+    let mut placed = vec![false; charts.len()];
+
+    while order.len() < charts.len() {
+        match (0..charts.len()).find(|&i| !placed[i] && depends_on[i].iter().all(|&j| placed[j])) {
+            Some(i) => {
+                placed[i] = true;
+                order.push(i);
+            }
+            None => {
+                return Err(GanttError::validation(
+                    None,
+                    "dependsOn",
+                    format!(
+                    "The items of {} depend on each other, so there is no order to combine them in",
+                    (0..charts.len())
+                        .filter(|&i| !placed[i])
+                        .map(|i| format!("'{}'", groups[i]))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                ))
+            }
+        }
+    }
+
+    let mut charts: Vec<Option<(String, ChartData)>> = charts.into_iter().map(Some).collect();
+    let mut combined = imported_chart(title, vec![], vec![]);
+
+    for i in order {
+        if let Some((group, chart)) = charts[i].take() {
+            combined.merge_group(chart, &group)?;
+        }
+    }
+
+    Ok(combined)
+}
+
+/// A time tracking tool that logged time can be imported from
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum TimeTracker {
+    Toggl,
+    Harvest,
+}
+
+/// Time logged against a task
+pub(crate) struct TimeEntry {
+    // The task names the entry could be for, matched against item ids and titles
+    names: Vec<String>,
+    date: NaiveDate,
+    hours: f32,
+}
+
+// Toggl durations are given as hours, minutes and seconds, e.g. "01:30:00"
+fn parse_hms(value: &str) -> Option<f32> {
+    let mut parts = value.split(':').map(|part| part.parse::<f32>().ok());
+    let hours = parts.next()??;
+    let minutes = parts.next().unwrap_or(Some(0.0))?;
+    let seconds = parts.next().unwrap_or(Some(0.0))?;
+
+    Some(hours + minutes / 60.0 + seconds / 3600.0)
+}
+
+/// Read the time entries from a Toggl or Harvest detailed CSV export
+///
+/// Toggl entries are matched to items by their `Task` or `Description` and Harvest entries by their `Task` or `Notes`.
+pub(crate) fn read_time_entries(
+    reader: impl Read,
+    tracker: TimeTracker,
+) -> Result<Vec<TimeEntry>, GanttError> {
+    let (name_columns, date_column, hours_column) = match tracker {
+        TimeTracker::Toggl => (["Task", "Description"], "Start date", "Duration"),
+        TimeTracker::Harvest => (["Task", "Notes"], "Date", "Hours"),
+    };
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers = csv_reader
+        .headers()
+        .map_err(|e| parse_error(1, e.to_string()))?
+        .clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(name))
+            .ok_or_else(|| parse_error(1, format!("There must be a '{}' column", name)))
+    };
+    let name_columns: Vec<usize> = name_columns
+        .iter()
+        .filter_map(|&name| column(name).ok())
+        .collect();
+    let date_column = column(date_column)?;
+    let hours_column = column(hours_column)?;
+    let mut entries = vec![];
+
+    for (i, record) in csv_reader.records().enumerate() {
+        let line = i + 2;
+        let record = record.map_err(|e| parse_error(line, e.to_string()))?;
+        let date_value = record.get(date_column).unwrap_or("");
+        let hours_value = record.get(hours_column).unwrap_or("");
+        let date = date_value
+            .parse::<NaiveDate>()
+            .map_err(|e| parse_error(line, format!("Bad date '{}': {}", date_value, e)))?;
+        let hours = match tracker {
+            TimeTracker::Toggl => parse_hms(hours_value),
+            TimeTracker::Harvest => hours_value.parse::<f32>().ok(),
+        }
+        .ok_or_else(|| parse_error(line, format!("Bad time '{}'", hours_value)))?;
+
+        entries.push(TimeEntry {
+            names: name_columns
+                .iter()
+                .filter_map(|&c| record.get(c))
+                .filter(|name| !name.is_empty())
+                .map(|name| name.to_owned())
+                .collect(),
+            date,
+            hours,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Set the actual start and percent complete of each item from the time logged against it
+///
+/// The percent complete is the time logged against the item's effort, or its duration if it has no effort, at the
+/// given hours per day, up to 100.  Returns the number of entries that didn't match any item.
+pub(crate) fn apply_time_entries(
+    chart_data: &mut ChartData,
+    entries: &[TimeEntry],
+    hours_per_day: f32,
+) -> usize {
+    // The first day and total hours logged for each item
+    let mut logged: Vec<Option<(NaiveDate, f32)>> = vec![None; chart_data.items.len()];
+    let mut unmatched = 0;
+
+    for entry in entries {
+        let index = chart_data.items.iter().position(|item| {
+            entry
+                .names
+                .iter()
+                .any(|name| item.id.as_ref() == Some(name) || item.title == *name)
+        });
+
+        match index {
+            Some(index) => {
+                let (start, hours) = logged[index].get_or_insert((entry.date, 0.0));
+
+                *start = (*start).min(entry.date);
+                *hours += entry.hours;
+            }
+            None => unmatched += 1,
+        }
+    }
+
+    for (item, logged) in chart_data.items.iter_mut().zip(logged) {
+        if let Some((start, hours)) = logged {
+            item.actual_start = Some(start.and_time(NaiveTime::MIN));
+
+            if let Some(days) = item.effort.or(item.duration).filter(|&days| days > 0.0) {
+                item.percent_complete =
+                    Some((hours / hours_per_day / days * 100.0).min(100.0).round());
+            }
+        }
+    }
+
+    unmatched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn csv_rows_become_items() {
+        let csv = "\
+Title, Resource, startDate, Duration, ID, dependsOn, Open
+Design, Alex, 2024-03-04, 2d, design, , false
+Build, Sam, , 3, build, design, true
+Launch, Alex, , , , design; build ,
+";
+        let chart_data = read_csv_file(csv.as_bytes(), "Plan".to_owned()).unwrap();
+        let items = &chart_data.items;
+
+        assert_eq!(chart_data.title, "Plan");
+        assert_eq!(
+            chart_data
+                .resources
+                .iter()
+                .map(|resource| resource.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Alex", "Sam"]
+        );
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].title, "Design");
+        assert_eq!(
+            items[0].start_date,
+            Some(date(2024, 3, 4).and_time(NaiveTime::MIN))
+        );
+        assert_eq!(items[0].duration, Some(2.0));
+        assert_eq!(items[0].depends_on, None);
+        assert_eq!(items[0].open, Some(false));
+        assert_eq!(items[1].resource_index, Some(1));
+        assert_eq!(items[1].depends_on, Some(vec!["design".to_owned()]));
+        assert_eq!(items[1].open, Some(true));
+        assert!(!items[1].milestone);
+        assert_eq!(items[2].resource_index, Some(0));
+        assert_eq!(
+            items[2].depends_on,
+            Some(vec!["design".to_owned(), "build".to_owned()])
+        );
+        assert!(items[2].milestone);
+    }
+
+    #[test]
+    fn csv_errors_give_the_line() {
+        let missing_title = read_csv_file("name,duration\nDesign,2\n".as_bytes(), String::new());
+        let bad_duration = read_csv_file(
+            "title,duration\nDesign,2\nBuild,soon\n".as_bytes(),
+            String::new(),
+        );
+
+        assert!(missing_title
+            .unwrap_err()
+            .to_string()
+            .contains("'title' column"));
+        assert!(bad_duration.unwrap_err().to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn toggl_time_sets_the_actual_start_and_percent_complete() {
+        let csv = "\
+User,Description,Task,Start date,Duration
+Sam,Wireframes,design,2024-03-05,04:00:00
+Sam,,design,2024-03-04,02:00:00
+Alex,Build,,2024-03-06,01:30:00
+Alex,Lunch,,2024-03-06,01:00:00
+";
+        let entries = read_time_entries(csv.as_bytes(), TimeTracker::Toggl).unwrap();
+        let mut chart_data: ChartData = json5::from_str(
+            r#"{title: "Plan", items: [
+                {title: "Design", id: "design", startDate: "2024-03-04", duration: 2, effort: 1.5},
+                {title: "Build", duration: 4},
+                {title: "Test", duration: 1},
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(entries.len(), 4);
+        assert_eq!(apply_time_entries(&mut chart_data, &entries, 8.0), 1);
+
+        let items = &chart_data.items;
+
+        assert_eq!(
+            items[0].actual_start,
+            Some(date(2024, 3, 4).and_time(NaiveTime::MIN))
+        );
+        assert_eq!(items[0].percent_complete, Some(50.0));
+        assert_eq!(
+            items[1].actual_start,
+            Some(date(2024, 3, 6).and_time(NaiveTime::MIN))
+        );
+        assert_eq!(items[1].percent_complete, Some(5.0));
+        assert_eq!(items[2].actual_start, None);
+        assert_eq!(items[2].percent_complete, None);
+    }
+
+    #[test]
+    fn toggl_errors_give_the_line() {
+        let missing_duration = read_time_entries(
+            "Description,Start date\nDesign,2024-03-04\n".as_bytes(),
+            TimeTracker::Toggl,
+        );
+        let bad_duration = read_time_entries(
+            "Description,Start date,Duration\nDesign,2024-03-04,1:00\nBuild,2024-03-05,soon\n"
+                .as_bytes(),
+            TimeTracker::Toggl,
+        );
+
+        assert!(missing_duration
+            .err()
+            .is_some_and(|e| e.to_string().contains("'Duration' column")));
+        assert!(bad_duration
+            .err()
+            .is_some_and(|e| e.to_string().contains("line 3")));
+    }
+
+    #[test]
+    fn harvest_hours_are_decimal() {
+        let csv = "Date,Task,Notes,Hours\n2024-03-04,Design,,1.5\n";
+        let entries = read_time_entries(csv.as_bytes(), TimeTracker::Harvest).unwrap();
+
+        assert_eq!(entries[0].names, ["Design"]);
+        assert_eq!(entries[0].date, date(2024, 3, 4));
+        assert_eq!(entries[0].hours, 1.5);
+    }
+
+    fn chart(json: &str) -> ChartData {
+        json5::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn charts_are_combined_after_the_charts_they_depend_on() {
+        let charts = vec![
+            (
+                "Web".to_owned(),
+                chart(
+                    r#"{title: "Web", items: [
+                        {title: "Site", id: "site", duration: 2, dependsOn: ["api"]},
+                    ]}"#,
+                ),
+            ),
+            (
+                "Backend".to_owned(),
+                chart(
+                    r#"{title: "Backend", items: [
+                        {title: "API", id: "api", startDate: "2024-03-04", duration: 3},
+                    ]}"#,
+                ),
+            ),
+            (
+                "Mobile".to_owned(),
+                chart(
+                    r#"{title: "Mobile", items: [
+                        {title: "App", id: "app", duration: 4, dependsOn: ["Backend/api"]},
+                    ]}"#,
+                ),
+            ),
+        ];
+        let combined = combine_charts("Relaunch".to_owned(), charts).unwrap();
+        let items = &combined.items;
+
+        assert_eq!(combined.title, "Relaunch");
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| item.id.as_deref().unwrap_or_default())
+                .collect::<Vec<_>>(),
+            ["Backend/api", "Web/site", "Mobile/app"]
+        );
+        // Plain ids are qualified with the one chart that has them
+        assert_eq!(items[1].depends_on, Some(vec!["Backend/api".to_owned()]));
+        assert_eq!(items[2].depends_on, Some(vec!["Backend/api".to_owned()]));
+        assert_eq!(items[1].group.as_deref(), Some("Web"));
+    }
+
+    #[test]
+    fn ids_in_more_than_one_chart_must_be_qualified() {
+        let charts = vec![
+            (
+                "Web".to_owned(),
+                chart(r#"{title: "Web", items: [{title: "Design", id: "design", duration: 1}]}"#),
+            ),
+            (
+                "Mobile".to_owned(),
+                chart(
+                    r#"{title: "Mobile", items: [{title: "Design", id: "design", duration: 1}]}"#,
+                ),
+            ),
+            (
+                "Launch".to_owned(),
+                chart(
+                    r#"{title: "Launch", items: [
+                        {title: "Launch", duration: 1, dependsOn: ["design"]},
+                    ]}"#,
+                ),
+            ),
+        ];
+        let message = combine_charts("Relaunch".to_owned(), charts)
+            .unwrap_err()
+            .to_string();
+
+        assert!(message.contains("'Web/design', 'Mobile/design'"));
+    }
+
+    #[test]
+    fn charts_depending_on_each_other_cant_be_combined() {
+        let charts = vec![
+            (
+                "Web".to_owned(),
+                chart(
+                    r#"{title: "Web", items: [
+                        {title: "Site", id: "site", duration: 1, dependsOn: ["app"]},
+                    ]}"#,
+                ),
+            ),
+            (
+                "Mobile".to_owned(),
+                chart(
+                    r#"{title: "Mobile", items: [
+                        {title: "App", id: "app", duration: 1, dependsOn: ["site"]},
+                    ]}"#,
+                ),
+            ),
+        ];
+        let message = combine_charts("Relaunch".to_owned(), charts)
+            .unwrap_err()
+            .to_string();
+
+        assert!(message.contains("'Web', 'Mobile'"));
+    }
+
+    #[test]
+    fn opml_outlines_with_outlines_under_them_become_groups() {
+        let opml = r#"<?xml version="1.0"?>
+<opml version="2.0">
+  <head><title>Relaunch</title></head>
+  <body>
+    <outline text="Build">
+      <outline text="Backend">
+        <outline text="API" id="api" start="2024-03-04" duration="3" resource="Sam"/>
+      </outline>
+      <outline text="Frontend" duration="2d" dependsOn="api" resource="Alex" _note="Mobile first"/>
+    </outline>
+    <outline text="Launch" dependsOn="api, frontend"/>
+  </body>
+</opml>
+"#;
+        let chart_data = read_opml_file(opml.as_bytes(), None, "relaunch".to_owned()).unwrap();
+        let items = &chart_data.items;
+
+        assert_eq!(chart_data.title, "Relaunch");
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| item.title.as_str())
+                .collect::<Vec<_>>(),
+            ["API", "Frontend", "Launch"]
+        );
+        assert_eq!(items[0].group.as_deref(), Some("Build / Backend"));
+        assert_eq!(items[0].resource_index, Some(0));
+        assert_eq!(items[1].group.as_deref(), Some("Build"));
+        assert_eq!(items[1].duration, Some(2.0));
+        assert_eq!(items[1].note.as_deref(), Some("Mobile first"));
+        assert_eq!(items[2].group, None);
+        assert_eq!(
+            items[2].depends_on,
+            Some(vec!["api".to_owned(), "frontend".to_owned()])
+        );
+        assert!(items[2].milestone);
+    }
+
+    #[test]
+    fn opml_titles_fall_back_to_the_file_title() {
+        let opml = r#"<opml version="2.0"><body><outline text="A" duration="1"/></body></opml>"#;
+
+        assert_eq!(
+            read_opml_file(opml.as_bytes(), None, "plan".to_owned())
+                .unwrap()
+                .title,
+            "plan"
+        );
+        assert_eq!(
+            read_opml_file(opml.as_bytes(), Some("Given".to_owned()), "plan".to_owned())
+                .unwrap()
+                .title,
+            "Given"
+        );
+        assert!(read_opml_file("<html/>".as_bytes(), None, "plan".to_owned()).is_err());
+    }
+}
@@ -1,24 +1,195 @@
-use serde::{Deserialize, Serialize};
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct ItemData {
     pub title: String,
-    
-    pub duration: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(rename = "dependsOn", skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+
+    // Days, which can be fractional
+    #[serde(
+        default,
+        deserialize_with = "deserialize_days",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration: Option<f32>,
+
+    #[serde(
+        rename = "durationOptimistic",
+        default,
+        deserialize_with = "deserialize_days",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration_optimistic: Option<f32>,
+
+    #[serde(
+        rename = "durationPessimistic",
+        default,
+        deserialize_with = "deserialize_days",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration_pessimistic: Option<f32>,
+
+    // A diamond on the item's date, which mustn't have a duration
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub milestone: bool,
+
+    // Person-days of work, defaults to the duration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort: Option<f32>,
 
     #[serde(rename = "durationMs", skip_serializing_if = "Option::is_none")]
     pub duration_ms: Option<i64>,
 
     #[serde(rename = "startMs", skip_serializing_if = "Option::is_none")]
-    pub start_ms: Option<i64>, // For Unix timestamp in milliseconds
+    pub start_ms: Option<i64>, // For Unix timestamp in milliseconds, such as a milestone at a time of day
 
-    #[serde(rename = "startDate", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "startDate",
+        default,
+        deserialize_with = "deserialize_date_or_date_time",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub start_date: Option<NaiveDateTime>,
-    
-    
-    
-    #[serde(rename = "resource")]
+
+    #[serde(rename = "resource", skip_serializing_if = "Option::is_none")]
     pub resource_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub open: Option<bool>,
-}
\ No newline at end of file
+
+    // Extra CSS classes for the item's title and bar
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+
+    // The group the item belongs to, such as the plan it was merged from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+
+    // When work on the item really started
+    #[serde(
+        rename = "actualStart",
+        default,
+        deserialize_with = "deserialize_date_or_date_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub actual_start: Option<NaiveDateTime>,
+
+    // The last day work was really done on the item
+    #[serde(
+        rename = "actualFinish",
+        default,
+        deserialize_with = "deserialize_date_or_date_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub actual_finish: Option<NaiveDateTime>,
+
+    // From 0 to 100
+    #[serde(rename = "percentComplete", skip_serializing_if = "Option::is_none")]
+    pub percent_complete: Option<f32>,
+
+    // Days of work left at the marked date, otherwise worked out from the percent complete
+    #[serde(
+        rename = "remainingDuration",
+        default,
+        deserialize_with = "deserialize_days",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub remaining_duration: Option<f32>,
+
+    // The last day of the task, or the date of the milestone, when the plan was agreed
+    #[serde(
+        rename = "baselineFinish",
+        default,
+        deserialize_with = "deserialize_date_or_date_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub baseline_finish: Option<NaiveDateTime>,
+
+    // Such as "high" or "low", for coloring the bars by
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+
+    // The parts of a task that was paused and resumed, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<SegmentData>,
+
+    // A callout shown as a numbered footnote under the chart
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+
+    // Such as "internal", for choosing which items are shown in charts for different audiences
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    // Whose title is replaced with a placeholder when the chart is redacted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub confidential: bool,
+}
+
+impl ItemData {
+    // When the item starts, if given as a date or as a Unix timestamp in milliseconds
+    pub(crate) fn start(&self) -> Option<NaiveDateTime> {
+        self.start_date.or_else(|| {
+            self.start_ms
+                .and_then(|ms| DateTime::from_timestamp_millis(ms).map(|dt| dt.naive_utc()))
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SegmentData {
+    pub start: NaiveDate,
+    // Days, which can be fractional
+    pub duration: f32,
+}
+
+// Accept either a plain date (e.g. "2022-7-15") or a full date and time
+pub(crate) fn deserialize_date_or_date_time<'de, D>(
+    deserializer: D,
+) -> Result<Option<NaiveDateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+
+    match s {
+        Some(s) => match s.parse::<NaiveDateTime>() {
+            Ok(date_time) => Ok(Some(date_time)),
+            Err(_) => s
+                .parse::<NaiveDate>()
+                .map(|date| Some(date.and_time(NaiveTime::MIN)))
+                .map_err(serde::de::Error::custom),
+        },
+        None => Ok(None),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Days {
+    Number(f32),
+    Text(String),
+}
+
+// Accept a number of days (e.g. 2.5) or a string with a "d" suffix (e.g. "2.5d")
+pub(crate) fn deserialize_days<'de, D>(deserializer: D) -> Result<Option<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<Days>::deserialize(deserializer)? {
+        Some(Days::Number(days)) => Ok(Some(days)),
+        Some(Days::Text(s)) => s
+            .trim()
+            .trim_end_matches('d')
+            .trim_end()
+            .parse::<f32>()
+            .map(Some)
+            .map_err(|_| serde::de::Error::custom(format!("invalid number of days '{}'", s))),
+        None => Ok(None),
+    }
+}
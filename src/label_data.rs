@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+// The text shown in the chart, which can be changed for other languages or terms
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct LabelData {
+    // The heading of the title column
+    pub tasks: String,
+    // The names of the month columns, from January
+    pub months: Vec<String>,
+    // The name of quarter columns, where "{quarter}" and "{year}" are replaced
+    pub quarter: String,
+    // Shown above HTML charts that scroll sideways
+    #[serde(rename = "scrollHint")]
+    pub scroll_hint: String,
+    // The headings of the data columns
+    pub start: String,
+    pub end: String,
+    pub duration: String,
+    pub resource: String,
+    // The task states in the legend
+    pub done: String,
+    pub open: String,
+    // The task states and the items without a priority or phase when coloring by them
+    #[serde(rename = "notStarted")]
+    pub not_started: String,
+    #[serde(rename = "inProgress")]
+    pub in_progress: String,
+    pub other: String,
+    // The headings of the HTML report
+    pub milestones: String,
+    pub date: String,
+    pub status: String,
+    pub complete: String,
+    pub utilization: String,
+    // The headings of the risk table
+    pub risk: String,
+    pub owner: String,
+    pub mitigation: String,
+    // The headings of the sign-off table
+    pub name: String,
+    pub role: String,
+    pub signature: String,
+    // The titles of redacted items, where "{number}" is replaced
+    pub redacted: String,
+    // The names of the totals row
+    #[serde(rename = "activeTasks")]
+    pub active_tasks: String,
+    #[serde(rename = "personDays")]
+    pub person_days: String,
+    // The resource of the items without one
+    pub unassigned: String,
+    // The headings of the index above HTML charts
+    pub phases: String,
+    pub groups: String,
+}
+
+impl Default for LabelData {
+    fn default() -> Self {
+        LabelData {
+            tasks: "Tasks".to_owned(),
+            months: [
+                "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            quarter: "Q{quarter} {year}".to_owned(),
+            scroll_hint: "Scroll sideways to see the whole chart \u{2192}".to_owned(),
+            start: "Start".to_owned(),
+            end: "End".to_owned(),
+            duration: "Duration".to_owned(),
+            resource: "Resource".to_owned(),
+            done: "Done".to_owned(),
+            open: "Not done".to_owned(),
+            not_started: "Not started".to_owned(),
+            in_progress: "In progress".to_owned(),
+            other: "Other".to_owned(),
+            milestones: "Milestones".to_owned(),
+            date: "Date".to_owned(),
+            status: "Status".to_owned(),
+            complete: "Complete".to_owned(),
+            utilization: "Utilization".to_owned(),
+            risk: "Risk".to_owned(),
+            owner: "Owner".to_owned(),
+            mitigation: "Mitigation".to_owned(),
+            name: "Name".to_owned(),
+            role: "Role".to_owned(),
+            signature: "Signature".to_owned(),
+            redacted: "Task {number}".to_owned(),
+            active_tasks: "Active tasks".to_owned(),
+            person_days: "Person-days".to_owned(),
+            unassigned: "Unassigned".to_owned(),
+            phases: "Phases".to_owned(),
+            groups: "Groups".to_owned(),
+        }
+    }
+}
+
+impl LabelData {
+    pub(crate) fn is_default(&self) -> bool {
+        *self == LabelData::default()
+    }
+
+    pub(crate) fn quarter_name(&self, quarter: u32, year: i32) -> String {
+        self.quarter
+            .replace("{quarter}", &quarter.to_string())
+            .replace("{year}", &year.to_string())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    print, stripe_pattern, text::TextMeasure, OpenStyle, RenderData, RenderOptions, ITEM_FONT_SIZE,
+};
+use svg::{
+    node::{element, Node},
+    Document,
+};
+
+// The color of the blocks showing what done and not done tasks look like
+static STATUS_STYLE: &str = ".legend-done{fill:#666666;stroke-width:1;stroke:#666666;}";
+
+/// Render the colors of the bars and the task states as a small SVG of their own, one to a line
+pub(crate) fn render_legend(options: &RenderOptions, rd: &RenderData) -> Document {
+    let text_measure = TextMeasure::new(options.embed_font.as_deref());
+    let gutter = &rd.resource_gutter;
+    let block_width = rd.resource_height - gutter.height();
+    let line_height = block_width + 10.0;
+    let text_x = gutter.left + block_width + 10.0;
+    let entries: Vec<(&str, String)> = rd
+        .color_names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.as_ref(), format!("{}-{}-closed", rd.color_class, i)))
+        .chain([
+            (rd.labels.done.as_str(), "legend-done".to_owned()),
+            (rd.labels.open.as_str(), "legend-open".to_owned()),
+        ])
+        .collect();
+    let text_width = entries
+        .iter()
+        .map(|(name, _)| text_measure.width(name, ITEM_FONT_SIZE))
+        .fold(0.0, f32::max);
+    let width = text_x + text_width + gutter.right;
+    let height = gutter.height() + entries.len() as f32 * line_height - 10.0;
+
+    let mut styles = rd.styles.clone();
+
+    let mut definitions = element::Definitions::new();
+
+    styles.push(STATUS_STYLE.to_owned());
+    styles.push(
+        match options.open_style {
+            OpenStyle::Outline => ".legend-open{fill:none;stroke-width:2;stroke:#666666;}",
+            OpenStyle::Stripes => {
+                definitions.append(stripe_pattern("legend-stripes", "legend-stripe"));
+                ".legend-open{fill:url(#legend-stripes);stroke-width:2;stroke:#666666;}\n.legend-stripe{stroke-width:3;stroke:#666666;}"
+            }
+            OpenStyle::Faded => {
+                ".legend-open{fill:#666666;fill-opacity:0.35;stroke-width:1;stroke:#666666;}"
+            }
+        }
+        .to_owned(),
+    );
+
+    if options.print_friendly {
+        for i in 0..rd.colors.len() {
+            definitions.append(print::pattern(rd.color_class, i));
+        }
+    }
+
+    let mut legend = element::Group::new();
+
+    for (i, (name, class)) in entries.into_iter().enumerate() {
+        let y = gutter.top + i as f32 * line_height;
+
+        legend.append(
+            element::Rectangle::new()
+                .set("class", class)
+                .set("x", gutter.left)
+                .set("y", y)
+                .set("rx", rd.rect_corner_radius)
+                .set("ry", rd.rect_corner_radius)
+                .set("width", block_width)
+                .set("height", block_width),
+        );
+        legend.append(
+            element::Text::new(name)
+                .set("class", "item")
+                .set("x", text_x)
+                .set("y", y + block_width / 2.0),
+        );
+    }
+
+    Document::new()
+        .set("width", width)
+        .set("height", height)
+        .set("viewBox", (0, 0, width, height))
+        .set("xmlns", "http://www.w3.org/2000/svg")
+        .set("style", "background-color: white;")
+        .add(element::Style::new(styles.join("\n")))
+        .add(definitions)
+        .add(legend)
+}
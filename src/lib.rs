@@ -1,618 +1,6508 @@
-use chart_data::ChartData;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+pub use break_data::BreakData;
+pub use chart_data::ChartData;
 /// Generate a Gantt chart
-use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Weekday};
-use clap::Parser;
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc,
+    Weekday,
+};
+use clap::{error::ErrorKind, ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 use core::fmt::Arguments;
-use easy_error::{self, bail, ResultExt};
+pub use error::GanttError;
+use import::TimeTracker;
+pub use item_data::{ItemData, SegmentData};
+pub use label_data::LabelData;
+pub use phase_data::PhaseData;
+use publish::WebhookService;
 use rand::prelude::*;
-use serde::{Deserialize, Serialize};
+use rayon::prelude::*;
+pub use resource_data::{AbsenceData, ResourceData};
+pub use risk_data::RiskData;
+pub use scenario_data::{ScenarioData, ScenarioItemData};
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+pub use sign_off_data::SignOffData;
+pub use sprint_data::SprintData;
 use std::{
-    error::Error,
-    fs::File,
-    io::{self, Read, Write},
-    path::PathBuf,
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    env,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read, Write},
+    mem,
+    path::{Path, PathBuf},
+    time::Instant,
 };
 use svg::{
     node::{element::path::Data, Node, *},
     Document,
 };
+use svg_writer::SvgWriter;
+use text::TextMeasure;
+#[cfg(feature = "tracing")]
+pub use tracing_log::TracingLog;
+pub use verify::verify_layout;
+mod break_data;
+mod cache;
 mod chart_data;
+mod dot;
+mod eps;
+mod error;
+mod font;
+mod holidays;
+mod html;
+mod import;
 mod item_data;
+mod label_data;
+mod legend;
 mod log_macros;
+mod optimize;
+mod outline;
+mod phase_data;
+mod print;
+mod publish;
+mod raster;
+mod report;
+mod resource_data;
+mod risk_data;
+mod routing;
+mod scenario_data;
+mod serve;
+mod sign_off_data;
+mod simulate;
+mod sprint_data;
+mod svg_writer;
+mod text;
+mod tikz;
+#[cfg(feature = "tracing")]
+mod tracing_log;
+mod typst;
+mod verify;
+
+static GOLDEN_RATIO_CONJUGATE: f32 = 0.618_034;
+// The point size of the item and resource text styles
+static ITEM_FONT_SIZE: f32 = 12.0;
+static HEADING_FONT_SIZE: f32 = 16.0;
+static ASSIGNEE_FONT_SIZE: f32 = 10.0;
+static DATA_FONT_SIZE: f32 = 10.0;
+// Phases without a color of their own take these in turn
+static PHASE_COLORS: [&str; 5] = ["#e9a23b", "#4a90d9", "#5bb974", "#c5649a", "#8c7ae6"];
+static SECONDS_PER_DAY: f32 = 86400.0;
+// The colors of tasks not started, in progress and done when coloring by status
+static STATUS_COLORS: [u32; 3] = [0xb0b0b0, 0x4a90d9, 0x5bb974];
+// The neutral color of the bars of items without a resource
+static UNASSIGNED_COLOR: u32 = 0x8a9aa8;
+// The most days of slip shown as minor and moderate, with any more being major
+static SLIP_DAYS: [(f32, &str); 2] = [(5.0, "minor"), (15.0, "moderate")];
+// How far the zigzags marking left out columns are from the column line, and how far they zig
+static ZIGZAG_GAP: f32 = 3.0;
+static ZIGZAG_WIDTH: f32 = 4.0;
+// The width breaks are squeezed into
+static BREAK_WIDTH: f32 = 12.0;
+static NOTE_FONT_SIZE: f32 = 10.0;
+// The sizes of the chart title, column headings and item titles of compact charts
+static COMPACT_FONT_SIZES: (f32, f32, f32) = (12.0, 10.0, 8.0);
+static RISK_SIGN_SIZE: f32 = 12.0;
+// The shares of the chart width taken by the risk, tasks, owner and mitigation columns of the risk table
+static RISK_COLUMN_SHARES: [f32; 4] = [0.3, 0.25, 0.15, 0.3];
+// The same for the name, role, date and signature columns of the sign-off table
+static SIGN_OFF_COLUMN_SHARES: [f32; 4] = [0.25, 0.25, 0.15, 0.35];
+static NOTE_LINE_HEIGHT: f32 = 20.0;
+static HEAT_STRIP_HEIGHT: f32 = 8.0;
+static BADGE_DOT_SIZE: f32 = 8.0;
+static BADGE_FONT_SIZE: f32 = 7.0;
+static BADGE_PADDING: f32 = 3.0;
+static BADGE_GAP: f32 = 5.0;
+// The share of the chart's diagonal the watermark spans, up to the largest font size
+static WATERMARK_SHARE: f32 = 0.7;
+static WATERMARK_MAX_FONT_SIZE: f32 = 120.0;
+static PROVENANCE_NAMESPACE: &str = "https://crates.io/crates/gantt_chart";
+static EXAMPLE_CHART: &str = include_str!("example.json5");
+
+// Diagonal stripes drawn in the stroke of a CSS class, for filling open task bars
+fn stripe_pattern(id: &str, line_class: &str) -> element::Pattern {
+    element::Pattern::new()
+        .set("id", id)
+        .set("width", 6)
+        .set("height", 6)
+        .set("patternUnits", "userSpaceOnUse")
+        .set("patternTransform", "rotate(45)")
+        .add(
+            element::Line::new()
+                .set("class", line_class)
+                .set("x1", 0)
+                .set("y1", 0)
+                .set("x2", 0)
+                .set("y2", 6),
+        )
+}
+
+// The most days a duration can be, as far longer ones overflow the dates
+static MAX_DAYS: f32 = 36525.0;
+
+// A number of days, which can be fractional, as a duration
+fn days_duration(days: f32) -> Duration {
+    Duration::seconds((days.clamp(-MAX_DAYS, MAX_DAYS) * SECONDS_PER_DAY).round() as i64)
+}
+
+// A number of days as text, rounded to the nearest half day or whole week if asked, with halves rounded up and
+// any duration longer than nothing kept to at least a half day or a week
+fn days_text(days: f32, round_durations: Option<RoundDurations>, days_per_week: f32) -> String {
+    let at_least = |rounded: f32, least: f32| {
+        if days > 0.0 {
+            rounded.max(least)
+        } else {
+            rounded
+        }
+    };
+
+    match round_durations {
+        None => format!("{}d", days),
+        Some(RoundDurations::HalfDay) => format!("{}d", at_least((days * 2.0).round() / 2.0, 0.5)),
+        Some(RoundDurations::Week) => format!("{}w", at_least((days / days_per_week).round(), 1.0)),
+    }
+}
+
+// The Monday nearest a date, as the start of a calendar week
+fn nearest_week_start(date: NaiveDateTime) -> NaiveDateTime {
+    let week_start = (date.date() - Duration::days(date.weekday().num_days_from_monday() as i64))
+        .and_time(NaiveTime::MIN);
+
+    if date - week_start >= Duration::hours(7 * 12) {
+        week_start + Duration::weeks(1)
+    } else {
+        week_start
+    }
+}
+
+// Days that can't be drawn, such as negative or NaN days, as the nearest that can
+fn clamp_days(days: f32) -> f32 {
+    if days.is_nan() {
+        0.0
+    } else {
+        days.clamp(0.0, MAX_DAYS)
+    }
+}
 
-static GOLDEN_RATIO_CONJUGATE: f32 = 0.618033988749895;
-static MONTH_NAMES: [&str; 12] = [
-    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
-];
+// The most month or quarter columns before the automatic scale moves to a longer period
+static MAX_AUTO_COLUMNS: i32 = 100;
 
 #[derive(Parser)]
-#[clap(version, about, long_about = None)]
+#[clap(version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Cli {
-    /// Specify the JSON data file
-    #[arg(value_name = "INPUT_FILE")]
-    input_file: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
 
-    /// The SVG output file
-    #[arg(value_name = "OUTPUT_FILE")]
-    output_file: Option<PathBuf>,
+    #[command(flatten)]
+    render: RenderArgs,
 
-    /// The width of the item title column
+    /// Only show errors
+    #[arg(
+        long,
+        global = true,
+        default_value_t = false,
+        conflicts_with = "verbose"
+    )]
+    quiet: bool,
+
+    /// Show progress details, repeat for scheduling decisions and layout steps
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+}
+
+#[derive(Args)]
+struct LayoutArgs {
+    /// The width of the item title column, with longer titles cut short
     #[arg(value_name = "WIDTH", short, long, default_value_t = 210.0)]
     title_width: f32,
 
+    /// Narrow the item title column to fit the longest title
+    #[arg(long, default_value_t = false)]
+    fit_title_width: bool,
+
+    /// Draw small titles above the bars instead of in a title column, with smaller text and tighter spacing for narrow screens
+    #[arg(long, default_value_t = false, conflicts_with_all = ["fit_title_width", "assignee_column", "columns", "capacity", "badges"])]
+    compact: bool,
+
+    /// Show each task's status before its title
+    #[arg(value_name = "STYLE", long, value_enum)]
+    badges: Option<Badge>,
+
+    /// Show the resource of each task beside its title
+    #[arg(value_name = "STYLE", long, value_enum)]
+    assignee_column: Option<AssigneeColumn>,
+
+    /// Columns of task data to show between the titles and the chart
+    #[arg(value_name = "COLUMNS", long, value_enum, value_delimiter = ',')]
+    columns: Vec<DataColumn>,
+
+    /// Round the durations in the data columns and the report to the nearest half day or whole week, with halves rounded up
+    #[arg(value_name = "UNIT", long, value_enum)]
+    round_durations: Option<RoundDurations>,
+
     /// The maximum width of each month
     #[arg(value_name = "WIDTH", short, long, default_value_t = 80.0)]
     max_month_width: f32,
 
+    /// The minimum width of each column, with HTML output scrolling sideways if the chart gets too wide
+    #[arg(value_name = "WIDTH", long)]
+    min_month_width: Option<f32>,
+
     /// Add a resource table at the bottom of the graph
     #[arg(short, long, default_value_t = false)]
     add_resource_table: bool,
-}
 
-impl Cli {
-    fn get_output(&self) -> Result<Box<dyn Write>, Box<dyn Error>> {
-        match self.output_file {
-            Some(ref path) => File::create(path)
-                .context(format!(
-                    "Unable to create file '{}'",
-                    path.to_string_lossy()
-                ))
-                .map(|f| Box::new(f) as Box<dyn Write>)
-                .map_err(|e| Box::new(e) as Box<dyn Error>),
-            None => Ok(Box::new(io::stdout())),
-        }
-    }
+    /// The time period shown in each column
+    #[arg(value_name = "SCALE", short, long, value_enum, default_value_t = Scale::Auto)]
+    scale: Scale,
 
-    fn get_input(&self) -> Result<Box<dyn Read>, Box<dyn Error>> {
-        match self.input_file {
-            Some(ref path) => File::open(path)
-                .context(format!("Unable to open file '{}'", path.to_string_lossy()))
-                .map(|f| Box::new(f) as Box<dyn Read>)
-                .map_err(|e| Box::new(e) as Box<dyn Error>),
-            None => Ok(Box::new(io::stdin())),
-        }
-    }
-}
+    /// The angle to turn the column headings to when they are too wide for their columns
+    #[arg(value_name = "DEGREES", long, value_enum, default_value_t = HeadingAngle::Diagonal)]
+    heading_angle: HeadingAngle,
 
-pub trait GanttChartLog {
-    fn output(self: &Self, args: Arguments);
-    fn warning(self: &Self, args: Arguments);
-    fn error(self: &Self, args: Arguments);
-}
+    /// Round the starts and ends of the bars to this period, for roadmaps where the exact days are just noise
+    #[arg(value_name = "PERIOD", long, value_enum)]
+    snap: Option<Snap>,
 
-pub struct GanttChartTool<'a> {
-    log: &'a dyn GanttChartLog,
-}
+    /// Add a chart of demand against capacity for each resource under the tasks
+    #[arg(long, default_value_t = false)]
+    capacity: bool,
 
-#[derive(Debug)]
-pub struct Gutter {
-    left: f32,
-    top: f32,
-    right: f32,
-    bottom: f32,
-}
+    /// Add a table of the chart's risks under the tasks, with their owners and mitigations
+    #[arg(long, default_value_t = false)]
+    risk_table: bool,
 
-impl Gutter {
-    pub fn height(&self) -> f32 {
-        self.bottom + self.top
-    }
+    /// Add a strip under the headings shading each column by the working days of the tasks in it
+    #[arg(long, default_value_t = false)]
+    heat_strip: bool,
 
-    pub fn width(&self) -> f32 {
-        self.right + self.left
-    }
-}
+    /// Add a row under the tasks totalling each column
+    #[arg(value_name = "TOTAL", long, value_enum)]
+    totals: Option<Totals>,
 
-#[derive(Debug)]
-struct RenderData {
-    title: String,
-    gutter: Gutter,
-    row_gutter: Gutter,
-    row_height: f32,
-    resource_gutter: Gutter,
-    resource_height: f32,
-    marked_date_offset: Option<f32>,
-    title_width: f32,
-    max_month_width: f32,
-    rect_corner_radius: f32,
-    styles: Vec<String>,
-    cols: Vec<ColumnRenderData>,
-    rows: Vec<RowRenderData>,
-    resources: Vec<String>,
-}
+    /// The order of the rows, rather than the order of the items in the chart file
+    #[arg(value_name = "ORDER", long, value_enum)]
+    sort: Option<Sort>,
 
-#[derive(Debug)]
-struct RowRenderData {
-    title: String,
-    resource_index: usize,
-    offset: f32,
-    // If length not present then this is a milestone
-    length: Option<f32>,
-    open: bool,
-}
+    /// Draw arrows from each task to the tasks that depend on it, routed around the bars, with this arrowhead
+    #[arg(value_name = "HEAD", long, value_enum)]
+    arrows: Option<ArrowHead>,
 
-#[derive(Debug)]
-struct ColumnRenderData {
-    width: f32,
-    month_name: String,
-}
+    /// Emphasize the task with this ID and the tasks it depends on or that depend on it, fading out the rest
+    #[arg(value_name = "ID", long)]
+    highlight: Option<String>,
 
-impl<'a> GanttChartTool<'a> {
-    pub fn new(log: &'a dyn GanttChartLog) -> GanttChartTool {
-        GanttChartTool { log }
-    }
+    /// What the colors of the bars show
+    #[arg(value_name = "FIELD", long, value_enum, default_value_t = ColorBy::Resource)]
+    color_by: ColorBy,
 
-    pub fn run(
-        self: &mut Self,
-        args: impl IntoIterator<Item = std::ffi::OsString>,
-    ) -> Result<(), Box<dyn Error>> {
-        let cli = match Cli::try_parse_from(args) {
-            Ok(cli) => cli,
-            Err(err) => {
-                output!(self.log, "{}", err.to_string());
-                return Ok(());
-            }
-        };
+    /// How the bars of open tasks are told apart from done ones
+    #[arg(value_name = "STYLE", long, value_enum, default_value_t = OpenStyle::Outline)]
+    open_style: OpenStyle,
 
-        let chart_data = Self::read_chart_file(cli.get_input()?)?;
-        let render_data =
-            self.process_chart_data(cli.title_width, cli.max_month_width, &chart_data)?;
-        let document = self.render_chart(cli.add_resource_table, &render_data)?;
+    /// Leave out months without any tasks, such as a long pause between phases
+    #[arg(long, default_value_t = false)]
+    skip_empty_months: bool,
 
-        Self::write_svg_file(cli.get_output()?, &document)?;
-        Ok(())
-    }
+    /// A file of holiday dates shared between charts, one per line or as a JSON array
+    #[arg(value_name = "FILE", long)]
+    holiday_file: Option<PathBuf>,
 
-    fn read_chart_file(mut reader: Box<dyn Read>) -> Result<ChartData, Box<dyn Error>> {
-        let mut content = String::new();
+    /// A TrueType, OpenType or WOFF font to embed in the chart and use for its text
+    #[arg(value_name = "FILE", long)]
+    embed_font: Option<PathBuf>,
 
-        reader.read_to_string(&mut content)?;
+    /// Write SVG output without a fixed width and height, so it can be inlined into HTML or other SVG files
+    #[arg(long, default_value_t = false)]
+    fragment: bool,
 
-        let chart_data: ChartData = json5::from_str(&content)?;
+    /// How the chart is fitted to a different shape, e.g. "xMidYMid meet" or "none"
+    #[arg(value_name = "VALUE", long)]
+    preserve_aspect_ratio: Option<String>,
 
-        Ok(chart_data)
-    }
+    /// Record when the chart was generated in its metadata, which is SOURCE_DATE_EPOCH if set, so the same chart
+    /// file no longer always renders the same
+    #[arg(long, default_value_t = false)]
+    timestamp: bool,
 
-    fn write_svg_file(writer: Box<dyn Write>, document: &Document) -> Result<(), Box<dyn Error>> {
-        svg::write(writer, document)?;
+    /// Check that the chart's layout is valid, failing if it isn't
+    #[arg(long, default_value_t = false)]
+    strict: bool,
 
-        Ok(())
-    }
+    /// Shrink SVG and HTML output, rounding numbers to this many decimal places
+    #[arg(value_name = "DIGITS", long, num_args = 0..=1, default_missing_value = "2")]
+    optimize: Option<usize>,
 
-    fn hsv_to_rgb(h: f32, s: f32, v: f32) -> u32 {
-        let h_i = (h * 6.0) as usize;
-        let f = h * 6.0 - h_i as f32;
-        let p = v * (1.0 - s);
-        let q = v * (1.0 - f * s);
-        let t = v * (1.0 - (1.0 - f) * s);
+    /// Use grayscale fills, patterns for the resources and thicker gridlines, for printing and photocopying
+    #[arg(long, default_value_t = false)]
+    print_friendly: bool,
 
-        fn rgb(r: f32, g: f32, b: f32) -> u32 {
-            ((r * 256.0) as u32) << 16 | ((g * 256.0) as u32) << 8 | ((b * 256.0) as u32)
-        }
+    /// Show only the milestones, on a timeline shortened to their dates
+    #[arg(long, default_value_t = false)]
+    milestones_only: bool,
 
-        if h_i == 0 {
-            rgb(v, t, p)
-        } else if h_i == 1 {
-            rgb(q, v, p)
-        } else if h_i == 2 {
-            rgb(p, v, t)
-        } else if h_i == 3 {
-            rgb(p, q, v)
-        } else if h_i == 4 {
-            rgb(t, p, v)
-        } else {
-            rgb(v, p, q)
-        }
-    }
+    /// Collapse the tasks in each group or phase into a single summary bar
+    #[arg(value_name = "FIELD", long, value_enum)]
+    rollup: Option<Rollup>,
 
-    fn process_chart_data(
-        self: &Self,
-        title_width: f32,
-        max_month_width: f32,
-        chart_data: &ChartData,
-    ) -> Result<RenderData, Box<dyn Error>> {
-        fn num_days_in_month(year: i32, month: u32) -> u32 {
-            // the first day of the next month...
-            let (y, m) = if month == 12 {
-                (year + 1, 1)
-            } else {
-                (year, month + 1)
-            };
-            let d = NaiveDate::from_ymd(y, m, 1);
+    /// Put the bars of all the tasks of each resource on one row, for a staffing view
+    #[arg(value_name = "FIELD", long, value_enum, conflicts_with = "rollup")]
+    aggregate_by: Option<AggregateBy>,
 
-            // ...is preceded by the last day of the original month
-            d.pred().day()
-        }
+    /// Collapse the tasks of these groups into a summary bar for each, leaving the other tasks as they are
+    #[arg(value_name = "GROUPS", long, value_delimiter = ',', conflicts_with_all = ["rollup", "aggregate_by"])]
+    collapse: Vec<String>,
 
-        // Fail if only one task
-        if chart_data.items.len() < 2 {
-            bail!("You must provide more than one task");
-        }
+    /// Draw a status line down the marked date, bending to how far each task has got
+    #[arg(long, default_value_t = false)]
+    status_line: bool,
 
-        let mut start_date = NaiveDateTime::MAX;
-        let mut end_date = NaiveDateTime::MIN;
-        let mut date = NaiveDateTime::MIN;
-        let mut shadow_durations: Vec<Option<i64>> = Vec::with_capacity(chart_data.items.len());
+    /// Forecast when tasks will finish from their progress at the marked date, moving the tasks that depend on them
+    #[arg(long, default_value_t = false)]
+    forecast: bool,
 
-        // Determine the project start & end dates
-        for (i, item) in chart_data.items.iter().enumerate() {
-            if let Some(item_start_date) = item.start_date {
-                date = item_start_date;
+    /// Draw another option for the plan over it, from a file changing the duration or start date of some of its items
+    #[arg(value_name = "FILE", long)]
+    scenario: Option<PathBuf>,
 
-                if item_start_date < start_date {
-                    // Move the start if it falls on a weekend
-                    start_date = match date.weekday() {
-                        Weekday::Sat => date + Duration::days(2),
-                        Weekday::Sun => date + Duration::days(1),
-                        _ => date,
-                    };
-                }
-            } else if i == 0 {
-                return Err(From::from(format!("First item must contain a start date")));
-            }
+    /// Work out the percent complete of tasks without one from the working days before the marked date
+    #[arg(long, default_value_t = false)]
+    auto_progress: bool,
 
-            // Skip the weekends and update a shadow list of the _real_ durations
-            if let Some(item_days) = item.duration {
-                let duration = match (date + Duration::days(item_days)).weekday() {
-                    Weekday::Sat => Duration::days(item_days + 2),
-                    Weekday::Sun => Duration::days(item_days + 1),
-                    _ => Duration::days(item_days),
-                };
+    /// Text written large and faint across the chart, such as "DRAFT", so unapproved plans aren't taken as final
+    #[arg(value_name = "TEXT", long)]
+    watermark: Option<String>,
 
-                date += duration;
+    /// Show only the items with one of these tags
+    #[arg(value_name = "TAGS", long = "tag", value_delimiter = ',')]
+    tags: Vec<String>,
 
-                shadow_durations.push(Some(duration.num_days()));
-            } else {
-                shadow_durations.push(None);
-            }
+    /// Leave out the items with any of these tags
+    #[arg(value_name = "TAGS", long = "exclude-tag", value_delimiter = ',')]
+    exclude_tags: Vec<String>,
 
-            if end_date < date {
-                end_date = date;
-            }
+    /// Replace the titles of confidential items, or all of them, with placeholders for sharing the chart outside
+    #[arg(value_name = "ITEMS", long, value_enum, num_args = 0..=1, default_missing_value = "confidential")]
+    redact: Option<Redact>,
+}
 
-            if let Some(item_resource_index) = item.resource_index {
-                if item_resource_index >= chart_data.resources.len() {
-                    return Err(From::from(format!("Resource index is out of range")));
-                }
-            } else if i == 0 {
-                return Err(From::from(format!(
-                    "First item must contain a resource index"
-                )));
-            }
-        }
+#[derive(Args)]
+struct RenderArgs {
+    /// Specify the JSON data file
+    #[arg(value_name = "INPUT_FILE")]
+    input_file: Option<PathBuf>,
 
-        start_date = NaiveDate::from_ymd(start_date.year(), start_date.month(), 1);
-        end_date = NaiveDate::from_ymd(
-            end_date.year(),
-            end_date.month(),
-            num_days_in_month(end_date.year(), end_date.month()),
-        );
+    /// The SVG output file
+    #[arg(value_name = "OUTPUT_FILE")]
+    output_file: Option<PathBuf>,
 
-        // Create all the column data
-        let mut all_items_width: f32 = 0.0;
-        let mut num_item_days: u32 = 0;
-        let mut cols = vec![];
+    #[command(flatten)]
+    layout: LayoutArgs,
 
-        date = start_date;
+    /// The output format, inferred from the output file extension if not given
+    #[arg(value_name = "FORMAT", short, long, value_enum)]
+    format: Option<OutputFormat>,
 
-        while date <= end_date {
-            let item_days = num_days_in_month(date.year(), date.month());
-            let item_width = max_month_width * (item_days as f32) / 31.0;
+    /// The quality of lossy WebP and JPEG output, from 1 to 100
+    #[arg(value_name = "QUALITY", short, long, default_value_t = 90, value_parser = clap::value_parser!(u8).range(1..=100))]
+    quality: u8,
 
-            num_item_days += item_days;
-            all_items_width += item_width;
+    /// Check and lay out the chart, printing a summary instead of writing it
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
 
-            cols.push(ColumnRenderData {
-                width: item_width,
-                month_name: MONTH_NAMES[date.month() as usize - 1].to_string(),
-            });
+    /// Also write the computed layout as JSON to this file
+    #[arg(long, value_name = "FILE")]
+    emit_render_data: Option<PathBuf>,
 
-            date = NaiveDate::from_ymd(
-                date.year() + (if date.month() == 12 { 1 } else { 0 }),
-                date.month() % 12 + 1,
-                1,
-            );
-        }
+    /// Also write the resource colors and task states as an SVG legend to this file
+    #[arg(long, value_name = "FILE")]
+    legend_file: Option<PathBuf>,
 
-        date = start_date;
+    /// Also write an HTML report with the chart and tables of the tasks, resources and milestones
+    #[arg(long, value_name = "FILE")]
+    report_html: Option<PathBuf>,
 
-        let mut resource_index: usize = 0;
-        let gutter = Gutter {
-            left: 10.0,
-            top: 80.0,
-            right: 10.0,
-            bottom: 10.0,
-        };
-        let row_gutter = Gutter {
-            left: 5.0,
-            top: 5.0,
-            right: 5.0,
-            bottom: 5.0,
-        };
-        // TODO(john): The 20.0 should be configurable, and for the resource table
-        let row_height = row_gutter.height() + 20.0;
-        let resource_gutter = Gutter {
-            left: 10.0,
-            top: 10.0,
-            right: 10.0,
-            bottom: 10.0,
-        };
-        let resource_height = resource_gutter.height() + 20.0;
-        let mut rows = vec![];
+    /// Also write an outline of the groups and tasks with their dates and owners to this file, as Markdown if it ends
+    /// in .md and otherwise as plain text
+    #[arg(long, value_name = "FILE")]
+    emit_outline: Option<PathBuf>,
 
-        // Calculate the X offsets of all the bars and milestones
-        for (i, item) in chart_data.items.iter().enumerate() {
-            if let Some(item_start_date) = item.start_date {
-                date = item_start_date;
-            }
+    /// Also write the title column to this SVG file, leaving it out of the chart, so pages can scroll the chart beside
+    /// it
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["split_by", "stdout_base64"])]
+    titles_file: Option<PathBuf>,
 
-            let offset = title_width
-                + gutter.left
-                + ((date - start_date).num_days() as f32) / (num_item_days as f32)
-                    * all_items_width;
+    /// Write the task dependency network in the given format instead of the chart
+    #[arg(value_name = "FORMAT", long, value_enum)]
+    emit_deps: Option<DepsFormat>,
 
-            let mut length: Option<f32> = None;
+    /// Write a chart for each resource, named after the output file and the resource
+    #[arg(value_name = "FIELD", long, value_enum, requires = "output_file")]
+    split_by: Option<SplitBy>,
 
-            if let Some(item_days) = shadow_durations[i] {
-                // Use the shadow duration instead of the actual duration as it accounts for weekends
-                date += Duration::days(item_days);
-                length = Some((item_days as f32) / (num_item_days as f32) * all_items_width);
-            }
+    /// Write the chart to standard output encoded as base64, in the format given with --format
+    #[arg(long, default_value_t = false, conflicts_with = "output_file")]
+    stdout_base64: bool,
+}
 
-            if let Some(item_resource_index) = item.resource_index {
-                resource_index = item_resource_index;
-            }
+#[derive(Subcommand)]
+enum Command {
+    /// Render a chart, the same as giving no subcommand
+    Render(RenderArgs),
+    /// Render many chart files into a directory, each named after its chart file
+    Batch {
+        /// Chart files, directories of chart files or glob patterns
+        #[arg(value_name = "INPUT", required = true)]
+        inputs: Vec<String>,
 
-            rows.push(RowRenderData {
-                title: item.title.clone(),
-                resource_index,
-                offset,
-                length,
-                open: item.open.unwrap_or(false),
-            });
-        }
+        /// The directory to write the charts to
+        #[arg(value_name = "DIR", short, long)]
+        out_dir: PathBuf,
 
-        let marked_date_offset = if let Some(date) = chart_data.marked_date {
-            // TODO(john): Put this offset calculation in a function
-            Some(
-                title_width
-                    + gutter.left
-                    + ((date - start_date).num_days() as f32) / (num_item_days as f32)
-                        * all_items_width,
-            )
-        } else {
-            None
-        };
+        #[command(flatten)]
+        layout: LayoutArgs,
 
-        let mut styles = vec![
-            ".outer-lines{stroke-width:3;stroke:#aaaaaa;}".to_owned(),
-            ".inner-lines{stroke-width:2;stroke:#dddddd;}".to_owned(),
-            ".item{font-family:Arial;font-size:12pt;dominant-baseline:middle;}".to_owned(),
-            ".resource{font-family:Arial;font-size:12pt;text-anchor:end;dominant-baseline:middle;}".to_owned(),
-            ".title{font-family:Arial;font-size:18pt;}".to_owned(),
-            ".heading{font-family:Arial;font-size:16pt;dominant-baseline:middle;text-anchor:middle;}".to_owned(),
-            ".task-heading{dominant-baseline:middle;text-anchor:start;}".to_owned(),
-            ".milestone{fill:black;stroke-width:1;stroke:black;}".to_owned(),
-            ".marker{stroke-width:2;stroke:#888888;stroke-dasharray:7;}".to_owned(),
-        ];
+        /// The output format
+        #[arg(value_name = "FORMAT", short, long, value_enum, default_value_t = OutputFormat::Svg)]
+        format: OutputFormat,
 
-        // Generate random resource colors based on https://martin.ankerl.com/2009/12/09/how-to-create-random-colors-programmatically/
-        let mut rng = rand::thread_rng();
-        let mut h: f32 = rng.gen();
+        /// The quality of lossy WebP and JPEG output, from 1 to 100
+        #[arg(value_name = "QUALITY", short, long, default_value_t = 90, value_parser = clap::value_parser!(u8).range(1..=100))]
+        quality: u8,
 
-        for i in 0..chart_data.resources.len() {
-            let rgb = GanttChartTool::hsv_to_rgb(h, 0.5, 0.5);
+        /// Render every chart, even those that haven't changed since they were last rendered into the directory
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
+    /// Check that a chart file is valid and can be laid out
+    Validate {
+        /// Specify the JSON data file
+        #[arg(value_name = "INPUT_FILE")]
+        input_file: Option<PathBuf>,
 
-            styles.push(format!(
-                ".resource-{}-closed{{fill:#{1:06x};stroke-width:1;stroke:#{1:06x};}}",
-                i, rgb,
-            ));
-            styles.push(format!(
-                ".resource-{}-open{{fill:none;stroke-width:2;stroke:#{1:06x};}}",
-                i, rgb,
-            ));
+        #[command(flatten)]
+        layout: LayoutArgs,
+    },
+    /// Rewrite a JSON5 chart file as plain JSON
+    Convert {
+        /// Specify the JSON data file
+        #[arg(value_name = "INPUT_FILE")]
+        input_file: Option<PathBuf>,
 
-            h = (h + GOLDEN_RATIO_CONJUGATE) % 1.0;
-        }
+        /// The JSON output file
+        #[arg(value_name = "OUTPUT_FILE")]
+        output_file: Option<PathBuf>,
+    },
+    /// Create a chart file from a task list in another format
+    Import {
+        /// The file to import, or the directory with `--from dir`
+        #[arg(value_name = "INPUT_FILE")]
+        input_file: Option<PathBuf>,
 
-        Ok(RenderData {
-            title: chart_data.title.to_owned(),
-            gutter,
-            row_gutter,
-            row_height,
-            resource_gutter,
-            resource_height,
-            styles,
-            title_width,
-            max_month_width,
-            marked_date_offset,
-            rect_corner_radius: 3.0,
-            cols,
-            rows,
-            resources: chart_data.resources.clone(),
-        })
-    }
+        /// The JSON output file
+        #[arg(value_name = "OUTPUT_FILE")]
+        output_file: Option<PathBuf>,
 
-    fn render_chart(
-        &self,
-        add_resource_table: bool,
-        rd: &RenderData,
-    ) -> Result<Document, Box<dyn Error>> {
-        let width: f32 = rd.gutter.left
-            + rd.title_width
-            + rd.cols.iter().map(|col| col.width).sum::<f32>()
-            + rd.gutter.right;
-        let height = rd.gutter.top
-            + (rd.rows.len() as f32 * rd.row_height)
-            + (if add_resource_table {
-                rd.resource_gutter.height() + rd.resource_height
-            } else {
-                0.0
-            })
-            + rd.gutter.bottom;
+        /// The format of the imported file
+        #[arg(value_name = "FORMAT", long, value_enum, default_value_t = ImportFormat::Csv)]
+        from: ImportFormat,
 
-        let mut document = Document::new()
-            .set("viewbox", (0, 0, width, height))
-            .set("xmlns", "http://www.w3.org/2000/svg")
-            .set("width", width)
-            .set("height", height)
-            .set("style", "background-color: white;");
-        let style = element::Style::new(rd.styles.join("\n"));
+        /// The chart title, defaulting to the input file name
+        #[arg(long)]
+        title: Option<String>,
 
-        // Render all the chart rows
-        let mut rows = element::Group::new();
+        /// The chart to add the logged time of a Toggl or Harvest export to
+        #[arg(value_name = "FILE", long)]
+        chart: Option<PathBuf>,
 
-        for i in 0..=rd.rows.len() {
-            let y = rd.gutter.top + (i as f32 * rd.row_height);
+        /// The working hours in a day of effort, for working out the percent complete from logged time
+        #[arg(value_name = "HOURS", long, default_value_t = 8.0)]
+        hours_per_day: f32,
+    },
+    /// Serve a chart as an interactive HTML page, rendered afresh for each request
+    Serve {
+        /// Specify the JSON data file
+        #[arg(value_name = "INPUT_FILE")]
+        input_file: PathBuf,
 
-            rows.append(if i == 0 || i == rd.rows.len() {
-                element::Line::new()
-                    .set("class", "outer-lines")
-                    .set("x1", rd.gutter.left)
-                    .set("y1", y)
-                    .set("x2", width - rd.gutter.right)
-                    .set("y2", y)
-            } else {
-                element::Line::new()
-                    .set("class", "inner-lines")
+        #[command(flatten)]
+        layout: LayoutArgs,
+
+        /// The local port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Serve a chart as an HTML page that reloads itself whenever the chart file is saved
+    Preview {
+        /// Specify the JSON data file
+        #[arg(value_name = "INPUT_FILE")]
+        input_file: PathBuf,
+
+        #[command(flatten)]
+        layout: LayoutArgs,
+
+        /// The local port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Render a chart as a PNG and post it to a Slack or Microsoft Teams webhook, for status updates
+    Publish {
+        /// Specify the JSON data file
+        #[arg(value_name = "INPUT_FILE")]
+        input_file: PathBuf,
+
+        #[command(flatten)]
+        layout: LayoutArgs,
+
+        /// The webhook URL to post to, which is a secret so is best given in the environment rather than on the command
+        /// line
+        #[arg(
+            value_name = "URL",
+            long,
+            env = "GANTT_CHART_WEBHOOK",
+            hide_env_values = true
+        )]
+        webhook: String,
+
+        /// The service the webhook is for, worked out from the URL if not given
+        #[arg(value_name = "SERVICE", long, value_enum)]
+        service: Option<WebhookService>,
+
+        /// Where the chart is published, to show it from instead of sending it, which Slack needs, as do Teams
+        /// webhooks for charts too big to send in a message
+        #[arg(value_name = "URL", long)]
+        image_url: Option<String>,
+    },
+    /// Delay tasks to see which milestones move and by how much, drawing the delayed plan if given an output file
+    Simulate {
+        /// Specify the JSON data file
+        #[arg(value_name = "INPUT_FILE")]
+        input_file: Option<PathBuf>,
+
+        /// The chart of the delayed plan, with arrows from where the items that moved finished before
+        #[arg(value_name = "OUTPUT_FILE")]
+        output_file: Option<PathBuf>,
+
+        /// A task's ID or title and the days it's late by, such as "design:+5d", which can be given more than once
+        #[arg(value_name = "TASK:DAYS", long = "delay", required = true, value_parser = simulate::parse_delay)]
+        delays: Vec<simulate::Delay>,
+
+        #[command(flatten)]
+        layout: LayoutArgs,
+
+        /// The output format, inferred from the output file extension if not given
+        #[arg(value_name = "FORMAT", short, long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+    /// Write an example chart file to start from
+    Example {
+        /// The JSON5 output file
+        #[arg(value_name = "OUTPUT_FILE")]
+        output_file: Option<PathBuf>,
+    },
+    /// Write a shell completion script to standard output
+    Completions {
+        /// The shell to generate the script for
+        shell: Shell,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum ImportFormat {
+    /// A CSV task list with a header row naming the columns
+    Csv,
+    /// A Toggl detailed time report CSV, setting the actual start and percent complete of a chart's tasks
+    Toggl,
+    /// A Harvest detailed time report CSV, setting the actual start and percent complete of a chart's tasks
+    Harvest,
+    /// A directory of chart files, such as one for each epic, combined with the items of each in a group
+    Dir,
+    /// An OPML outline from a mind mapping tool, with the outlines that have others under them as groups
+    Opml,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Scale {
+    /// One column per month, or per quarter or year if there would be too many months
+    Auto,
+    /// One column per month
+    Month,
+    /// One column per calendar quarter
+    Quarter,
+    /// One column per calendar year
+    Year,
+    /// One column per sprint, sized by the sprint length
+    Sprint,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum HeadingAngle {
+    /// Reading diagonally up to the right
+    #[value(name = "45")]
+    Diagonal,
+    /// Reading straight up
+    #[value(name = "90")]
+    Vertical,
+}
+
+impl HeadingAngle {
+    fn degrees(self) -> f32 {
+        match self {
+            HeadingAngle::Diagonal => 45.0,
+            HeadingAngle::Vertical => 90.0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum AssigneeColumn {
+    /// The resource name
+    Name,
+    /// The initials of the resource name
+    Initials,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DataColumn {
+    /// The first day of the task
+    Start,
+    /// The last day of the task
+    End,
+    /// The duration of the task in days
+    Duration,
+    /// The name of the task's resource
+    Resource,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RoundDurations {
+    /// The nearest half day
+    HalfDay,
+    /// The nearest whole week of working days, or of calendar days for the days summary rows span
+    Week,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ColorBy {
+    /// Each resource has its own color
+    Resource,
+    /// Tasks are colored by whether they are done, in progress or not started
+    Status,
+    /// Each `priority` has its own color
+    Priority,
+    /// Tasks have the color of the phase they start in
+    Phase,
+}
+
+impl ColorBy {
+    // The start of the CSS classes of the bars
+    fn class(self) -> &'static str {
+        match self {
+            ColorBy::Resource => "resource",
+            ColorBy::Status => "status",
+            ColorBy::Priority => "priority",
+            ColorBy::Phase => "phase-color",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OpenStyle {
+    /// An outline of the bar
+    Outline,
+    /// Diagonal stripes in the bar's color
+    Stripes,
+    /// A paler bar
+    Faded,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Rollup {
+    /// One summary bar for the tasks in each `group`
+    Group,
+    /// One summary bar for the tasks starting in each phase
+    Phase,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Badge {
+    /// A dot colored by whether the task is done, in progress or not started
+    Dot,
+    /// The percent complete on a chip colored in the same way
+    Percent,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ArrowHead {
+    /// A filled triangle
+    Triangle,
+    /// An open chevron
+    Open,
+    /// A round dot
+    Dot,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Sort {
+    /// Each task after the tasks it depends on, keeping chains of dependent tasks together
+    Topological,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Snap {
+    /// The nearest Monday, with each task at least a week long
+    Week,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Totals {
+    /// The number of tasks on at some time in each column
+    Tasks,
+    /// The person-days of work planned in each column
+    Effort,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum AggregateBy {
+    /// One row for each resource with the bars of all their tasks
+    Resource,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Redact {
+    /// Only the items marked `confidential`
+    Confidential,
+    /// Every item
+    All,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum SplitBy {
+    /// One chart for each resource, with only their tasks
+    Resource,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum DepsFormat {
+    /// A Graphviz DOT digraph
+    Dot,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// A static SVG image
+    Svg,
+    /// An interactive HTML page wrapping the SVG
+    Html,
+    /// A PNG bitmap
+    Png,
+    /// A WebP bitmap
+    Webp,
+    /// A JPEG bitmap
+    Jpeg,
+    /// Encapsulated PostScript
+    Eps,
+    /// LaTeX TikZ code for the pgfgantt package
+    Tikz,
+    /// Typst drawing code
+    Typst,
+}
+
+/// Options controlling how a chart is laid out and rendered
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    /// The width of the item title column
+    pub title_width: f32,
+    /// Narrow the title column to fit the longest title, up to the title width
+    pub fit_title_width: bool,
+    /// Draw the titles above the bars with smaller text and tighter spacing, leaving out the title column and the
+    /// assignee and data columns beside it
+    pub compact: bool,
+    /// Show a badge with the status of each task before its title, except in compact charts
+    pub badges: Option<Badge>,
+    /// Show the resource of each task in a column beside its title
+    pub assignee_column: Option<AssigneeColumn>,
+    /// Columns of task data between the titles and the chart
+    pub columns: Vec<DataColumn>,
+    /// What the durations in the data columns and the report are rounded to, if anything
+    pub round_durations: Option<RoundDurations>,
+    /// The maximum width of each month
+    pub max_month_width: f32,
+    /// The narrowest a column can be, if columns shouldn't shrink with their length
+    pub min_month_width: Option<f32>,
+    /// Add a resource table at the bottom of the chart
+    pub add_resource_table: bool,
+    /// The time period shown in each column
+    pub scale: Scale,
+    /// The angle the column headings are turned to when they are too wide for their columns
+    pub heading_angle: HeadingAngle,
+    /// The period the starts and ends of the bars are rounded to, if any
+    pub snap: Option<Snap>,
+    /// Add a chart of demand against capacity for each resource
+    pub show_capacity: bool,
+    /// Add a table of the risks under the chart
+    pub risk_table: bool,
+    /// Shade a strip under the headings by how many working days of tasks there are in each column
+    pub heat_strip: bool,
+    /// Add a row under the tasks with a total for each column
+    pub totals: Option<Totals>,
+    /// How the rows are ordered, if not in the order of the items
+    pub sort: Option<Sort>,
+    /// Draw dependency arrows with this arrowhead
+    pub arrows: Option<ArrowHead>,
+    /// The ID of the task to highlight along with its dependency chain
+    pub highlight: Option<String>,
+    /// What the colors of the bars show
+    pub color_by: ColorBy,
+    /// How the bars of open tasks are drawn
+    pub open_style: OpenStyle,
+    /// Leave out the columns no task or milestone falls in, marking the gaps on the axis
+    pub skip_empty_months: bool,
+    /// The output format
+    pub format: OutputFormat,
+    /// The quality of lossy WebP and JPEG output, from 1 to 100
+    pub quality: u8,
+    /// The contents of a font file to embed in the chart and use for its text
+    pub embed_font: Option<Vec<u8>>,
+    /// Leave out the width and height of SVG output so it takes the size of where it is placed
+    pub fragment: bool,
+    /// Only show the tasks of the resource with this index
+    pub only_resource: Option<usize>,
+    /// Check the layout with [`verify_layout`], failing if it is invalid
+    pub strict: bool,
+    /// Merge identical styles, drop empty groups and round numbers to this many decimal places in SVG and HTML output
+    pub optimize: Option<usize>,
+    /// The SVG `preserveAspectRatio` of the chart
+    pub preserve_aspect_ratio: Option<String>,
+    /// Record when the chart was generated in its SVG metadata
+    pub timestamp: bool,
+    /// Use grayscale fills, patterns for the resources and thicker gridlines, whatever the resource colors
+    pub print_friendly: bool,
+    /// Only show the milestones, with the columns shortened to their dates
+    pub milestones_only: bool,
+    /// Collapse the tasks in each group or phase into a summary bar spanning them
+    pub rollup: Option<Rollup>,
+    /// Put the bars of the tasks of each resource on one row, ignored when rolling up or collapsing groups
+    pub aggregate_by: Option<AggregateBy>,
+    /// Collapse the tasks of these groups into a summary bar for each, ignored when rolling up
+    pub collapse: Vec<String>,
+    /// Draw a status line down the marked date that bends through each bar to its percent complete
+    pub status_line: bool,
+    /// Forecast the finish of tasks in progress at the marked date and of the tasks that depend on them
+    pub forecast: bool,
+    /// Another option for the plan, with its bars drawn over the plan's where they differ
+    pub scenario: Option<ScenarioData>,
+    /// Take tasks without a percent complete to be on plan at the marked date
+    pub auto_progress: bool,
+    /// Text written large, rotated and faint across the chart
+    pub watermark: Option<String>,
+    /// Only show the items with one of these tags, if any are given
+    pub tags: Vec<String>,
+    /// Leave out the items with any of these tags
+    pub exclude_tags: Vec<String>,
+    /// Replace the titles and notes of these items with numbered placeholders
+    pub redact: Option<Redact>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            title_width: 210.0,
+            fit_title_width: false,
+            compact: false,
+            badges: None,
+            assignee_column: None,
+            columns: vec![],
+            round_durations: None,
+            max_month_width: 80.0,
+            min_month_width: None,
+            add_resource_table: false,
+            scale: Scale::Auto,
+            heading_angle: HeadingAngle::Diagonal,
+            snap: None,
+            show_capacity: false,
+            risk_table: false,
+            heat_strip: false,
+            totals: None,
+            sort: None,
+            arrows: None,
+            highlight: None,
+            color_by: ColorBy::Resource,
+            open_style: OpenStyle::Outline,
+            skip_empty_months: false,
+            format: OutputFormat::Svg,
+            quality: 90,
+            embed_font: None,
+            fragment: false,
+            only_resource: None,
+            strict: false,
+            optimize: None,
+            preserve_aspect_ratio: None,
+            timestamp: false,
+            print_friendly: false,
+            milestones_only: false,
+            rollup: None,
+            aggregate_by: None,
+            collapse: vec![],
+            status_line: false,
+            forecast: false,
+            scenario: None,
+            auto_progress: false,
+            watermark: None,
+            tags: vec![],
+            exclude_tags: vec![],
+            redact: None,
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Whether the output is binary rather than text
+    pub fn is_binary(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Png | OutputFormat::Webp | OutputFormat::Jpeg
+        )
+    }
+
+    /// The usual file extension for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Svg => "svg",
+            OutputFormat::Html => "html",
+            OutputFormat::Png => "png",
+            OutputFormat::Webp => "webp",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Eps => "eps",
+            OutputFormat::Tikz => "tex",
+            OutputFormat::Typst => "typ",
+        }
+    }
+
+    // The format with the output file's extension, or SVG
+    fn from_path(path: Option<&PathBuf>) -> OutputFormat {
+        match path
+            .and_then(|path| path.extension())
+            .and_then(|ext| ext.to_str())
+        {
+            Some(ext) => match ext.to_ascii_lowercase().as_str() {
+                "html" | "htm" => OutputFormat::Html,
+                "png" => OutputFormat::Png,
+                "webp" => OutputFormat::Webp,
+                "jpg" | "jpeg" => OutputFormat::Jpeg,
+                "eps" | "ps" => OutputFormat::Eps,
+                "tex" => OutputFormat::Tikz,
+                "typ" => OutputFormat::Typst,
+                _ => OutputFormat::Svg,
+            },
+            None => OutputFormat::Svg,
+        }
+    }
+}
+
+impl LayoutArgs {
+    fn read_holidays(&self) -> Result<Vec<NaiveDate>, GanttError> {
+        match self.holiday_file {
+            Some(ref path) => holidays::read_holiday_file(open_input(Some(path))?),
+            None => Ok(vec![]),
+        }
+    }
+
+    fn get_render_options(
+        &self,
+        format: OutputFormat,
+        quality: u8,
+    ) -> Result<RenderOptions, GanttError> {
+        let embed_font = match self.embed_font {
+            Some(ref path) => Some(fs::read(path)?),
+            None => None,
+        };
+        let scenario = match self.scenario {
+            Some(ref path) => {
+                let mut content = String::new();
+
+                open_input(Some(path))?.read_to_string(&mut content)?;
+                Some(json5::from_str(&content)?)
+            }
+            None => None,
+        };
+
+        Ok(RenderOptions {
+            title_width: self.title_width,
+            fit_title_width: self.fit_title_width,
+            compact: self.compact,
+            badges: self.badges,
+            assignee_column: self.assignee_column,
+            columns: self.columns.clone(),
+            round_durations: self.round_durations,
+            max_month_width: self.max_month_width,
+            min_month_width: self.min_month_width,
+            add_resource_table: self.add_resource_table,
+            scale: self.scale,
+            heading_angle: self.heading_angle,
+            snap: self.snap,
+            show_capacity: self.capacity,
+            risk_table: self.risk_table,
+            heat_strip: self.heat_strip,
+            totals: self.totals,
+            sort: self.sort,
+            arrows: self.arrows,
+            highlight: self.highlight.clone(),
+            color_by: self.color_by,
+            open_style: self.open_style,
+            skip_empty_months: self.skip_empty_months,
+            format,
+            quality,
+            embed_font,
+            fragment: self.fragment,
+            only_resource: None,
+            strict: self.strict,
+            optimize: self.optimize,
+            preserve_aspect_ratio: self.preserve_aspect_ratio.clone(),
+            timestamp: self.timestamp,
+            print_friendly: self.print_friendly,
+            milestones_only: self.milestones_only,
+            rollup: self.rollup,
+            aggregate_by: self.aggregate_by,
+            collapse: self.collapse.clone(),
+            status_line: self.status_line,
+            forecast: self.forecast,
+            scenario,
+            auto_progress: self.auto_progress,
+            watermark: self.watermark.clone(),
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            redact: self.redact,
+        })
+    }
+}
+
+impl RenderArgs {
+    fn get_render_options(&self) -> Result<RenderOptions, GanttError> {
+        self.layout
+            .get_render_options(self.get_format(), self.quality)
+    }
+
+    fn get_format(&self) -> OutputFormat {
+        self.format
+            .unwrap_or_else(|| OutputFormat::from_path(self.output_file.as_ref()))
+    }
+}
+
+fn create_output(path: Option<&PathBuf>) -> Result<Box<dyn Write>, GanttError> {
+    match path {
+        Some(path) => File::create(path)
+            .map(|f| Box::new(f) as Box<dyn Write>)
+            .map_err(|e| {
+                GanttError::IoError(io::Error::new(
+                    e.kind(),
+                    format!("Unable to create file '{}': {}", path.to_string_lossy(), e),
+                ))
+            }),
+        None => Ok(Box::new(io::stdout())),
+    }
+}
+
+fn open_input(path: Option<&PathBuf>) -> Result<Box<dyn Read>, GanttError> {
+    match path {
+        Some(path) => File::open(path)
+            .map(|f| Box::new(f) as Box<dyn Read>)
+            .map_err(|e| {
+                GanttError::IoError(io::Error::new(
+                    e.kind(),
+                    format!("Unable to open file '{}': {}", path.to_string_lossy(), e),
+                ))
+            }),
+        None => Ok(Box::new(io::stdin())),
+    }
+}
+
+// Expand the inputs to a batch into a list of chart files
+fn find_chart_files(inputs: &[String]) -> Result<Vec<PathBuf>, GanttError> {
+    let mut files = vec![];
+
+    for input in inputs {
+        let path = PathBuf::from(input);
+
+        if path.is_dir() {
+            let mut dir_files: Vec<PathBuf> = fs::read_dir(&path)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.is_file()
+                        && matches!(
+                            path.extension().and_then(|ext| ext.to_str()),
+                            Some("json5" | "json" | "jsonl")
+                        )
+                })
+                .collect();
+
+            dir_files.sort();
+            files.append(&mut dir_files);
+        } else if path.exists() {
+            files.push(path);
+        } else {
+            let paths = glob::glob(input)
+                .map_err(|e| GanttError::UsageError(format!("Bad pattern '{}': {}", input, e)))?;
+            let mut glob_files: Vec<PathBuf> = paths.filter_map(|path| path.ok()).collect();
+
+            if glob_files.is_empty() {
+                return Err(GanttError::UsageError(format!(
+                    "No chart files match '{}'",
+                    input
+                )));
+            }
+
+            files.append(&mut glob_files);
+        }
+    }
+
+    // A file given more than once, such as by a directory and a pattern, is only rendered once
+    let mut seen = HashSet::new();
+
+    files.retain(|file| seen.insert(file.clone()));
+
+    Ok(files)
+}
+
+// Name the output file of each chart of a batch after its chart file, which must be different for each chart as they
+// are all written at once
+fn batch_output_files(
+    input_files: &[PathBuf],
+    out_dir: &Path,
+    format: OutputFormat,
+) -> Result<Vec<PathBuf>, GanttError> {
+    let mut inputs_by_output: HashMap<PathBuf, &PathBuf> = HashMap::new();
+
+    input_files
+        .iter()
+        .map(|input_file| {
+            let output_file = out_dir
+                .join(input_file.file_stem().unwrap_or_default())
+                .with_extension(format.extension());
+
+            match inputs_by_output.insert(output_file.clone(), input_file) {
+                Some(other_file) => Err(GanttError::UsageError(format!(
+                    "{} and {} would both be rendered to {}, so give them different names or render them in separate batches",
+                    describe_path(Some(other_file), ""),
+                    describe_path(Some(input_file), ""),
+                    describe_path(Some(&output_file), "")
+                ))),
+                None => Ok(output_file),
+            }
+        })
+        .collect()
+}
+
+// A quoted path for messages, or what is used in its place
+fn describe_path(path: Option<&PathBuf>, default: &str) -> String {
+    path.map_or(default.to_owned(), |path| {
+        format!("'{}'", path.to_string_lossy())
+    })
+}
+
+/// The hash Git gives the contents of a chart file, for setting [`ChartData::source_hash`] of charts read elsewhere
+///
+/// Rendered charts carry this in their metadata, so `git log --find-object=HASH` finds the commits that had the chart
+/// file they were made from.
+pub fn source_hash(content: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+
+    hasher.update(format!("blob {}\0", content.len()));
+    hasher.update(content);
+
+    sha1_hex(hasher)
+}
+
+// The Git object hash of content read a piece at a time
+//
+// The hash starts with the length of the content, so if it isn't known up front the content is kept and hashed once
+// it has all been read.
+struct BlobHasher {
+    hasher: Sha1,
+    len: Option<u64>,
+    read: u64,
+    content: Vec<u8>,
+}
+
+impl BlobHasher {
+    fn new(len: Option<u64>) -> BlobHasher {
+        let mut hasher = Sha1::new();
+
+        if let Some(len) = len {
+            hasher.update(format!("blob {}\0", len));
+        }
+
+        BlobHasher {
+            hasher,
+            len,
+            read: 0,
+            content: vec![],
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.read += bytes.len() as u64;
+
+        if self.len.is_some() {
+            self.hasher.update(bytes);
+        } else {
+            self.content.extend_from_slice(bytes);
+        }
+    }
+
+    // The hash, or none if the content wasn't the length given, such as a file changed while it was read
+    fn finish(self) -> Option<String> {
+        match self.len {
+            Some(len) if len == self.read => Some(sha1_hex(self.hasher)),
+            Some(_) => None,
+            None => Some(source_hash(&self.content)),
+        }
+    }
+}
+
+// The time a chart is generated at, which is SOURCE_DATE_EPOCH if it is set for a reproducible build
+fn generated_time() -> DateTime<Utc> {
+    env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .unwrap_or_else(Utc::now)
+}
+
+// The length of an input file, for hashing it as it is read, or none for standard input
+fn input_len(path: Option<&PathBuf>) -> Option<u64> {
+    path.and_then(|path| fs::metadata(path).ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+}
+
+// The SHA-1 of everything given to the hasher, in hex
+fn sha1_hex(hasher: Sha1) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Where messages go, which may be from several threads at once when rendering in parallel
+pub trait GanttChartLog: Sync {
+    fn output(&self, args: Arguments);
+    fn warning(&self, args: Arguments);
+    fn error(&self, args: Arguments);
+    /// Progress details, only shown when asked for
+    fn info(&self, _args: Arguments) {}
+    /// Scheduling decisions, such as dates moved off weekends
+    fn debug(&self, _args: Arguments) {}
+    /// Step by step detail of the layout
+    fn trace(&self, _args: Arguments) {}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+    Debug,
+    Trace,
+}
+
+// Passes on only the messages wanted at the given verbosity
+struct FilteredLog<'a> {
+    log: &'a dyn GanttChartLog,
+    verbosity: Verbosity,
+}
+
+impl<'a> GanttChartLog for FilteredLog<'a> {
+    fn output(&self, args: Arguments) {
+        self.log.output(args);
+    }
+
+    fn warning(&self, args: Arguments) {
+        if self.verbosity >= Verbosity::Normal {
+            self.log.warning(args);
+        }
+    }
+
+    fn error(&self, args: Arguments) {
+        self.log.error(args);
+    }
+
+    fn info(&self, args: Arguments) {
+        if self.verbosity >= Verbosity::Verbose {
+            self.log.info(args);
+        }
+    }
+
+    fn debug(&self, args: Arguments) {
+        if self.verbosity >= Verbosity::Debug {
+            self.log.debug(args);
+        }
+    }
+
+    fn trace(&self, args: Arguments) {
+        if self.verbosity >= Verbosity::Trace {
+            self.log.trace(args);
+        }
+    }
+}
+
+/// What rendering a chart cost, for keeping an eye on the time taken by very large plans
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenderStats {
+    /// The number of SVG elements written, which is zero for the formats that aren't drawn from SVG
+    pub elements: usize,
+    /// The time taken to schedule and lay out the chart
+    pub layout_time: std::time::Duration,
+    /// The time taken to render and write the laid out chart
+    pub render_time: std::time::Duration,
+}
+
+pub struct GanttChartTool<'a> {
+    log: &'a dyn GanttChartLog,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Gutter {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+}
+
+impl Gutter {
+    pub fn height(&self) -> f32 {
+        self.bottom + self.top
+    }
+
+    pub fn width(&self) -> f32 {
+        self.right + self.left
+    }
+}
+
+/// A chart that has been scheduled and laid out, ready to be rendered
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderData<'a> {
+    title: Cow<'a, str>,
+    start_date: NaiveDateTime,
+    end_date: NaiveDateTime,
+    marked_date: Option<NaiveDateTime>,
+    gutter: Gutter,
+    row_gutter: Gutter,
+    row_height: f32,
+    resource_gutter: Gutter,
+    resource_height: f32,
+    // The height of the column heading row, and the angle its headings are turned to if they are
+    heading_height: f32,
+    heading_angle: Option<f32>,
+    marked_date_offset: Option<f32>,
+    title_width: f32,
+    // The room for the status badges before the titles, if they are shown
+    badge_width: f32,
+    // The resource names or initials shown beside the titles, if any
+    assignees: Vec<String>,
+    assignee_width: f32,
+    data_columns: Vec<DataColumnRenderData<'a>>,
+    rect_corner_radius: f32,
+    #[serde(skip)]
+    styles: Vec<String>,
+    #[serde(skip)]
+    labels: &'a LabelData,
+    #[serde(skip)]
+    source_hash: Option<&'a str>,
+    resource_colors: Vec<u32>,
+    // The bars are given the colors of what they are colored by, with these names
+    #[serde(skip)]
+    color_class: &'static str,
+    color_names: Vec<Cow<'a, str>>,
+    colors: Vec<u32>,
+    sprints: Vec<BandRenderData<'a>>,
+    phases: Vec<BandRenderData<'a>>,
+    cols: Vec<ColumnRenderData>,
+    rows: Vec<RowRenderData<'a>>,
+    resources: Vec<Cow<'a, str>>,
+    // The resource index of the items without a resource, which comes after the chart's resources
+    unassigned_index: usize,
+    // The X offset and width of each absence for each resource
+    absences: Vec<Vec<(f32, f32)>>,
+    capacity: Vec<CapacityRenderData>,
+    // The working days of the tasks in each column, if the heat strip is shown
+    heat: Vec<u32>,
+    // The total of each column and what it is the total of, if the totals row is shown
+    totals: Vec<f32>,
+    #[serde(skip)]
+    totals_label: &'a str,
+    // The rows of the risk table, if it is shown
+    risks: Vec<RiskRenderData<'a>>,
+    sign_off: &'a [SignOffData],
+}
+
+impl<'a> RenderData<'a> {
+    fn totals_height(&self) -> f32 {
+        if self.totals.is_empty() {
+            0.0
+        } else {
+            self.row_height
+        }
+    }
+
+    fn capacity_height(&self) -> f32 {
+        if self.capacity.is_empty() {
+            0.0
+        } else {
+            self.resource_gutter.top + (self.capacity.len() as f32) * self.row_height
+        }
+    }
+
+    // The bottom of the headings, above the heat strip if it is shown
+    fn header_bottom(&self) -> f32 {
+        if self.heat.is_empty() {
+            self.gutter.top
+        } else {
+            self.gutter.top - HEAT_STRIP_HEIGHT
+        }
+    }
+
+    // The X offset where the title text ends, and the assignee and data columns start
+    fn titles_end(&self) -> f32 {
+        self.gutter.left + self.title_width
+            - self.assignee_width
+            - self.data_columns.iter().map(|col| col.width).sum::<f32>()
+    }
+
+    fn width(&self) -> f32 {
+        self.gutter.left
+            + self.title_width
+            + self.cols.iter().map(|col| col.width).sum::<f32>()
+            + self.gutter.right
+    }
+
+    // The X offset of the left edge of each column, followed by the right edge of the last
+    fn col_offsets(&self) -> Vec<f32> {
+        let mut x = self.gutter.left + self.title_width;
+        let mut offsets = Vec::with_capacity(self.cols.len() + 1);
+
+        offsets.push(x);
+
+        for col in &self.cols {
+            x += col.width;
+            offsets.push(x);
+        }
+
+        offsets
+    }
+
+    // The IDs of the elements the phases and groups start at, for linking to from the index of an HTML chart, with none
+    // for the phases and rows that repeat a name that comes before
+    fn section_ids(&self) -> (Vec<Option<String>>, Vec<Option<String>>) {
+        let mut seen = HashSet::new();
+        let mut first_id = |kind: &str, name: &str| {
+            let id = html::anchor_id(kind, name);
+
+            if seen.insert(id.clone()) {
+                Some(id)
+            } else {
+                None
+            }
+        };
+        let phase_ids = self
+            .phases
+            .iter()
+            .map(|phase| first_id("phase", &phase.name))
+            .collect();
+        let row_ids = self
+            .rows
+            .iter()
+            .map(|row| row.group.and_then(|group| first_id("group", group)))
+            .collect();
+
+        (phase_ids, row_ids)
+    }
+
+    // The row index and text of each note, numbered in this order
+    fn notes(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, row)| row.note.map(|note| (i, note)))
+    }
+
+    // The height of a table under the chart, with a row for the headings
+    fn table_height(&self, rows: usize) -> f32 {
+        if rows == 0 {
+            0.0
+        } else {
+            self.resource_gutter.top + (rows + 1) as f32 * self.row_height
+        }
+    }
+
+    fn notes_height(&self) -> f32 {
+        match self.notes().count() {
+            0 => 0.0,
+            count => self.resource_gutter.top + count as f32 * NOTE_LINE_HEIGHT,
+        }
+    }
+
+    fn height(&self, add_resource_table: bool) -> f32 {
+        self.gutter.top
+            + (self.rows.len() as f32 * self.row_height)
+            + self.totals_height()
+            + self.capacity_height()
+            + (if add_resource_table {
+                self.resource_gutter.height() + self.resource_height
+            } else {
+                0.0
+            })
+            + self.table_height(self.risks.len())
+            + self.notes_height()
+            + self.table_height(self.sign_off.len())
+            + self.gutter.bottom
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RiskRenderData<'a> {
+    title: &'a str,
+    // The titles of the items at risk
+    tasks: String,
+    owner: &'a str,
+    mitigation: &'a str,
+}
+
+impl RiskRenderData<'_> {
+    // The text of the risk's row in the risk table
+    fn cells(&self) -> [&str; 4] {
+        [self.title, &self.tasks, self.owner, self.mitigation]
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RowRenderData<'a> {
+    title: Cow<'a, str>,
+    start_date: NaiveDateTime,
+    // The end date is exclusive and takes weekends into account
+    end_date: Option<NaiveDateTime>,
+    resource_index: usize,
+    // The index of the color of the bar
+    color_index: usize,
+    offset: f32,
+    // If length not present then this is a milestone
+    length: Option<f32>,
+    open: bool,
+    // Rows that must finish before this one can start
+    predecessors: Vec<usize>,
+    // The X offsets of the optimistic and pessimistic finish
+    estimate: Option<(f32, f32)>,
+    // Extra CSS classes for the title and bar
+    classes: &'a [String],
+    percent_complete: Option<f32>,
+    // The X offset and length of each part of a split task
+    segments: Vec<(f32, f32)>,
+    // The X offset of the baseline finish, and the days the finish has slipped since
+    baseline_offset: Option<f32>,
+    slip_days: Option<f32>,
+    // The X offset and length of when the work was really done
+    actual: Option<(f32, f32)>,
+    // The X offset and length of the forecast, if it is different from the plan
+    forecast: Option<(f32, f32)>,
+    // The X offset and length of the bar or milestone in the scenario, if it is different from the plan
+    scenario: Option<(f32, f32)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<&'a str>,
+    // One of the chart's risks is to this row
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    at_risk: bool,
+    // The segments are the bars of several tasks, which aren't joined like the parts of a split task
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    aggregated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<&'a str>,
+    // In the dependency chain of the highlighted task, or the highlighted task itself
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    highlighted: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    selected: bool,
+}
+
+impl<'a> RowRenderData<'a> {
+    // Whether the task is not started, in progress or done, as an index into the status colors, taking tasks
+    // without a percent complete to be done unless they are open
+    fn status(&self) -> usize {
+        match self.percent_complete {
+            Some(percent_complete) if percent_complete >= 100.0 => 2,
+            Some(percent_complete) if percent_complete > 0.0 => 1,
+            Some(_) => 0,
+            None if self.open => 0,
+            None => 2,
+        }
+    }
+
+    // The X offset and length of each part of the bar, of which there are several for a split task
+    fn bars(&self) -> Vec<(f32, f32)> {
+        match self.length {
+            Some(_) if !self.segments.is_empty() => self.segments.clone(),
+            Some(length) => vec![(self.offset, length)],
+            None => vec![],
+        }
+    }
+
+    // The X offset and length of the done part of each bar, filling each part of a split task in turn
+    fn progress_bars(&self) -> Vec<(f32, f32)> {
+        let bars = self.bars();
+        let mut done_length = match self.percent_complete {
+            Some(percent_complete) => {
+                bars.iter().map(|&(_, length)| length).sum::<f32>() * percent_complete / 100.0
+            }
+            None => 0.0,
+        };
+        let mut progress_bars = vec![];
+
+        for (offset, length) in bars {
+            if done_length <= 0.0 {
+                break;
+            }
+
+            progress_bars.push((offset, done_length.min(length)));
+            done_length -= length;
+        }
+
+        progress_bars
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DataColumnRenderData<'a> {
+    heading: &'a str,
+    offset: f32,
+    width: f32,
+    // The text for each row
+    cells: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BandRenderData<'a> {
+    name: Cow<'a, str>,
+    offset: f32,
+    width: f32,
+    color: Option<Cow<'a, str>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CapacityRenderData {
+    resource_index: usize,
+    // The demand and capacity in person-days for each column
+    cells: Vec<(f32, f32)>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ColumnRenderData {
+    width: f32,
+    name: String,
+    // Empty columns before this one were left out
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    gap_before: bool,
+    // The column is a break squeezed into a narrow gap
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    is_break: bool,
+}
+
+impl<'a> GanttChartTool<'a> {
+    pub fn new(log: &'a dyn GanttChartLog) -> GanttChartTool<'a> {
+        GanttChartTool { log }
+    }
+
+    pub fn run(
+        &mut self,
+        args: impl IntoIterator<Item = std::ffi::OsString>,
+    ) -> Result<(), GanttError> {
+        let cli = match Cli::try_parse_from(args) {
+            Ok(cli) => cli,
+            Err(err) => match err.kind() {
+                ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => {
+                    output!(self.log, "{}", err.to_string());
+                    return Ok(());
+                }
+                _ => return Err(GanttError::UsageError(err.to_string())),
+            },
+        };
+        let log = FilteredLog {
+            log: self.log,
+            verbosity: if cli.quiet {
+                Verbosity::Quiet
+            } else {
+                match cli.verbose {
+                    0 => Verbosity::Normal,
+                    1 => Verbosity::Verbose,
+                    2 => Verbosity::Debug,
+                    _ => Verbosity::Trace,
+                }
+            },
+        };
+        let tool = GanttChartTool::new(&log);
+
+        tool.run_cli(&cli)
+    }
+
+    fn run_cli(&self, cli: &Cli) -> Result<(), GanttError> {
+        match cli.command {
+            None => self.render_file(&cli.render),
+            Some(Command::Render(ref args)) => self.render_file(args),
+            Some(Command::Batch {
+                ref inputs,
+                ref out_dir,
+                ref layout,
+                format,
+                quality,
+                force,
+            }) => {
+                let options = layout.get_render_options(format, quality)?;
+                let holidays = layout.read_holidays()?;
+                let input_files = find_chart_files(inputs)?;
+                let output_files = batch_output_files(&input_files, out_dir, format)?;
+                let mut failed = 0;
+
+                fs::create_dir_all(out_dir)?;
+
+                let mut cache = cache::BatchCache::load(out_dir);
+                let options_hash = cache::options_hash(&options);
+
+                // Charts are independent, so render them all at once and report in order afterwards. Charts with the
+                // same key as when they were last rendered into the directory are left as they are
+                let results: Vec<_> = input_files
+                    .par_iter()
+                    .zip(output_files.par_iter())
+                    .map(|(input_file, output_file)| {
+                        self.render_batch_chart(
+                            input_file,
+                            output_file,
+                            &holidays,
+                            &options,
+                            &options_hash,
+                            (!force).then_some(&cache),
+                        )
+                    })
+                    .collect();
+
+                for ((input_file, output_file), result) in
+                    input_files.iter().zip(&output_files).zip(results)
+                {
+                    match result {
+                        Ok((key, stats)) => {
+                            match stats {
+                                Some(stats) => info!(
+                                    self.log,
+                                    "Rendered {} to {} in {:.1?}",
+                                    describe_path(Some(input_file), ""),
+                                    describe_path(Some(output_file), ""),
+                                    stats.layout_time + stats.render_time
+                                ),
+                                None => info!(
+                                    self.log,
+                                    "Left {} as {} is unchanged",
+                                    describe_path(Some(output_file), ""),
+                                    describe_path(Some(input_file), "")
+                                ),
+                            }
+
+                            cache.insert(output_file, key);
+                        }
+                        Err(e) => {
+                            error!(self.log, "{}: {}", describe_path(Some(input_file), ""), e);
+                            cache.remove(output_file);
+                            failed += 1;
+                        }
+                    }
+                }
+
+                cache.save()?;
+
+                if failed > 0 {
+                    return Err(GanttError::BatchError {
+                        failed,
+                        total: input_files.len(),
+                    });
+                }
+
+                Ok(())
+            }
+            Some(Command::Validate {
+                ref input_file,
+                ref layout,
+            }) => {
+                let chart_data = Self::read_chart(input_file.as_ref(), &layout.read_holidays()?)?;
+                let options = layout.get_render_options(OutputFormat::Svg, 90)?;
+
+                self.process_chart_data(&options, &chart_data)?;
+
+                // Most likely to be missing a dependsOn, or one too many
+                for i in Self::unconnected_items(&chart_data) {
+                    warning!(
+                        self.log,
+                        "Item '{}' depends on nothing and nothing depends on it",
+                        chart_data.items[i].title
+                    );
+                }
+
+                output!(
+                    self.log,
+                    "{} is valid",
+                    describe_path(input_file.as_ref(), "Standard input")
+                );
+
+                Ok(())
+            }
+            Some(Command::Convert {
+                ref input_file,
+                ref output_file,
+            }) => {
+                let chart_data = Self::read_chart_file(
+                    open_input(input_file.as_ref())?,
+                    input_len(input_file.as_ref()),
+                )?;
+
+                Self::write_chart_data_file(create_output(output_file.as_ref())?, &chart_data)
+            }
+            Some(Command::Import {
+                ref input_file,
+                ref output_file,
+                from: from @ (ImportFormat::Csv | ImportFormat::Opml),
+                ref title,
+                ..
+            }) => {
+                let file_title = match input_file {
+                    Some(path) => path
+                        .file_stem()
+                        .map_or("".to_owned(), |stem| stem.to_string_lossy().into_owned()),
+                    None => "".to_owned(),
+                };
+                let reader = open_input(input_file.as_ref())?;
+                // OPML files can have a title of their own
+                let chart_data = if from == ImportFormat::Opml {
+                    import::read_opml_file(reader, title.clone(), file_title)?
+                } else {
+                    import::read_csv_file(reader, title.clone().unwrap_or(file_title))?
+                };
+
+                info!(
+                    self.log,
+                    "Imported {} items for {} resources",
+                    chart_data.items.len(),
+                    chart_data.resources.len()
+                );
+
+                Self::write_chart_data_file(create_output(output_file.as_ref())?, &chart_data)
+            }
+            Some(Command::Import {
+                ref input_file,
+                ref output_file,
+                from: ImportFormat::Dir,
+                ref title,
+                ..
+            }) => {
+                let dir = match input_file {
+                    Some(dir) if dir.is_dir() => dir,
+                    _ => {
+                        return Err(GanttError::UsageError(
+                            "A directory of chart files is needed to import from".to_owned(),
+                        ))
+                    }
+                };
+                let files = find_chart_files(&[dir.to_string_lossy().into_owned()])?;
+
+                if files.is_empty() {
+                    return Err(GanttError::UsageError(format!(
+                        "There are no chart files in '{}'",
+                        dir.to_string_lossy()
+                    )));
+                }
+
+                // The items of each file are grouped under its title, or its name if it has none
+                let fragments = files
+                    .iter()
+                    .map(|file| {
+                        let fragment =
+                            Self::read_chart_file(open_input(Some(file))?, input_len(Some(file)))?;
+                        let group = if fragment.title.is_empty() {
+                            file.file_stem()
+                                .map_or("".to_owned(), |stem| stem.to_string_lossy().into_owned())
+                        } else {
+                            fragment.title.clone()
+                        };
+
+                        Ok((group, fragment))
+                    })
+                    .collect::<Result<Vec<_>, GanttError>>()?;
+                let chart_data = import::combine_charts(
+                    title.clone().unwrap_or_else(|| {
+                        dir.file_name()
+                            .map_or("".to_owned(), |name| name.to_string_lossy().into_owned())
+                    }),
+                    fragments,
+                )?;
+
+                info!(
+                    self.log,
+                    "Imported {} items for {} resources from {} files",
+                    chart_data.items.len(),
+                    chart_data.resources.len(),
+                    files.len()
+                );
+
+                Self::write_chart_data_file(create_output(output_file.as_ref())?, &chart_data)
+            }
+            Some(Command::Import {
+                ref input_file,
+                ref output_file,
+                from,
+                ref chart,
+                hours_per_day,
+                ..
+            }) => {
+                let tracker = if from == ImportFormat::Toggl {
+                    TimeTracker::Toggl
+                } else {
+                    TimeTracker::Harvest
+                };
+                let chart = chart.as_ref().ok_or_else(|| {
+                    GanttError::UsageError(format!(
+                        "--chart is needed to import {:?} time entries",
+                        tracker
+                    ))
+                })?;
+                let mut chart_data =
+                    Self::read_chart_file(open_input(Some(chart))?, input_len(Some(chart)))?;
+                let entries = import::read_time_entries(open_input(input_file.as_ref())?, tracker)?;
+                let unmatched =
+                    import::apply_time_entries(&mut chart_data, &entries, hours_per_day);
+
+                info!(
+                    self.log,
+                    "Imported {} time entries",
+                    entries.len() - unmatched
+                );
+
+                if unmatched > 0 {
+                    warning!(
+                        self.log,
+                        "{} time entries are not for any item id or title",
+                        unmatched
+                    );
+                }
+
+                Self::write_chart_data_file(create_output(output_file.as_ref())?, &chart_data)
+            }
+            Some(Command::Serve {
+                ref input_file,
+                ref layout,
+                port,
+            })
+            | Some(Command::Preview {
+                ref input_file,
+                ref layout,
+                port,
+            }) => {
+                let options = layout.get_render_options(OutputFormat::Html, 90)?;
+                let live_reload = matches!(cli.command, Some(Command::Preview { .. }))
+                    .then_some(input_file.as_path());
+
+                let options_hash = cache::options_hash(&options);
+                let last_render = cache::LastRender::default();
+
+                // The page is only rendered again when the chart file or the holidays have changed
+                serve::serve_html(self.log, port, live_reload, || {
+                    let holidays = layout.read_holidays()?;
+                    let mut content = vec![];
+
+                    open_input(Some(input_file))?.read_to_end(&mut content)?;
+
+                    last_render.get_or_render(
+                        cache::cache_key(&content, &holidays, &options_hash),
+                        || {
+                            let len = Some(content.len() as u64);
+                            let chart_data = Self::read_chart_from(
+                                Box::new(io::Cursor::new(content)),
+                                len,
+                                &holidays,
+                            )?;
+
+                            self.render_to_string(&chart_data, &options)
+                        },
+                    )
+                })
+            }
+            Some(Command::Publish {
+                ref input_file,
+                ref layout,
+                ref webhook,
+                service,
+                ref image_url,
+            }) => {
+                let chart_data = Self::read_chart(Some(input_file), &layout.read_holidays()?)?;
+                let service = service.unwrap_or_else(|| WebhookService::from_url(webhook));
+                let status_date = chart_data
+                    .marked_date
+                    .unwrap_or_else(|| Local::now().date_naive());
+                let text = format!("{} status as of {}", chart_data.title, status_date);
+                // The chart is only rendered to send it in a Teams message, as Slack can only show it from a URL
+                let image_url = match (image_url, service) {
+                    (Some(image_url), _) => Some(image_url.clone()),
+                    (None, WebhookService::Teams) => {
+                        let options = layout.get_render_options(OutputFormat::Png, 90)?;
+                        let mut png: Vec<u8> = vec![];
+
+                        self.render_to_writer(&chart_data, &options, &mut png)?;
+
+                        Some(publish::png_data_url(&png)?)
+                    }
+                    (None, WebhookService::Slack) => {
+                        warning!(
+                            self.log,
+                            "Slack webhooks can't take images, so only the message is posted without --image-url"
+                        );
+
+                        None
+                    }
+                };
+
+                publish::post_webhook(
+                    webhook,
+                    &publish::webhook_message(service, &text, image_url.as_deref()),
+                )?;
+
+                info!(self.log, "Posted the chart to the {:?} webhook", service);
+
+                Ok(())
+            }
+            Some(Command::Simulate {
+                ref input_file,
+                ref output_file,
+                ref delays,
+                ref layout,
+                format,
+            }) => {
+                let chart_data = Self::read_chart(input_file.as_ref(), &layout.read_holidays()?)?;
+                // A row for each item, to compare the schedules by
+                let schedule_options = RenderOptions::default();
+                let before = self.process_chart_data(&schedule_options, &chart_data)?;
+                let start_dates: Vec<NaiveDateTime> =
+                    before.rows.iter().map(|row| row.start_date).collect();
+                let delayed = simulate::apply_delays(&chart_data, delays, &start_dates)?;
+                // The delayed chart warns about the same things again
+                let quiet_log = FilteredLog {
+                    log: self.log,
+                    verbosity: Verbosity::Quiet,
+                };
+                let quiet_tool = GanttChartTool::new(&quiet_log);
+                let after = quiet_tool.process_chart_data(&schedule_options, &delayed)?;
+                let moves = simulate::describe_moves(&chart_data, &before, &after);
+
+                if moves.is_empty() {
+                    output!(self.log, "No milestones move");
+                }
+
+                for line in moves {
+                    output!(self.log, "{}", line);
+                }
+
+                if output_file.is_some() {
+                    let format =
+                        format.unwrap_or_else(|| OutputFormat::from_path(output_file.as_ref()));
+                    let options = layout.get_render_options(format, 90)?;
+                    let mut marked = delayed.clone();
+
+                    simulate::mark_moves(&mut marked, &before, &after);
+                    quiet_tool.render_to_writer(
+                        &marked,
+                        &options,
+                        create_output(output_file.as_ref())?,
+                    )?;
+
+                    info!(
+                        self.log,
+                        "Wrote the delayed chart to {}",
+                        describe_path(output_file.as_ref(), "")
+                    );
+                }
+
+                Ok(())
+            }
+            Some(Command::Example { ref output_file }) => {
+                create_output(output_file.as_ref())?.write_all(EXAMPLE_CHART.as_bytes())?;
+
+                info!(
+                    self.log,
+                    "Wrote an example chart to {}",
+                    describe_path(output_file.as_ref(), "standard output")
+                );
+
+                Ok(())
+            }
+            Some(Command::Completions { shell }) => {
+                clap_complete::generate(
+                    shell,
+                    &mut Cli::command(),
+                    "gantt-chart",
+                    &mut io::stdout(),
+                );
+
+                Ok(())
+            }
+        }
+    }
+
+    fn render_file(&self, args: &RenderArgs) -> Result<(), GanttError> {
+        let chart_data = Self::read_chart(args.input_file.as_ref(), &args.layout.read_holidays()?)?;
+        let options = args.get_render_options()?;
+
+        info!(
+            self.log,
+            "Read {} items from {}",
+            chart_data.items.len(),
+            describe_path(args.input_file.as_ref(), "standard input")
+        );
+
+        let started = Instant::now();
+        let render_data = self.process_chart_data(&options, &chart_data)?;
+        let layout_time = started.elapsed();
+
+        if args.dry_run {
+            output!(
+                self.log,
+                "{} rows, {} columns, {} x {}",
+                render_data.rows.len(),
+                render_data.cols.len(),
+                render_data.width(),
+                render_data.height(options.add_resource_table)
+            );
+
+            return Ok(());
+        }
+
+        if let Some(DepsFormat::Dot) = args.emit_deps {
+            return dot::write_dot_file(create_output(args.output_file.as_ref())?, &render_data);
+        }
+
+        if let Some(ref path) = args.emit_render_data {
+            Self::write_render_data_file(create_output(Some(path))?, &render_data)?;
+        }
+
+        if let Some(ref path) = args.legend_file {
+            let legend = legend::render_legend(&options, &render_data).to_string();
+            let legend = match options.optimize {
+                Some(precision) => optimize::optimize_svg(&legend, precision),
+                None => legend,
+            };
+
+            Self::write_svg_file(create_output(Some(path))?, &legend)?;
+            info!(self.log, "Wrote the legend to {}", path.display());
+        }
+
+        if let Some(ref path) = args.report_html {
+            let (svg, _) = self.render_chart(&options, &render_data)?;
+            let svg = match options.optimize {
+                Some(precision) => optimize::optimize_svg(&svg, precision),
+                None => svg,
+            };
+
+            report::write_report_html(
+                create_output(Some(path))?,
+                &chart_data,
+                &render_data,
+                &svg,
+                options.round_durations,
+            )?;
+            info!(self.log, "Wrote the report to {}", path.display());
+        }
+
+        if let Some(ref path) = args.emit_outline {
+            let markdown = path.extension().is_some_and(|ext| {
+                ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown")
+            });
+
+            outline::write_outline_file(create_output(Some(path))?, &render_data, markdown)?;
+            info!(self.log, "Wrote the outline to {}", path.display());
+        }
+
+        if let (Some(SplitBy::Resource), Some(output_file)) = (args.split_by, &args.output_file) {
+            return self.write_resource_charts(&chart_data, &options, output_file);
+        }
+
+        if let Some(ref path) = args.titles_file {
+            return self.write_split_chart(&options, &render_data, path, args.output_file.as_ref());
+        }
+
+        if args.stdout_base64 {
+            let mut buffer: Vec<u8> = vec![];
+
+            self.write_chart(&options, &render_data, &mut buffer)?;
+            writeln!(io::stdout(), "{}", STANDARD.encode(buffer))?;
+            info!(
+                self.log,
+                "Wrote {:?} output to standard output as base64", options.format
+            );
+
+            return Ok(());
+        }
+
+        let started = Instant::now();
+        let elements = self.write_chart(
+            &options,
+            &render_data,
+            create_output(args.output_file.as_ref())?,
+        )?;
+
+        info!(
+            self.log,
+            "Wrote {:?} output to {}",
+            options.format,
+            describe_path(args.output_file.as_ref(), "standard output")
+        );
+        info!(
+            self.log,
+            "Laid out the chart in {:.1?} and rendered {} elements in {:.1?}",
+            layout_time,
+            elements,
+            started.elapsed()
+        );
+
+        Ok(())
+    }
+
+    // Render a chart of a batch, unless the cache has the key of everything it is rendered from for the output file
+    // already there, returning the key and what rendering it cost
+    fn render_batch_chart(
+        &self,
+        input_file: &PathBuf,
+        output_file: &PathBuf,
+        holidays: &[NaiveDate],
+        options: &RenderOptions,
+        options_hash: &str,
+        cache: Option<&cache::BatchCache>,
+    ) -> Result<(String, Option<RenderStats>), GanttError> {
+        let mut content = vec![];
+
+        open_input(Some(input_file))?.read_to_end(&mut content)?;
+
+        let key = cache::cache_key(&content, holidays, options_hash);
+
+        if cache.is_some_and(|cache| cache.is_current(output_file, &key)) {
+            return Ok((key, None));
+        }
+
+        let len = Some(content.len() as u64);
+        let chart_data = Self::read_chart_from(Box::new(io::Cursor::new(content)), len, holidays)?;
+        let stats =
+            self.render_to_writer(&chart_data, options, create_output(Some(output_file))?)?;
+
+        Ok((key, Some(stats)))
+    }
+
+    // Write the title column and the rest of the chart as two SVGs of the same height, with the rows in the same places
+    fn write_split_chart(
+        &self,
+        options: &RenderOptions,
+        render_data: &RenderData,
+        titles_file: &PathBuf,
+        output_file: Option<&PathBuf>,
+    ) -> Result<(), GanttError> {
+        if options.format != OutputFormat::Svg {
+            return Err(GanttError::UsageError(
+                "The title column can only be written to its own file with SVG output".to_owned(),
+            ));
+        }
+
+        let titles_right = render_data.gutter.left + render_data.title_width;
+
+        self.write_svg_chart(
+            options,
+            render_data,
+            Some((0.0, titles_right)),
+            create_output(Some(titles_file))?,
+        )?;
+        self.write_svg_chart(
+            options,
+            render_data,
+            Some((titles_right, render_data.width())),
+            create_output(output_file)?,
+        )?;
+
+        info!(
+            self.log,
+            "Wrote the title column to {} and the rest of the chart to {}",
+            titles_file.display(),
+            describe_path(output_file, "standard output")
+        );
+
+        Ok(())
+    }
+
+    // Write a chart for each resource with tasks, with the resource name added to the output file name
+    fn write_resource_charts(
+        &self,
+        chart_data: &ChartData,
+        options: &RenderOptions,
+        output_file: &Path,
+    ) -> Result<(), GanttError> {
+        if chart_data.resources.is_empty() {
+            return Err(GanttError::validation(
+                None,
+                "resources",
+                "There are no resources to split the chart by".to_owned(),
+            ));
+        }
+
+        for (i, resource) in chart_data.resources.iter().enumerate() {
+            let options = RenderOptions {
+                only_resource: Some(i),
+                ..options.clone()
+            };
+            let render_data = self.process_chart_data(&options, chart_data)?;
+
+            if render_data.rows.is_empty() {
+                info!(self.log, "Skipped {} as they have no tasks", resource.name);
+                continue;
+            }
+
+            let name: String = resource
+                .name
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                .collect();
+            let path = output_file.with_file_name(format!(
+                "{}-{}.{}",
+                output_file
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+                name,
+                options.format.extension()
+            ));
+
+            self.write_chart(&options, &render_data, create_output(Some(&path))?)?;
+
+            info!(
+                self.log,
+                "Wrote {} chart to {}",
+                resource.name,
+                describe_path(Some(&path), "")
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Render a chart in the format given in the options into any writer, returning what it cost
+    pub fn render_to_writer(
+        &self,
+        chart_data: &ChartData,
+        options: &RenderOptions,
+        writer: impl Write,
+    ) -> Result<RenderStats, GanttError> {
+        let started = Instant::now();
+        let render_data = self.process_chart_data(options, chart_data)?;
+        let layout_time = started.elapsed();
+        let started = Instant::now();
+        let elements = self.write_chart(options, &render_data, writer)?;
+
+        Ok(RenderStats {
+            elements,
+            layout_time,
+            render_time: started.elapsed(),
+        })
+    }
+
+    // Write the chart in the format given in the options, returning the number of SVG elements written
+    fn write_chart(
+        &self,
+        options: &RenderOptions,
+        render_data: &RenderData,
+        writer: impl Write,
+    ) -> Result<usize, GanttError> {
+        let elements = match options.format {
+            OutputFormat::Svg => self.write_svg_chart(options, render_data, None, writer)?,
+            OutputFormat::Html => {
+                let (svg, elements) = self.render_chart(options, render_data)?;
+                let svg = match options.optimize {
+                    Some(precision) => optimize::optimize_svg(&svg, precision),
+                    None => svg,
+                };
+                let (phase_ids, row_ids) = render_data.section_ids();
+                let phase_links = phase_ids
+                    .into_iter()
+                    .zip(render_data.phases.iter())
+                    .filter_map(|(id, phase)| Some((id?, phase.name.as_ref())))
+                    .collect();
+                let group_links = row_ids
+                    .into_iter()
+                    .zip(render_data.rows.iter())
+                    .filter_map(|(id, row)| Some((id?, row.group?)))
+                    .collect();
+
+                html::write_html_file(
+                    writer,
+                    &render_data.title,
+                    &svg,
+                    options
+                        .min_month_width
+                        .map(|_| render_data.labels.scroll_hint.as_str()),
+                    &[
+                        (render_data.labels.phases.as_str(), phase_links),
+                        (render_data.labels.groups.as_str(), group_links),
+                    ],
+                )?;
+
+                elements
+            }
+            OutputFormat::Png | OutputFormat::Webp | OutputFormat::Jpeg => {
+                let (svg, elements) = self.render_chart(options, render_data)?;
+
+                raster::write_raster_file(writer, &svg, options)?;
+
+                elements
+            }
+            OutputFormat::Eps => {
+                let (svg, elements) = self.render_chart(options, render_data)?;
+
+                eps::write_eps_file(writer, &render_data.title, &svg, options)?;
+
+                elements
+            }
+            OutputFormat::Tikz => {
+                tikz::write_tikz_file(writer, render_data)?;
+
+                0
+            }
+            OutputFormat::Typst => {
+                typst::write_typst_file(writer, options.add_resource_table, render_data)?;
+
+                0
+            }
+        };
+
+        Ok(elements)
+    }
+
+    /// Render a chart in one of the text formats given in the options into a string
+    pub fn render_to_string(
+        &self,
+        chart_data: &ChartData,
+        options: &RenderOptions,
+    ) -> Result<String, GanttError> {
+        if options.format.is_binary() {
+            return Err(GanttError::UsageError(format!(
+                "{:?} output is binary and must be rendered with render_to_writer",
+                options.format
+            )));
+        }
+
+        let mut buffer: Vec<u8> = vec![];
+
+        self.render_to_writer(chart_data, options, &mut buffer)?;
+
+        String::from_utf8(buffer).map_err(|e| GanttError::LayoutError(e.to_string()))
+    }
+
+    // Read a JSON5 chart file, or JSON Lines with the chart's other fields on the first line and an item on each line
+    // after, which are read a line at a time so that large generated plans can be streamed in. The length of the file,
+    // if known, lets the lines be hashed as they are read.
+    fn read_chart_file(reader: Box<dyn Read>, len: Option<u64>) -> Result<ChartData, GanttError> {
+        let mut reader = BufReader::new(reader);
+        let mut first_line = String::new();
+
+        reader.read_line(&mut first_line)?;
+
+        let mut fields =
+            match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&first_line) {
+                Ok(fields) => fields,
+                Err(_) => {
+                    let mut content = first_line;
+
+                    reader.read_to_string(&mut content)?;
+
+                    let mut chart_data: ChartData = json5::from_str(&content)?;
+
+                    chart_data.source_hash = Some(source_hash(content.as_bytes()));
+
+                    return Ok(chart_data);
+                }
+            };
+        let line_error = |line: usize, e: serde_json::Error| {
+            GanttError::ParseError(format!("line {}: {}", line, e))
+        };
+
+        fields
+            .entry("items")
+            .or_insert_with(|| serde_json::Value::Array(vec![]));
+
+        let mut chart_data: ChartData = serde_json::from_value(serde_json::Value::Object(fields))
+            .map_err(|e| line_error(1, e))?;
+        let mut line = String::new();
+        let mut line_number = 1;
+        let mut hasher = BlobHasher::new(len);
+
+        hasher.update(first_line.as_bytes());
+
+        loop {
+            line.clear();
+
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+
+            line_number += 1;
+            hasher.update(line.as_bytes());
+
+            if !line.trim().is_empty() {
+                chart_data
+                    .items
+                    .push(serde_json::from_str(&line).map_err(|e| line_error(line_number, e))?);
+            }
+        }
+
+        chart_data.source_hash = hasher.finish();
+
+        Ok(chart_data)
+    }
+
+    // Read a chart file, adding in any holidays shared between charts
+    fn read_chart(
+        input_file: Option<&PathBuf>,
+        holidays: &[NaiveDate],
+    ) -> Result<ChartData, GanttError> {
+        Self::read_chart_from(open_input(input_file)?, input_len(input_file), holidays)
+    }
+
+    fn read_chart_from(
+        reader: Box<dyn Read>,
+        len: Option<u64>,
+        holidays: &[NaiveDate],
+    ) -> Result<ChartData, GanttError> {
+        let mut chart_data = Self::read_chart_file(reader, len)?;
+
+        chart_data.holidays.extend_from_slice(holidays);
+
+        Ok(chart_data)
+    }
+
+    // Write the chart as SVG, or the part of it between two X offsets, straight to the writer unless it is to be
+    // optimized, which needs all of it at once
+    fn write_svg_chart(
+        &self,
+        options: &RenderOptions,
+        render_data: &RenderData,
+        crop: Option<(f32, f32)>,
+        writer: impl Write,
+    ) -> Result<usize, GanttError> {
+        match options.optimize {
+            Some(precision) => {
+                let mut buffer = vec![];
+                let elements = self.write_chart_svg(options, render_data, crop, &mut buffer)?;
+
+                Self::write_svg_file(
+                    writer,
+                    &optimize::optimize_svg(&String::from_utf8_lossy(&buffer), precision),
+                )?;
+
+                Ok(elements)
+            }
+            None => self.write_chart_svg(options, render_data, crop, writer),
+        }
+    }
+
+    fn write_svg_file(mut writer: impl Write, svg: &str) -> Result<(), GanttError> {
+        writer.write_all(svg.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn write_chart_data_file(
+        mut writer: impl Write,
+        chart_data: &ChartData,
+    ) -> Result<(), GanttError> {
+        serde_json::to_writer_pretty(&mut writer, chart_data).map_err(io::Error::from)?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+
+    fn write_render_data_file(
+        mut writer: impl Write,
+        render_data: &RenderData,
+    ) -> Result<(), GanttError> {
+        serde_json::to_writer_pretty(&mut writer, render_data)
+            .map_err(|e| GanttError::LayoutError(e.to_string()))?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+
+    fn hsv_to_rgb(h: f32, s: f32, v: f32) -> u32 {
+        let h_i = (h * 6.0) as usize;
+        let f = h * 6.0 - h_i as f32;
+        let p = v * (1.0 - s);
+        let q = v * (1.0 - f * s);
+        let t = v * (1.0 - (1.0 - f) * s);
+
+        fn rgb(r: f32, g: f32, b: f32) -> u32 {
+            ((r * 256.0) as u32) << 16 | ((g * 256.0) as u32) << 8 | ((b * 256.0) as u32)
+        }
+
+        if h_i == 0 {
+            rgb(v, t, p)
+        } else if h_i == 1 {
+            rgb(q, v, p)
+        } else if h_i == 2 {
+            rgb(p, v, t)
+        } else if h_i == 3 {
+            rgb(p, q, v)
+        } else if h_i == 4 {
+            rgb(t, p, v)
+        } else {
+            rgb(v, p, q)
+        }
+    }
+
+    /// Schedule the items of a chart and lay it out, checking the layout too if the options are strict
+    pub fn process_chart_data<'c>(
+        &self,
+        options: &RenderOptions,
+        chart_data: &'c ChartData,
+    ) -> Result<RenderData<'c>, GanttError> {
+        let RenderOptions {
+            title_width,
+            fit_title_width,
+            assignee_column,
+            max_month_width,
+            min_month_width,
+            scale,
+            show_capacity,
+            ..
+        } = *options;
+        // Compact charts have their titles above the bars, so there's no title column or columns beside it
+        let (title_width, fit_title_width, assignee_column, columns) = if options.compact {
+            (0.0, false, None, &[][..])
+        } else {
+            (
+                title_width,
+                fit_title_width,
+                assignee_column,
+                options.columns.as_slice(),
+            )
+        };
+
+        if chart_data.items.is_empty() {
+            return Err(GanttError::validation(
+                None,
+                "items",
+                "You must provide at least one item".to_owned(),
+            ));
+        }
+
+        if options.auto_progress && chart_data.marked_date.is_none() {
+            return Err(GanttError::validation(
+                None,
+                "markedDate",
+                "Working out the percent complete needs a marked date to work it out at".to_owned(),
+            ));
+        }
+
+        if let Some(ref weekend) = chart_data.weekend {
+            if weekend.len() >= 7
+                && (0..7).all(|n| weekend.contains(&Weekday::try_from(n).unwrap()))
+            {
+                return Err(GanttError::validation(
+                    None,
+                    "weekend",
+                    "The weekend can't be every day of the week".to_owned(),
+                ));
+            }
+        }
+
+        if chart_data.labels.months.len() != 12 {
+            return Err(GanttError::validation(
+                None,
+                "labels",
+                format!(
+                    "There must be 12 month names, not {}",
+                    chart_data.labels.months.len()
+                ),
+            ));
+        }
+
+        let mut start_date = NaiveDateTime::MAX;
+        let mut end_date = NaiveDateTime::MIN;
+        let mut date = NaiveDateTime::MIN;
+        let mut shadow_durations: Vec<Option<Duration>> =
+            Vec::with_capacity(chart_data.items.len());
+        let mut item_start_dates: Vec<NaiveDateTime> = Vec::with_capacity(chart_data.items.len());
+        let mut item_end_dates: Vec<NaiveDateTime> = Vec::with_capacity(chart_data.items.len());
+        let mut item_predecessors: Vec<Vec<usize>> = Vec::with_capacity(chart_data.items.len());
+        let mut item_ids: HashMap<&str, usize> = HashMap::new();
+
+        // Move an exclusive end date on to the next working day
+        let working_day_end = |mut date: NaiveDateTime| {
+            while !chart_data.is_working_day(date.date()) {
+                date += Duration::days(1);
+            }
+
+            date
+        };
+
+        // Determine the project start & end dates
+        for (i, item) in chart_data.items.iter().enumerate() {
+            let mut predecessors = vec![];
+
+            if item
+                .resource_index
+                .is_some_and(|resource_index| resource_index >= chart_data.resources.len())
+            {
+                return Err(GanttError::validation(
+                    Some(i),
+                    "resource",
+                    "Resource index is out of range".to_owned(),
+                ));
+            }
+
+            let resource = chart_data.item_resource(Self::resource_index(chart_data, item));
+
+            if let Some(ref depends_on) = item.depends_on {
+                for id in depends_on {
+                    match item_ids.get(id.as_str()) {
+                        Some(&j) => predecessors.push(j),
+                        None => {
+                            // Depending on a later item is most likely to be a cycle
+                            if let Some(cycle) = Self::dependency_cycle(chart_data, i) {
+                                return Err(GanttError::validation(
+                                    Some(i),
+                                    "dependsOn",
+                                    format!(
+                                        "Items depend on each other in a cycle: {}",
+                                        cycle
+                                            .iter()
+                                            .map(|&j| format!("'{}'", chart_data.items[j].title))
+                                            .collect::<Vec<_>>()
+                                            .join(" -> ")
+                                    ),
+                                ));
+                            }
+
+                            return Err(GanttError::validation(
+                                Some(i),
+                                "dependsOn",
+                                format!(
+                                "Item '{}' depends on '{}' which is not the id of an earlier item",
+                                item.title, id
+                            ),
+                            ));
+                        }
+                    }
+                }
+            } else if i > 0 && item.start().is_none() {
+                // Items without a start date are scheduled after the previous item
+                predecessors.push(i - 1);
+            }
+
+            if let Some(ref id) = item.id {
+                if item_ids.insert(id, i).is_some() {
+                    return Err(GanttError::validation(
+                        Some(i),
+                        "id",
+                        format!("Item id '{}' is used more than once", id),
+                    ));
+                }
+            }
+
+            if let Some(class) = item
+                .classes
+                .iter()
+                .find(|class| class.is_empty() || class.contains(|c: char| c.is_whitespace()))
+            {
+                return Err(GanttError::validation(
+                    Some(i),
+                    "classes",
+                    format!("'{}' is not a valid CSS class name", class),
+                ));
+            }
+
+            if item.milestone {
+                if item.duration.is_some() || !item.segments.is_empty() {
+                    return Err(GanttError::validation(
+                        Some(i),
+                        "milestone",
+                        format!("Milestone '{}' can't have a duration", item.title),
+                    ));
+                }
+            } else if item.duration.is_none() && item.segments.is_empty() {
+                // Most likely to be a missing or misspelt duration
+                warning!(
+                    self.log,
+                    "Item '{}' has no duration so is drawn as a milestone, give it 'milestone: true' if it is one",
+                    item.title
+                );
+            }
+
+            // Bad durations are clamped rather than giving bars a negative width
+            for (field, days) in [
+                ("duration", item.duration),
+                ("durationOptimistic", item.duration_optimistic),
+                ("durationPessimistic", item.duration_pessimistic),
+                ("remainingDuration", item.remaining_duration),
+                ("effort", item.effort),
+            ] {
+                match days {
+                    Some(days) if clamp_days(days) != days => warning!(
+                        self.log,
+                        "Item '{}' has {} days for its {}, which is taken as {}",
+                        item.title,
+                        days,
+                        field,
+                        clamp_days(days)
+                    ),
+                    Some(days) if days == 0.0 && field == "duration" => warning!(
+                        self.log,
+                        "Item '{}' has a duration of 0 days, so its bar has no width",
+                        item.title
+                    ),
+                    _ => (),
+                }
+            }
+
+            if let Some(percent_complete) = item.percent_complete {
+                if !(0.0..=100.0).contains(&percent_complete) {
+                    return Err(GanttError::validation(
+                        Some(i),
+                        "percentComplete",
+                        format!("{} is not between 0 and 100", percent_complete),
+                    ));
+                }
+            }
+
+            match (item.actual_start, item.actual_finish) {
+                (None, Some(_)) => {
+                    return Err(GanttError::validation(
+                        Some(i),
+                        "actualFinish",
+                        "An actual finish needs an actual start".to_owned(),
+                    ));
+                }
+                (Some(actual_start), Some(actual_finish)) if actual_finish < actual_start => {
+                    return Err(GanttError::validation(
+                        Some(i),
+                        "actualFinish",
+                        "The actual finish is before the actual start".to_owned(),
+                    ));
+                }
+                _ => (),
+            }
+
+            if item.start().is_none() && item.depends_on.is_some() {
+                // Start as soon as all the dependencies are complete
+                if let Some(dependencies_end_date) =
+                    predecessors.iter().map(|&j| item_end_dates[j]).max()
+                {
+                    date = dependencies_end_date;
+
+                    debug!(
+                        self.log,
+                        "Item '{}' starts when its dependencies end on {}",
+                        item.title,
+                        date.date()
+                    );
+                }
+            } else if let (Some(start_date), Some(dependencies_end_date)) = (
+                item.start(),
+                predecessors.iter().map(|&j| item_end_dates[j]).max(),
+            ) {
+                // A milestone is usually the end of the items it depends on, which its own date
+                // stops it following when they move
+                if item.duration.is_none()
+                    && item.duration_ms.is_none()
+                    && item.segments.is_empty()
+                    && start_date != dependencies_end_date
+                {
+                    warning!(
+                        self.log,
+                        "Milestone '{}' is dated {} but the items it depends on end on {}, leave out its startDate to keep it at their end",
+                        item.title,
+                        start_date.date(),
+                        dependencies_end_date.date()
+                    );
+                }
+            }
+
+            if let Some(item_start_date) = item
+                .segments
+                .first()
+                .map(|segment| segment.start.and_time(NaiveTime::MIN))
+                .or(item.start())
+            {
+                date = item_start_date;
+
+                if item_start_date < start_date {
+                    // Move the start if it falls on a weekend
+                    start_date = date;
+
+                    while !chart_data.is_working_day(start_date.date()) {
+                        start_date += Duration::days(1);
+                    }
+
+                    if start_date != date {
+                        debug!(
+                            self.log,
+                            "Chart start moved from the weekend of {} to {}",
+                            date.date(),
+                            start_date.date()
+                        );
+                    }
+                }
+            } else if i == 0 {
+                return Err(GanttError::validation(
+                    Some(i),
+                    "startDate",
+                    "First item must contain a start date".to_owned(),
+                ));
+            }
+
+            // Work can't start while the resource is away
+            if item.duration.is_some() && resource.is_absent(date.date()) {
+                let absent_date = date;
+
+                while resource.is_absent(date.date()) {
+                    date += Duration::days(1);
+                }
+
+                debug!(
+                    self.log,
+                    "Item '{}' moved from {} to {} as '{}' is away",
+                    item.title,
+                    absent_date.date(),
+                    date.date(),
+                    resource.name
+                );
+            }
+
+            item_start_dates.push(date);
+            item_predecessors.push(predecessors);
+
+            for (segment, next_segment) in item.segments.iter().zip(item.segments.iter().skip(1)) {
+                if segment.duration <= 0.0
+                    || segment.start.and_time(NaiveTime::MIN) + days_duration(segment.duration)
+                        > next_segment.start.and_time(NaiveTime::MIN)
+                {
+                    return Err(GanttError::validation(
+                        Some(i),
+                        "segments",
+                        "Segments must have a duration and be in order without overlapping"
+                            .to_owned(),
+                    ));
+                }
+            }
+
+            // Skip the weekends and update a shadow list of the _real_ durations
+            if let Some(segment) = item.segments.last() {
+                // Split tasks run from the start of their first segment to the end of their last
+                let segment_end_date = working_day_end(
+                    segment.start.and_time(NaiveTime::MIN) + days_duration(segment.duration),
+                );
+
+                shadow_durations.push(Some(segment_end_date - date));
+                date = segment_end_date;
+            } else if let Some(item_days) = item.duration.map(clamp_days) {
+                // Extend the work by any days the resource is away
+                let mut absent_days = 0;
+
+                loop {
+                    let n = (0..item_days.ceil() as i64 + absent_days)
+                        .filter(|&d| resource.is_absent((date + Duration::days(d)).date()))
+                        .count() as i64;
+
+                    if n == absent_days {
+                        break;
+                    }
+
+                    absent_days = n;
+                }
+
+                if absent_days > 0 {
+                    debug!(
+                        self.log,
+                        "Item '{}' extended by {} days as '{}' is away",
+                        item.title,
+                        absent_days,
+                        resource.name
+                    );
+                }
+
+                let work_duration = days_duration(item_days) + Duration::days(absent_days);
+                let mut days_off = 0;
+
+                while !chart_data
+                    .is_working_day((date + work_duration + Duration::days(days_off)).date())
+                {
+                    days_off += 1;
+                }
+
+                if days_off > 0 {
+                    debug!(
+                        self.log,
+                        "Item '{}' extended by {} days so it ends on a working day",
+                        item.title,
+                        days_off
+                    );
+                }
+
+                let duration = work_duration + Duration::days(days_off);
+
+                date += duration;
+
+                shadow_durations.push(Some(duration));
+            } else {
+                shadow_durations.push(None);
+            }
+
+            item_end_dates.push(date);
+
+            trace!(
+                self.log,
+                "Scheduled item {} '{}' from {} to {}",
+                i,
+                item.title,
+                item_start_dates[i].date(),
+                date.date()
+            );
+
+            if end_date < date {
+                end_date = date;
+            }
+
+            // Make room for the pessimistic estimate whisker
+            if let Some(item_days) = item.duration_pessimistic {
+                let pessimistic_end_date =
+                    item_start_dates[i] + days_duration(clamp_days(item_days));
+
+                if end_date < pessimistic_end_date {
+                    end_date = pessimistic_end_date;
+                }
+            }
+
+            // Make room for when the work was really done
+            if let Some(actual_start) = item.actual_start {
+                start_date = start_date.min(actual_start);
+            }
+
+            if let Some(actual_finish) = item.actual_finish {
+                end_date = end_date.max(actual_finish + Duration::days(1));
+            }
+        }
+
+        // Forecast when tasks in progress will finish from the work left at the marked date, moving the
+        // tasks that depend on them along with them
+        let mut forecast_dates: Vec<(NaiveDateTime, NaiveDateTime)> = vec![];
+
+        if options.forecast {
+            let status_date = match chart_data.marked_date {
+                Some(date) => date.and_time(NaiveTime::MIN),
+                None => {
+                    return Err(GanttError::validation(
+                        None,
+                        "markedDate",
+                        "Forecasting needs a marked date to forecast from".to_owned(),
+                    ))
+                }
+            };
+
+            for (i, item) in chart_data.items.iter().enumerate() {
+                let (planned_start_date, planned_end_date) =
+                    (item_start_dates[i], item_end_dates[i]);
+                let percent_complete = item.percent_complete.unwrap_or(0.0);
+                let predecessors_end_date = item_predecessors[i]
+                    .iter()
+                    .map(|&j| forecast_dates[j].1)
+                    .max();
+                let forecast = match shadow_durations[i] {
+                    Some(duration)
+                        if percent_complete < 100.0
+                            && (percent_complete > 0.0 || item.remaining_duration.is_some()) =>
+                    {
+                        let remaining_days = item.remaining_duration.map_or(
+                            duration.num_seconds() as f32 / SECONDS_PER_DAY
+                                * (1.0 - percent_complete / 100.0),
+                            clamp_days,
+                        );
+
+                        (
+                            planned_start_date,
+                            working_day_end(
+                                status_date.max(planned_start_date) + days_duration(remaining_days),
+                            ),
+                        )
+                    }
+                    duration => match predecessors_end_date {
+                        Some(predecessors_end_date)
+                            if predecessors_end_date > planned_start_date
+                                && percent_complete < 100.0 =>
+                        {
+                            (
+                                predecessors_end_date,
+                                match duration {
+                                    Some(duration) => {
+                                        working_day_end(predecessors_end_date + duration)
+                                    }
+                                    None => predecessors_end_date,
+                                },
+                            )
+                        }
+                        _ => (planned_start_date, planned_end_date),
+                    },
+                };
+
+                if forecast.1 != planned_end_date {
+                    trace!(
+                        self.log,
+                        "Item {} '{}' is forecast to end on {} rather than {}",
+                        i,
+                        item.title,
+                        forecast.1.date(),
+                        planned_end_date.date()
+                    );
+                }
+
+                end_date = end_date.max(forecast.1);
+                forecast_dates.push(forecast);
+            }
+        }
+
+        // The start and end dates of the items in the scenario, which the chart is widened to fit
+        let scenario_dates: Vec<(NaiveDateTime, NaiveDateTime)> = match options.scenario {
+            Some(ref scenario) => {
+                let scenario_chart = scenario.apply(chart_data)?;
+                // The scenario's items would mostly warn about the same things as the plan's
+                let quiet_log = FilteredLog {
+                    log: self.log,
+                    verbosity: Verbosity::Quiet,
+                };
+                let scenario_data = GanttChartTool::new(&quiet_log)
+                    .process_chart_data(&RenderOptions::default(), &scenario_chart)?;
+
+                scenario_data
+                    .rows
+                    .iter()
+                    .map(|row| (row.start_date, row.end_date.unwrap_or(row.start_date)))
+                    .collect()
+            }
+            None => vec![],
+        };
+
+        for &(scenario_start_date, scenario_end_date) in &scenario_dates {
+            start_date = start_date.min(scenario_start_date);
+            end_date = end_date.max(scenario_end_date);
+        }
+
+        // Shorten the chart to the milestones, leaving out the time taken by the tasks around them
+        if options.milestones_only {
+            let milestone_dates = || {
+                item_start_dates
+                    .iter()
+                    .zip(shadow_durations.iter())
+                    .filter(|(_, duration)| duration.is_none())
+                    .map(|(&date, _)| date)
+            };
+
+            match (milestone_dates().min(), milestone_dates().max()) {
+                (Some(first_date), Some(last_date)) => {
+                    start_date = first_date;
+                    end_date = last_date;
+                }
+                _ => {
+                    return Err(GanttError::validation(
+                        None,
+                        "items",
+                        "There are no milestones to show".to_owned(),
+                    ))
+                }
+            }
+        }
+
+        // Work out the name and number of days in each column
+        let mut col_days: Vec<(String, u32)> = vec![];
+
+        // Fall back to longer periods rather than squeeze in hundreds of columns
+        let scale = match scale {
+            Scale::Auto => {
+                let num_months = (end_date.year() - start_date.year()) * 12
+                    + end_date.month() as i32
+                    - start_date.month() as i32
+                    + 1;
+                let num_quarters = (end_date.year() - start_date.year()) * 4
+                    + (end_date.month0() / 3) as i32
+                    - (start_date.month0() / 3) as i32
+                    + 1;
+                let scale = if num_months <= MAX_AUTO_COLUMNS {
+                    Scale::Month
+                } else if num_quarters <= MAX_AUTO_COLUMNS {
+                    Scale::Quarter
+                } else {
+                    Scale::Year
+                };
+
+                if scale != Scale::Month {
+                    warning!(
+                        self.log,
+                        "The chart spans {} months so {:?} columns are used instead, give a --scale to override this",
+                        num_months,
+                        scale
+                    );
+                }
+
+                scale
+            }
+            scale => scale,
+        };
+        // The number of days that make a column as wide as the maximum
+        let mut days_per_max_width = 31.0;
+
+        match scale {
+            Scale::Auto | Scale::Month | Scale::Quarter | Scale::Year => {
+                let months_per_col = match scale {
+                    Scale::Quarter => 3,
+                    Scale::Year => 12,
+                    _ => 1,
+                };
+
+                days_per_max_width = 31.0 * months_per_col as f32;
+                start_date = NaiveDate::from_ymd_opt(
+                    start_date.year(),
+                    start_date.month0() / months_per_col * months_per_col + 1,
+                    1,
+                )
+                .unwrap()
+                .and_time(NaiveTime::MIN);
+                date = start_date;
+
+                while date <= end_date {
+                    let month0 = date.month0() + months_per_col;
+                    let next_date = NaiveDate::from_ymd_opt(
+                        date.year() + (month0 / 12) as i32,
+                        month0 % 12 + 1,
+                        1,
+                    )
+                    .unwrap()
+                    .and_time(NaiveTime::MIN);
+
+                    col_days.push((
+                        match scale {
+                            Scale::Quarter => chart_data
+                                .labels
+                                .quarter_name(date.month0() / 3 + 1, date.year()),
+                            Scale::Year => date.year().to_string(),
+                            _ => chart_data.labels.months[date.month0() as usize].clone(),
+                        },
+                        (next_date - date).num_days() as u32,
+                    ));
+
+                    date = next_date;
+                }
+
+                end_date = date - Duration::days(1);
+            }
+            Scale::Sprint => {
+                let mut sprints: Vec<&SprintData> = chart_data.sprints.iter().collect();
+
+                if sprints.is_empty() {
+                    return Err(GanttError::validation(
+                        None,
+                        "sprints",
+                        "The sprint scale requires at least one sprint".to_owned(),
+                    ));
+                }
+
+                sprints.sort_by_key(|sprint| sprint.start);
+
+                // Items outside of the sprints go in unnamed columns at either end
+                date = start_date.min(sprints[0].start.and_time(NaiveTime::MIN));
+                start_date = date;
+
+                for sprint in sprints {
+                    let sprint_start_date = sprint.start.and_time(NaiveTime::MIN);
+
+                    if sprint_start_date < date || sprint.end < sprint.start {
+                        return Err(GanttError::validation(
+                            None,
+                            "sprints",
+                            format!(
+                                "Sprint '{}' overlaps the previous sprint or ends before it starts",
+                                sprint.name
+                            ),
+                        ));
+                    }
+
+                    if date < sprint_start_date {
+                        col_days
+                            .push((String::new(), (sprint_start_date - date).num_days() as u32));
+                    }
+
+                    date = sprint.end.and_time(NaiveTime::MIN) + Duration::days(1);
+                    col_days.push((
+                        sprint.name.clone(),
+                        (date - sprint_start_date).num_days() as u32,
+                    ));
+                }
+
+                if end_date >= date {
+                    col_days.push((String::new(), (end_date - date).num_days() as u32 + 1));
+                    date = end_date + Duration::days(1);
+                }
+
+                end_date = date - Duration::days(1);
+            }
+        }
+
+        // Create all the column data
+        let mut cols = vec![];
+        let mut col_start_dates = vec![];
+        let mut col_end_dates = vec![];
+        let mut col_num_days = vec![];
+        // The X offset of each column from the start of the first
+        let mut col_lefts = vec![];
+        let mut x = 0.0;
+
+        // The dates anything is drawn between, to leave out the columns with nothing in them
+        let item_spans: Vec<(NaiveDateTime, NaiveDateTime)> = if options.skip_empty_months {
+            chart_data
+                .items
+                .iter()
+                .enumerate()
+                .flat_map(|(i, item)| {
+                    let start_date = item_start_dates[i];
+                    let planned = (
+                        start_date,
+                        start_date + shadow_durations[i].unwrap_or_else(Duration::zero),
+                    );
+                    let actual = item.actual_start.map(|actual_start| {
+                        (
+                            actual_start,
+                            item.actual_finish.unwrap_or(actual_start) + Duration::days(1),
+                        )
+                    });
+
+                    std::iter::once(planned)
+                        .chain(actual)
+                        .chain(forecast_dates.get(i).copied())
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        // The breaks as their first day and the day after their last, in order
+        let mut breaks: Vec<(NaiveDateTime, NaiveDateTime)> = chart_data
+            .breaks
+            .iter()
+            .map(|b| {
+                (
+                    b.start.and_time(NaiveTime::MIN),
+                    b.end.and_time(NaiveTime::MIN) + Duration::days(1),
+                )
+            })
+            .collect();
+
+        breaks.sort();
+
+        for (i, &(break_start, break_end)) in breaks.iter().enumerate() {
+            if break_end <= break_start
+                || breaks
+                    .get(i + 1)
+                    .is_some_and(|&(next_start, _)| next_start < break_end)
+            {
+                return Err(GanttError::validation(
+                    None,
+                    "breaks",
+                    format!(
+                        "The break starting {} overlaps the next break or ends before it starts",
+                        break_start.date()
+                    ),
+                ));
+            }
+        }
+
+        let mut gap_before = false;
+
+        date = start_date;
+
+        for (name, item_days) in col_days {
+            let item_width = (max_month_width * (item_days as f32) / days_per_max_width)
+                .max(min_month_width.unwrap_or(0.0));
+            let col_end_date = date + Duration::days(item_days as i64);
+
+            // Milestones are drawn on their date, so count as in the column they fall in
+            if options.skip_empty_months
+                && !item_spans.iter().any(|&(span_start, span_end)| {
+                    span_start < col_end_date && (span_end > date || span_start >= date)
+                })
+            {
+                gap_before = !cols.is_empty();
+                date = col_end_date;
+                continue;
+            }
+
+            // Breaks are cut out of the columns they fall in and squeezed into narrow columns of their own
+            let mut pieces = vec![];
+            let mut piece_start = date;
+
+            for &(break_start, break_end) in &breaks {
+                let (break_start, break_end) =
+                    (break_start.max(piece_start), break_end.min(col_end_date));
+
+                if break_start < break_end {
+                    if piece_start < break_start {
+                        pieces.push((piece_start, break_start, false));
+                    }
+
+                    pieces.push((break_start, break_end, true));
+                    piece_start = break_end;
+                }
+            }
+
+            if piece_start < col_end_date || pieces.is_empty() {
+                pieces.push((piece_start, col_end_date, false));
+            }
+
+            // The column's name goes on the first part of it that isn't a break
+            let mut name = Some(name);
+
+            for (piece_start, piece_end, is_break) in pieces {
+                let piece_days = (piece_end - piece_start).num_days() as u32;
+
+                // A break carrying on from the previous column is widened rather than repeated
+                if is_break
+                    && cols
+                        .last()
+                        .is_some_and(|col: &ColumnRenderData| col.is_break)
+                {
+                    if let (Some(num_days), Some(end_date)) =
+                        (col_num_days.last_mut(), col_end_dates.last_mut())
+                    {
+                        if *end_date == piece_start {
+                            *num_days += piece_days;
+                            *end_date = piece_end;
+                            continue;
+                        }
+                    }
+                }
+
+                let width = if is_break {
+                    BREAK_WIDTH
+                } else if piece_days == item_days {
+                    item_width
+                } else {
+                    item_width * piece_days as f32 / item_days as f32
+                };
+
+                col_start_dates.push(piece_start);
+                col_end_dates.push(piece_end);
+                col_num_days.push(piece_days);
+                col_lefts.push(x);
+                x += width;
+
+                cols.push(ColumnRenderData {
+                    width,
+                    name: if is_break {
+                        String::new()
+                    } else {
+                        name.take().unwrap_or_default()
+                    },
+                    gap_before,
+                    is_break,
+                });
+                gap_before = false;
+            }
+
+            date = col_end_date;
+        }
+
+        // Compact rows have room for the title above the bar
+        let row_gutter = if options.compact {
+            Gutter {
+                left: 2.0,
+                top: 12.0,
+                right: 2.0,
+                bottom: 2.0,
+            }
+        } else {
+            Gutter {
+                left: 5.0,
+                top: 5.0,
+                right: 5.0,
+                bottom: 5.0,
+            }
+        };
+        // TODO(john): The 20.0 should be configurable, and for the resource table
+        let row_height = row_gutter.height() + if options.compact { 10.0 } else { 20.0 };
+        let text_measure = TextMeasure::new(options.embed_font.as_deref());
+        // The titles shown, with numbered placeholders in place of those that can't be shared
+        let is_redacted: Vec<bool> = chart_data
+            .items
+            .iter()
+            .map(|item| match options.redact {
+                Some(Redact::All) => true,
+                Some(Redact::Confidential) => item.confidential,
+                None => false,
+            })
+            .collect();
+        let mut redacted_count = 0;
+        let titles: Vec<Cow<str>> = chart_data
+            .items
+            .iter()
+            .zip(is_redacted.iter())
+            .map(|(item, &is_redacted)| {
+                if is_redacted {
+                    redacted_count += 1;
+                    Cow::Owned(
+                        chart_data
+                            .labels
+                            .redacted
+                            .replace("{number}", &redacted_count.to_string()),
+                    )
+                } else {
+                    Cow::Borrowed(item.title.as_str())
+                }
+            })
+            .collect();
+        // Room for the status badges before the titles
+        let badge_width = match options.badges {
+            _ if options.compact => 0.0,
+            Some(Badge::Dot) => BADGE_DOT_SIZE + BADGE_GAP,
+            Some(Badge::Percent) => {
+                text_measure.width("100%", BADGE_FONT_SIZE) + 2.0 * BADGE_PADDING + BADGE_GAP
+            }
+            None => 0.0,
+        };
+        let title_width = if fit_title_width {
+            // Resource names are in the title column of the capacity chart
+            let capacity_names = chart_data
+                .resources
+                .iter()
+                .map(|resource| resource.name.as_str())
+                .filter(|_| show_capacity);
+
+            titles
+                .iter()
+                .map(|title| text_measure.width(title, ITEM_FONT_SIZE) + badge_width)
+                .chain(capacity_names.map(|name| text_measure.width(name, ITEM_FONT_SIZE)))
+                .map(|width| width + row_gutter.width())
+                .fold(0.0, f32::max)
+                .min(title_width)
+        } else {
+            title_width
+        };
+        let assignees: Vec<String> = match assignee_column {
+            Some(AssigneeColumn::Name) => chart_data
+                .resources
+                .iter()
+                .map(|resource| resource.name.clone())
+                .collect(),
+            Some(AssigneeColumn::Initials) => chart_data
+                .resources
+                .iter()
+                .map(|resource| resource.initials())
+                .collect(),
+            None => vec![],
+        };
+        let assignee_width = if assignees.is_empty() {
+            0.0
+        } else {
+            assignees
+                .iter()
+                .map(|assignee| {
+                    text_measure.width(assignee, ASSIGNEE_FONT_SIZE) + row_gutter.width()
+                })
+                .fold(0.0, f32::max)
+        };
+        // Dates are all about the same width, so the columns can be sized before the rows are laid out
+        let date_width = text_measure.width("0000-00-00", DATA_FONT_SIZE);
+        let data_column_widths: Vec<f32> = columns
+            .iter()
+            .map(|&column| {
+                let cells_width = match column {
+                    DataColumn::Start | DataColumn::End => date_width,
+                    DataColumn::Duration => chart_data
+                        .items
+                        .iter()
+                        .map(|item| {
+                            text_measure.width(
+                                &Self::duration_text(chart_data, item, options.round_durations),
+                                DATA_FONT_SIZE,
+                            )
+                        })
+                        .fold(0.0, f32::max),
+                    DataColumn::Resource => chart_data
+                        .resources
+                        .iter()
+                        .map(|resource| text_measure.width(&resource.name, DATA_FONT_SIZE))
+                        .fold(0.0, f32::max),
+                };
+
+                text_measure
+                    .width(
+                        Self::data_column_heading(&chart_data.labels, column),
+                        DATA_FONT_SIZE,
+                    )
+                    .max(cells_width)
+                    + row_gutter.width()
+            })
+            .collect();
+        // The assignee and data columns are added to the right of the titles
+        let title_width = title_width + assignee_width + data_column_widths.iter().sum::<f32>();
+        // Column headings are turned when any are too wide for their columns, making the heading row taller to fit
+        // them and the chart wider for those that reach past the last column
+        let heading_font_size = if options.compact {
+            COMPACT_FONT_SIZES.1
+        } else {
+            HEADING_FONT_SIZE
+        };
+        let heading_widths: Vec<f32> = cols
+            .iter()
+            .map(|col| text_measure.width(&col.name, heading_font_size))
+            .collect();
+        let heading_angle = cols
+            .iter()
+            .zip(heading_widths.iter())
+            .any(|(col, &heading_width)| heading_width > col.width)
+            .then(|| options.heading_angle.degrees());
+        let (heading_height, heading_overhang) = match heading_angle {
+            Some(angle) => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let text_height = text_measure.height(heading_font_size);
+                let mut x = 0.0;
+                let mut right = 0.0_f32;
+
+                for (col, heading_width) in cols.iter().zip(heading_widths.iter()) {
+                    right = right
+                        .max(x + col.width / 2.0 + heading_width * cos + text_height / 2.0 * sin);
+                    x += col.width;
+                }
+
+                (
+                    heading_widths
+                        .iter()
+                        .map(|heading_width| {
+                            heading_width * sin + text_height * cos + row_gutter.height()
+                        })
+                        .fold(row_height, f32::max),
+                    (right - x).max(0.0),
+                )
+            }
+            None => (row_height, 0.0),
+        };
+        let gutter = Gutter {
+            left: 10.0,
+            // Sprint names go in an extra header row above the months, and phase names above those
+            top: if options.compact { 30.0 } else { 50.0 }
+                + heading_height
+                + if chart_data.sprints.is_empty() || scale == Scale::Sprint {
+                    0.0
+                } else {
+                    row_height
+                }
+                + if chart_data.phases.is_empty() {
+                    0.0
+                } else {
+                    row_height
+                }
+                + if options.heat_strip {
+                    HEAT_STRIP_HEIGHT
+                } else {
+                    0.0
+                },
+            right: 10.0 + heading_overhang,
+            bottom: 10.0,
+        };
+        let resource_gutter = Gutter {
+            left: 10.0,
+            top: 10.0,
+            right: 10.0,
+            bottom: 10.0,
+        };
+        let resource_height = resource_gutter.height() + 20.0;
+        let mut rows = vec![];
+        // Columns can be widened to the minimum width, so dates are placed within their column
+        let date_offset = |date: NaiveDateTime| -> f32 {
+            let i = col_start_dates
+                .partition_point(|&start| start <= date)
+                .saturating_sub(1);
+
+            // Dates in left out columns are put at the end of the column before them
+            let date = if options.skip_empty_months {
+                date.min(col_end_dates[i])
+            } else {
+                date
+            };
+
+            title_width
+                + gutter.left
+                + col_lefts[i]
+                + ((date - col_start_dates[i]).num_seconds() as f32 / SECONDS_PER_DAY)
+                    / (col_num_days[i] as f32)
+                    * cols[i].width
+        };
+
+        // Flag the items at risk, and list the risks if they are shown
+        let mut at_risk = vec![false; chart_data.items.len()];
+        let mut risks = vec![];
+
+        for risk in &chart_data.risks {
+            let mut tasks = vec![];
+
+            for id in &risk.tasks {
+                match item_ids.get(id.as_str()) {
+                    Some(&i) => {
+                        at_risk[i] = true;
+                        tasks.push(titles[i].as_ref());
+                    }
+                    None => {
+                        return Err(GanttError::validation(
+                            None,
+                            "risks",
+                            format!(
+                                "Risk '{}' is to '{}' which is not the id of an item",
+                                risk.title, id
+                            ),
+                        ))
+                    }
+                }
+            }
+
+            if options.risk_table {
+                risks.push(RiskRenderData {
+                    title: &risk.title,
+                    tasks: tasks.join(", "),
+                    owner: risk.owner.as_deref().unwrap_or(""),
+                    mitigation: risk.mitigation.as_deref().unwrap_or(""),
+                });
+            }
+        }
+
+        // The dates the columns cover, which the bars and milestones are kept within
+        let (first_date, last_date) = (col_start_dates[0], col_end_dates[col_end_dates.len() - 1]);
+        // Where a date is drawn, which is on a week boundary when snapping to weeks
+        let snap = |date: NaiveDateTime| match options.snap {
+            Some(Snap::Week) => nearest_week_start(date).clamp(first_date, last_date),
+            None => date,
+        };
+        // The X offset and length of a bar, with tasks kept at least a week long when snapping to weeks
+        let bar_span = |start_date: NaiveDateTime, end_date: NaiveDateTime| -> (f32, f32) {
+            let snapped_end_date = match options.snap {
+                Some(Snap::Week) if end_date > start_date => {
+                    snap(end_date).max((snap(start_date) + Duration::weeks(1)).min(last_date))
+                }
+                _ => snap(end_date),
+            };
+            let start_offset = date_offset(snap(start_date));
+
+            (start_offset, date_offset(snapped_end_date) - start_offset)
+        };
+
+        // Calculate the X offsets of all the bars and milestones
+        for (i, item) in chart_data.items.iter().enumerate() {
+            // Use the shadow duration instead of the actual duration as it accounts for weekends
+            let item_end_date =
+                item_start_dates[i] + shadow_durations[i].unwrap_or_else(Duration::zero);
+
+            if item_start_dates[i] < first_date || item_end_date > last_date {
+                warning!(
+                    self.log,
+                    "Item '{}' is scheduled from {} to {}, outside the chart's dates, so it is cut to fit",
+                    item.title,
+                    item_start_dates[i].date(),
+                    item_end_date.date()
+                );
+            }
+
+            date = item_start_dates[i].clamp(first_date, last_date);
+
+            let offset = date_offset(snap(date));
+
+            let row_start_date = date;
+            let mut length: Option<f32> = None;
+
+            if shadow_durations[i].is_some() {
+                date = item_end_date.clamp(first_date, last_date);
+                length = Some(bar_span(row_start_date, date).1);
+            }
+
+            let resource_index = Self::resource_index(chart_data, item);
+            let estimate = match (item.duration_optimistic, item.duration_pessimistic) {
+                (None, None) => None,
+                (optimistic, pessimistic) => {
+                    let most_likely = item.duration.unwrap_or(0.0);
+                    let optimistic = clamp_days(optimistic.unwrap_or(most_likely));
+                    let pessimistic = clamp_days(pessimistic.unwrap_or(most_likely));
+
+                    // In order, so the whisker can't have a negative width
+                    Some((
+                        date_offset(snap(
+                            row_start_date + days_duration(optimistic.min(pessimistic)),
+                        )),
+                        date_offset(snap(
+                            row_start_date + days_duration(optimistic.max(pessimistic)),
+                        )),
+                    ))
+                }
+            };
+
+            // Baseline finishes are the last day of a task, while the end date is the day after
+            let baseline_end_date = item.baseline_finish.map(|baseline_finish| match length {
+                Some(_) => baseline_finish + Duration::days(1),
+                None => baseline_finish,
+            });
+            let slip_days = baseline_end_date.map(|baseline_end_date| {
+                ((date - baseline_end_date).num_seconds() as f32 / SECONDS_PER_DAY * 100.0).round()
+                    / 100.0
+            });
+
+            // Work still going on is shown up to the marked date
+            let actual_end_date = item
+                .actual_finish
+                .map(|actual_finish| actual_finish + Duration::days(1))
+                .or_else(|| {
+                    chart_data
+                        .marked_date
+                        .map(|date| date.and_time(NaiveTime::MIN))
+                });
+            let actual = match (item.actual_start, actual_end_date) {
+                (Some(actual_start), Some(actual_end_date)) if actual_start < actual_end_date => {
+                    Some(bar_span(actual_start, actual_end_date))
+                }
+                _ => None,
+            };
+
+            let forecast = forecast_dates
+                .get(i)
+                .filter(|&&(forecast_start_date, forecast_end_date)| {
+                    forecast_start_date != row_start_date || forecast_end_date != date
+                })
+                .map(|&(forecast_start_date, forecast_end_date)| {
+                    bar_span(forecast_start_date, forecast_end_date)
+                });
+
+            let scenario = scenario_dates
+                .get(i)
+                .filter(|&&scenario_dates| scenario_dates != (row_start_date, date))
+                .map(|&(scenario_start_date, scenario_end_date)| {
+                    bar_span(
+                        scenario_start_date.clamp(first_date, last_date),
+                        scenario_end_date.clamp(first_date, last_date),
+                    )
+                });
+
+            // Without completion data, tasks are taken to be on plan at the marked date
+            let percent_complete = match (item.percent_complete, chart_data.marked_date) {
+                (None, Some(marked_date)) if options.auto_progress && length.is_some() => {
+                    Some(Self::elapsed_percent(
+                        chart_data,
+                        row_start_date,
+                        date,
+                        marked_date.and_time(NaiveTime::MIN),
+                    ))
+                }
+                (percent_complete, _) => percent_complete,
+            };
+
+            let segments = item
+                .segments
+                .iter()
+                .map(|segment| {
+                    let segment_start_date = segment.start.and_time(NaiveTime::MIN);
+
+                    bar_span(
+                        segment_start_date,
+                        working_day_end(segment_start_date + days_duration(segment.duration)),
+                    )
+                })
+                .collect();
+
+            rows.push(RowRenderData {
+                title: titles[i].clone(),
+                start_date: row_start_date,
+                end_date: length.map(|_| date),
+                resource_index,
+                color_index: resource_index,
+                offset,
+                length,
+                open: item.open.unwrap_or(false),
+                predecessors: mem::take(&mut item_predecessors[i]),
+                estimate,
+                classes: item.classes.as_slice(),
+                percent_complete,
+                segments,
+                baseline_offset: baseline_end_date.map(|date| date_offset(snap(date))),
+                slip_days,
+                actual,
+                forecast,
+                scenario,
+                // Notes could give away what a redacted item is
+                note: item.note.as_deref().filter(|_| !is_redacted[i]),
+                at_risk: at_risk[i],
+                aggregated: false,
+                group: item.group.as_deref(),
+                highlighted: false,
+                selected: false,
+            });
+        }
+
+        // Mark the highlighted task and its chain while there is still a row for each item
+        if let Some(ref id) = options.highlight {
+            let index = chart_data
+                .items
+                .iter()
+                .position(|item| item.id.as_ref() == Some(id))
+                .ok_or_else(|| {
+                    GanttError::validation(
+                        None,
+                        "items",
+                        format!("There is no item with the ID '{}' to highlight", id),
+                    )
+                })?;
+            let mut successors: Vec<Vec<usize>> = vec![vec![]; rows.len()];
+            let mut visited = vec![false; rows.len()];
+
+            for (i, row) in rows.iter().enumerate() {
+                for &j in &row.predecessors {
+                    successors[j].push(i);
+                }
+            }
+
+            for i in Self::dependency_chain(&rows, &successors, index, &mut visited) {
+                rows[i].highlighted = true;
+            }
+
+            rows[index].selected = true;
+        }
+
+        let mut data_column_offset =
+            gutter.left + title_width - data_column_widths.iter().sum::<f32>();
+        let mut data_columns: Vec<DataColumnRenderData> = columns
+            .iter()
+            .zip(data_column_widths)
+            .map(|(&column, width)| {
+                let cells = chart_data
+                    .items
+                    .iter()
+                    .zip(rows.iter())
+                    .map(|(item, row)| match column {
+                        DataColumn::Start => row.start_date.format("%Y-%m-%d").to_string(),
+                        // End dates are exclusive, and milestones end where they start
+                        DataColumn::End => row
+                            .end_date
+                            .map_or(row.start_date, |end_date| end_date - Duration::seconds(1))
+                            .format("%Y-%m-%d")
+                            .to_string(),
+                        DataColumn::Duration => {
+                            Self::duration_text(chart_data, item, options.round_durations)
+                        }
+                        DataColumn::Resource => {
+                            chart_data.resource_name(row.resource_index).to_owned()
+                        }
+                    })
+                    .collect();
+                let offset = data_column_offset;
+
+                data_column_offset += width;
+
+                DataColumnRenderData {
+                    heading: Self::data_column_heading(&chart_data.labels, column),
+                    offset,
+                    width,
+                    cells,
+                }
+            })
+            .collect();
+
+        // The X offset and width of a span of whole days, clipped to the chart so it doesn't widen it
+        let clip_days = |start: NaiveDate, end: NaiveDate| -> Option<(f32, f32)> {
+            let clipped_start_date = start.and_time(NaiveTime::MIN).max(start_date);
+            let clipped_end_date = (end.and_time(NaiveTime::MIN) + Duration::days(1))
+                .min(end_date + Duration::days(1));
+
+            if clipped_start_date < clipped_end_date {
+                let offset = date_offset(clipped_start_date);
+
+                Some((offset, date_offset(clipped_end_date) - offset))
+            } else {
+                None
+            }
+        };
+
+        // With the sprint scale the sprints are already shown as the columns
+        let sprints = chart_data
+            .sprints
+            .iter()
+            .filter(|_| scale != Scale::Sprint)
+            .filter_map(|sprint| {
+                clip_days(sprint.start, sprint.end).map(|(offset, width)| BandRenderData {
+                    name: Cow::Borrowed(&sprint.name),
+                    offset,
+                    width,
+                    color: None,
+                })
+            })
+            .collect();
+
+        let phases = chart_data
+            .phases
+            .iter()
+            .enumerate()
+            .filter_map(|(i, phase)| {
+                clip_days(phase.start, phase.end).map(|(offset, width)| {
+                    let color = if options.print_friendly {
+                        // Named colors can't be converted, so they become a middle gray
+                        let color = print::grayscale_css(
+                            phase
+                                .color
+                                .as_deref()
+                                .unwrap_or(PHASE_COLORS[i % PHASE_COLORS.len()]),
+                        );
+
+                        Some(Cow::Owned(if color.starts_with('#') {
+                            color
+                        } else {
+                            "#808080".to_owned()
+                        }))
+                    } else {
+                        phase.color.as_deref().map(Cow::Borrowed)
+                    };
+
+                    BandRenderData {
+                        name: Cow::Borrowed(&phase.name),
+                        offset,
+                        width,
+                        color,
+                    }
+                })
+            })
+            .collect();
+
+        // Absences as an X offset and width for each resource
+        let absences = chart_data
+            .item_resources()
+            .map(|resource| {
+                resource
+                    .absences
+                    .iter()
+                    .filter_map(|absence| clip_days(absence.start, absence.end))
+                    .collect()
+            })
+            .collect();
+
+        let (color_names, key_colors) = Self::color_rows(options.color_by, chart_data, &mut rows);
+
+        let mut capacity = if show_capacity {
+            Self::calc_capacity(chart_data, &rows, &col_start_dates, &col_end_dates)
+        } else {
+            vec![]
+        };
+
+        if let Some(name) = options.collapse.iter().find(|&name| {
+            !chart_data
+                .items
+                .iter()
+                .any(|item| item.group.as_ref() == Some(name))
+        }) {
+            return Err(GanttError::validation(
+                None,
+                "items",
+                format!("There are no items in group '{}' to collapse", name),
+            ));
+        }
+
+        // The group, phase or resource each row is rolled up into
+        let mut keys: Vec<Option<&str>> = match options.rollup {
+            Some(rollup) => chart_data
+                .items
+                .iter()
+                .zip(rows.iter())
+                .map(|(item, row)| match rollup {
+                    Rollup::Group => item.group.as_deref(),
+                    Rollup::Phase => chart_data
+                        .phases
+                        .iter()
+                        .find(|phase| {
+                            phase.start <= row.start_date.date()
+                                && row.start_date.date() <= phase.end
+                        })
+                        .map(|phase| phase.name.as_str()),
+                })
+                .collect(),
+            None if !options.collapse.is_empty() => chart_data
+                .items
+                .iter()
+                .map(|item| {
+                    item.group
+                        .as_deref()
+                        .filter(|group| options.collapse.iter().any(|name| name == group))
+                })
+                .collect(),
+            // Milestones aren't anyone's work, so keep their own rows
+            None if options.aggregate_by == Some(AggregateBy::Resource) => rows
+                .iter()
+                .map(|row| {
+                    row.length
+                        .map(|_| chart_data.resource_name(row.resource_index))
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        let is_tagged: Vec<bool> = chart_data
+            .items
+            .iter()
+            .map(|item| {
+                (options.tags.is_empty() || item.tags.iter().any(|tag| options.tags.contains(tag)))
+                    && !item
+                        .tags
+                        .iter()
+                        .any(|tag| options.exclude_tags.contains(tag))
+            })
+            .collect();
+        // Worked out while the rows still line up with the items
+        let totals = match options.totals {
+            Some(totals) => Self::calc_totals(
+                chart_data,
+                chart_data
+                    .items
+                    .iter()
+                    .zip(rows.iter())
+                    .enumerate()
+                    .filter(|(i, (_, row))| {
+                        is_tagged[*i]
+                            && options
+                                .only_resource
+                                .is_none_or(|only_resource| row.resource_index == only_resource)
+                    })
+                    .map(|(_, item_row)| item_row),
+                totals,
+                &cols,
+                &col_start_dates,
+                &col_end_dates,
+            ),
+            None => vec![],
+        };
+
+        // Keep only the items with the tags asked for, before any are rolled up, with the dates of the whole chart
+        if !options.tags.is_empty() || !options.exclude_tags.is_empty() {
+            if !is_tagged.contains(&true) {
+                return Err(GanttError::validation(
+                    None,
+                    "items",
+                    "There are no items with the tags asked for".to_owned(),
+                ));
+            }
+
+            if !keys.is_empty() {
+                keys = keys
+                    .into_iter()
+                    .zip(is_tagged.iter())
+                    .filter_map(|(key, &is_tagged)| is_tagged.then_some(key))
+                    .collect();
+            }
+
+            Self::retain_rows(&mut rows, &mut data_columns, |i, _| is_tagged[i]);
+        }
+
+        // Counted before the tasks are rolled up, so overlapping tasks each count
+        let heat = if options.heat_strip {
+            Self::calc_heat(
+                chart_data,
+                rows.iter().filter(|row| {
+                    options
+                        .only_resource
+                        .is_none_or(|only_resource| row.resource_index == only_resource)
+                }),
+                &cols,
+                &col_start_dates,
+                &col_end_dates,
+            )
+        } else {
+            vec![]
+        };
+
+        if options.rollup.is_some() || !options.collapse.is_empty() {
+            rows = Self::rollup_rows(
+                &rows,
+                &keys,
+                columns,
+                &mut data_columns,
+                chart_data,
+                options.round_durations,
+                false,
+            );
+        } else if options.aggregate_by.is_some() {
+            rows = Self::rollup_rows(
+                &rows,
+                &keys,
+                columns,
+                &mut data_columns,
+                chart_data,
+                options.round_durations,
+                true,
+            );
+        }
+
+        // Keep only the rows of one resource, with the dates and columns of the whole chart, or only the milestones
+        if options.only_resource.is_some() || options.milestones_only {
+            Self::retain_rows(&mut rows, &mut data_columns, |_, row| {
+                options
+                    .only_resource
+                    .is_none_or(|only_resource| row.resource_index == only_resource)
+                    && !(options.milestones_only && row.length.is_some())
+            });
+
+            if let Some(only_resource) = options.only_resource {
+                capacity
+                    .retain(|resource_capacity| resource_capacity.resource_index == only_resource);
+            }
+        }
+
+        if options.sort == Some(Sort::Topological) {
+            let order = Self::topological_order(&rows);
+
+            Self::reorder_rows(&mut rows, &mut data_columns, &order);
+        }
+
+        if options.status_line && chart_data.marked_date.is_none() {
+            return Err(GanttError::validation(
+                None,
+                "markedDate",
+                "The status line needs a marked date to show the status on".to_owned(),
+            ));
+        }
+
+        let marked_date_offset = chart_data
+            .marked_date
+            .map(|date| date_offset(date.and_time(NaiveTime::MIN)));
+
+        let mut styles = vec![];
+        let font_family = match options.embed_font {
+            Some(ref data) => {
+                let family = font::font_family(data);
+
+                styles.push(font::font_face_style(&family, data));
+                format!("'{}',Arial", family)
+            }
+            None => "Arial".to_owned(),
+        };
+
+        let (title_font_size, heading_font_size, item_above_font_size) = if options.compact {
+            COMPACT_FONT_SIZES
+        } else {
+            (18.0, HEADING_FONT_SIZE, ITEM_FONT_SIZE)
+        };
+
+        styles.extend([
+            ".outer-lines{stroke-width:3;stroke:#aaaaaa;}".to_owned(),
+            ".inner-lines{stroke-width:2;stroke:#dddddd;}".to_owned(),
+            ".item{font-family:Arial;font-size:12pt;dominant-baseline:middle;}".to_owned(),
+            ".resource{font-family:Arial;font-size:12pt;text-anchor:end;dominant-baseline:middle;}".to_owned(),
+            format!(".title{{font-family:Arial;font-size:{}pt;}}", title_font_size),
+            ".assignee{font-family:Arial;font-size:10pt;fill:#666666;dominant-baseline:middle;}".to_owned(),
+            ".data-heading{font-family:Arial;font-size:10pt;font-weight:bold;dominant-baseline:middle;}".to_owned(),
+            ".data-cell{font-family:Arial;font-size:10pt;fill:#444444;dominant-baseline:middle;}".to_owned(),
+            format!(".heading{{font-family:Arial;font-size:{}pt;dominant-baseline:middle;text-anchor:middle;}}", heading_font_size),
+            format!(".item-above{{font-family:Arial;font-size:{}pt;}}", item_above_font_size),
+            ".task-heading{dominant-baseline:middle;text-anchor:start;}".to_owned(),
+            ".turned-heading{text-anchor:start;}".to_owned(),
+            ".milestone{fill:black;stroke-width:1;stroke:black;}".to_owned(),
+            ".marker{stroke-width:2;stroke:#888888;stroke-dasharray:7;}".to_owned(),
+            ".actual{fill:#333333;}".to_owned(),
+            ".forecast{fill-opacity:0.35;stroke-opacity:0.35;}".to_owned(),
+            ".scenario{fill:none;stroke:#333333;stroke-width:1.5;stroke-dasharray:4 2;}".to_owned(),
+            ".slip{stroke-width:2;}".to_owned(),
+            ".slip-minor{fill:#e0a020;stroke:#e0a020;}".to_owned(),
+            ".slip-moderate{fill:#e06020;stroke:#e06020;}".to_owned(),
+            ".slip-major{fill:#c00000;stroke:#c00000;}".to_owned(),
+            ".break{fill:#eeeeee;}".to_owned(),
+            ".axis-break{fill:none;stroke-width:2;stroke:#aaaaaa;stroke-linejoin:round;}".to_owned(),
+            ".risk{fill:#e0a020;stroke:#a06000;stroke-width:1;stroke-linejoin:round;}".to_owned(),
+            ".risk-mark{font-family:Arial;font-size:7pt;font-weight:bold;fill:black;text-anchor:middle;dominant-baseline:middle;}".to_owned(),
+            ".signature-line{stroke-width:1;stroke:#444444;}".to_owned(),
+            ".note-ref{font-family:Arial;font-size:8pt;font-weight:bold;fill:#444444;dominant-baseline:hanging;}".to_owned(),
+            ".note{font-family:Arial;font-size:10pt;fill:#444444;dominant-baseline:middle;}".to_owned(),
+            ".status-line{fill:none;stroke-width:2;stroke:#d03030;stroke-linejoin:round;}".to_owned(),
+            ".sprint{fill:#4a90d9;fill-opacity:0.06;}".to_owned(),
+            ".sprint-alt{fill:#4a90d9;fill-opacity:0.14;}".to_owned(),
+            ".phase{fill-opacity:0.15;}".to_owned(),
+            ".phase-name{font-family:Arial;font-size:10pt;font-weight:bold;fill:#444444;dominant-baseline:middle;text-anchor:middle;}".to_owned(),
+            ".sprint-name{font-family:Arial;font-size:10pt;fill:#4a6a8a;dominant-baseline:middle;text-anchor:middle;}".to_owned(),
+            ".capacity-used{fill:#9ccc9c;}".to_owned(),
+            ".capacity-over{fill:#e88a8a;}".to_owned(),
+            ".capacity-label{font-family:Arial;font-size:9pt;text-anchor:middle;dominant-baseline:middle;}".to_owned(),
+            ".capacity-label-over{font-weight:bold;fill:#a00000;}".to_owned(),
+            ".absence{fill:url(#absence-hatch);}".to_owned(),
+            ".absence-hatch-line{stroke:#bbbbbb;stroke-width:2;}".to_owned(),
+            ".elapsed{fill:#888888;fill-opacity:0.12;}".to_owned(),
+            ".estimate{stroke-width:1.5;stroke:#333333;fill:none;}".to_owned(),
+            ".progress{fill:black;fill-opacity:0.25;}".to_owned(),
+            ".badge-text{font-family:Arial;font-size:7pt;font-weight:bold;fill:white;text-anchor:middle;dominant-baseline:middle;}".to_owned(),
+            ".heat-strip{fill:#f4f4f4;}".to_owned(),
+            ".heat{fill:#e06020;}".to_owned(),
+            "rect.unassigned{stroke:#5c6670;stroke-dasharray:4 2;}".to_owned(),
+            ".dependency{fill:none;stroke:#666666;stroke-width:1.2;}".to_owned(),
+            ".arrow-head{fill:#666666;}".to_owned(),
+            ".arrow-head-open{fill:none;stroke:#666666;stroke-width:1.5;}".to_owned(),
+            ".dimmed{opacity:0.25;}".to_owned(),
+            ".selected .row-highlight{fill:#fff3c4;}".to_owned(),
+            ".total{font-family:Arial;font-size:9pt;font-weight:bold;text-anchor:middle;dominant-baseline:middle;}".to_owned(),
+            ".watermark{font-family:Arial;font-weight:bold;fill:#d03030;fill-opacity:0.15;text-anchor:middle;dominant-baseline:middle;pointer-events:none;}".to_owned(),
+        ]
+        .map(|style| style.replace("font-family:Arial", &format!("font-family:{}", font_family))));
+
+        styles.extend(
+            STATUS_COLORS
+                .iter()
+                .enumerate()
+                .map(|(i, color)| format!(".badge-{}{{fill:#{:06x};}}", i, color)),
+        );
+
+        if options.print_friendly {
+            for style in styles.iter_mut() {
+                *style = print::grayscale_css(style);
+            }
+
+            styles.extend([
+                ".outer-lines{stroke-width:5;stroke:#555555;}".to_owned(),
+                ".inner-lines{stroke-width:3;stroke:#999999;}".to_owned(),
+                ".sprint{fill-opacity:0.12;}".to_owned(),
+                ".sprint-alt{fill-opacity:0.25;}".to_owned(),
+                ".phase{fill-opacity:0.25;}".to_owned(),
+            ]);
+        }
+
+        // Generate random resource colors based on https://martin.ankerl.com/2009/12/09/how-to-create-random-colors-programmatically/
+        let mut rng = rand::thread_rng();
+        let mut h: f32 = rng.gen();
+        let mut resource_colors = vec![];
+        let mut color_styles = |class: &str, i: usize, rgb: u32| -> u32 {
+            // The colors are told apart by the patterns defined when rendering when printing
+            let (fill, stroke, stroke_width, rgb) = if options.print_friendly {
+                (
+                    format!("url(#{}-{}-pattern)", class, i),
+                    "#333333".to_owned(),
+                    1.5,
+                    print::shade(i),
+                )
+            } else {
+                (format!("#{:06x}", rgb), format!("#{:06x}", rgb), 1.0, rgb)
+            };
+
+            styles.push(format!(
+                ".{}-{}-closed{{fill:{};stroke-width:{};stroke:{};}}",
+                class, i, fill, stroke_width, stroke
+            ));
+            styles.push(match options.open_style {
+                OpenStyle::Outline => format!(
+                    ".{}-{}-open{{fill:none;stroke-width:2;stroke:{};}}",
+                    class, i, stroke
+                ),
+                OpenStyle::Stripes => format!(
+                    ".{0}-{1}-open{{fill:url(#{0}-{1}-stripes);stroke-width:2;stroke:{2};}}\n.{0}-{1}-stripe{{stroke-width:3;stroke:{2};}}",
+                    class, i, stroke
+                ),
+                OpenStyle::Faded => format!(
+                    ".{}-{}-open{{fill:{};fill-opacity:0.35;stroke-width:1;stroke:{};}}",
+                    class, i, fill, stroke
+                ),
+            });
+
+            rgb
+        };
+
+        for i in 0..chart_data.resources.len() {
+            resource_colors.push(color_styles(
+                "resource",
+                i,
+                GanttChartTool::hsv_to_rgb(h, 0.5, 0.5),
+            ));
+            h = (h + GOLDEN_RATIO_CONJUGATE) % 1.0;
+        }
+
+        // The items without a resource aren't colored by anything
+        if chart_data.has_unassigned() {
+            resource_colors.push(color_styles(
+                "resource",
+                chart_data.resources.len(),
+                UNASSIGNED_COLOR,
+            ));
+        }
+
+        let colors = if options.color_by == ColorBy::Resource {
+            resource_colors.clone()
+        } else {
+            key_colors
+                .into_iter()
+                .enumerate()
+                .map(|(i, rgb)| color_styles(options.color_by.class(), i, rgb))
+                .collect()
+        };
+
+        let render_data = RenderData {
+            title: Cow::Borrowed(&chart_data.title),
+            start_date,
+            end_date,
+            marked_date: chart_data
+                .marked_date
+                .map(|date| date.and_time(NaiveTime::MIN)),
+            gutter,
+            row_gutter,
+            row_height,
+            resource_gutter,
+            resource_height,
+            heading_height,
+            heading_angle,
+            styles,
+            labels: &chart_data.labels,
+            source_hash: chart_data.source_hash.as_deref(),
+            resource_colors,
+            color_class: options.color_by.class(),
+            color_names,
+            colors,
+            sprints,
+            phases,
+            title_width,
+            badge_width,
+            assignees,
+            assignee_width,
+            data_columns,
+            marked_date_offset,
+            rect_corner_radius: 3.0,
+            cols,
+            rows,
+            resources: (0..chart_data.item_resources().count())
+                .map(|i| Cow::Borrowed(chart_data.resource_name(i)))
+                .collect(),
+            unassigned_index: chart_data.resources.len(),
+            absences,
+            capacity,
+            heat,
+            totals,
+            totals_label: match options.totals {
+                Some(Totals::Effort) => &chart_data.labels.person_days,
+                _ => &chart_data.labels.active_tasks,
+            },
+            risks,
+            sign_off: &chart_data.sign_off,
+        };
+
+        if options.strict {
+            verify_layout(&render_data)?;
+        }
+
+        Ok(render_data)
+    }
+
+    fn data_column_heading(labels: &LabelData, column: DataColumn) -> &str {
+        match column {
+            DataColumn::Start => &labels.start,
+            DataColumn::End => &labels.end,
+            DataColumn::Duration => &labels.duration,
+            DataColumn::Resource => &labels.resource,
+        }
+    }
+
+    // The duration in days, rounded to hundredths for durations given in milliseconds
+    fn duration_text(
+        chart_data: &ChartData,
+        item: &ItemData,
+        round_durations: Option<RoundDurations>,
+    ) -> String {
+        let days = item
+            .duration
+            .or_else(|| {
+                item.duration_ms
+                    .map(|ms| (ms as f32 / SECONDS_PER_DAY / 10.0).round() / 100.0)
+            })
+            .unwrap_or(0.0);
+
+        days_text(days, round_durations, chart_data.working_days_per_week())
+    }
+
+    // The percent of the working days from the start to the end that are before the status date
+    fn elapsed_percent(
+        chart_data: &ChartData,
+        start_date: NaiveDateTime,
+        end_date: NaiveDateTime,
+        status_date: NaiveDateTime,
+    ) -> f32 {
+        let working_days = |from: NaiveDateTime, to: NaiveDateTime| {
+            (0..(to - from).num_days().max(0))
+                .filter(|&d| chart_data.is_working_day((from + Duration::days(d)).date()))
+                .count() as f32
+        };
+        let total_days = working_days(start_date, end_date);
+
+        if total_days == 0.0 {
+            return if status_date >= end_date { 100.0 } else { 0.0 };
+        }
+
+        (working_days(start_date, status_date.min(end_date)) / total_days * 100.0).round()
+    }
+
+    // Set the color index of each row from what the bars are colored by, giving the names of the
+    // colors and, unless they are the resource colors, the colors themselves
+    fn color_rows<'c>(
+        color_by: ColorBy,
+        chart_data: &'c ChartData,
+        rows: &mut [RowRenderData],
+    ) -> (Vec<Cow<'c, str>>, Vec<u32>) {
+        let labels = &chart_data.labels;
+        // The color of the items without a priority or phase
+        let other_color = 0xb0b0b0;
+
+        match color_by {
+            ColorBy::Resource => (
+                (0..chart_data.item_resources().count())
+                    .map(|i| Cow::Borrowed(chart_data.resource_name(i)))
+                    .collect(),
+                vec![],
+            ),
+            ColorBy::Status => {
+                for (item, row) in chart_data.items.iter().zip(rows.iter_mut()) {
+                    let percent_complete = row.percent_complete.unwrap_or(0.0);
+
+                    row.color_index = if percent_complete >= 100.0 || item.actual_finish.is_some() {
+                        2
+                    } else if percent_complete > 0.0 || item.actual_start.is_some() {
+                        1
+                    } else {
+                        0
+                    };
+                }
+
+                (
+                    vec![
+                        Cow::Borrowed(labels.not_started.as_str()),
+                        Cow::Borrowed(labels.in_progress.as_str()),
+                        Cow::Borrowed(labels.done.as_str()),
+                    ],
+                    STATUS_COLORS.to_vec(),
+                )
+            }
+            ColorBy::Priority => {
+                let mut names: Vec<Cow<str>> = vec![];
+                let mut colors = vec![];
+
+                for (item, row) in chart_data.items.iter().zip(rows.iter_mut()) {
+                    let name = item.priority.as_deref().unwrap_or(&labels.other);
+
+                    row.color_index = match names.iter().position(|other| other == name) {
+                        Some(j) => j,
+                        None => {
+                            names.push(Cow::Borrowed(name));
+                            colors.push(if item.priority.is_some() {
+                                GanttChartTool::hsv_to_rgb(
+                                    (colors.len() as f32 * GOLDEN_RATIO_CONJUGATE) % 1.0,
+                                    0.5,
+                                    0.5,
+                                )
+                            } else {
+                                other_color
+                            });
+                            names.len() - 1
+                        }
+                    };
+                }
+
+                (names, colors)
+            }
+            ColorBy::Phase => {
+                let mut names: Vec<Cow<str>> = chart_data
+                    .phases
+                    .iter()
+                    .map(|phase| Cow::Borrowed(phase.name.as_str()))
+                    .collect();
+                // Only colors given as #rrggbb can be used, as they are also needed for other formats
+                let mut colors: Vec<u32> = chart_data
+                    .phases
+                    .iter()
+                    .enumerate()
+                    .map(|(i, phase)| {
+                        phase
+                            .color
+                            .as_deref()
+                            .and_then(Self::hex_color)
+                            .or_else(|| Self::hex_color(PHASE_COLORS[i % PHASE_COLORS.len()]))
+                            .unwrap_or(other_color)
+                    })
+                    .collect();
+                let mut has_other = false;
+
+                for row in rows.iter_mut() {
+                    row.color_index = match chart_data.phases.iter().position(|phase| {
+                        phase.start <= row.start_date.date() && row.start_date.date() <= phase.end
+                    }) {
+                        Some(j) => j,
+                        None => {
+                            has_other = true;
+                            chart_data.phases.len()
+                        }
+                    };
+                }
+
+                if has_other {
+                    names.push(Cow::Borrowed(labels.other.as_str()));
+                    colors.push(other_color);
+                }
+
+                (names, colors)
+            }
+        }
+    }
+
+    // Items without a resource are on an unassigned one after the chart's resources
+    fn resource_index(chart_data: &ChartData, item: &ItemData) -> usize {
+        item.resource_index.unwrap_or(chart_data.resources.len())
+    }
+
+    // A CSS color given as #rrggbb
+    fn hex_color(color: &str) -> Option<u32> {
+        color
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+    }
+
+    // The rows in an order where each comes after its predecessors, with the rows that depend on a row
+    // following straight after it where they can
+    fn topological_order(rows: &[RowRenderData]) -> Vec<usize> {
+        let mut successors: Vec<Vec<usize>> = vec![vec![]; rows.len()];
+        let mut waiting: Vec<usize> = vec![0; rows.len()];
+
+        for (i, row) in rows.iter().enumerate() {
+            for &j in &row.predecessors {
+                successors[j].push(i);
+                waiting[i] += 1;
+            }
+        }
+
+        // A stack of the rows whose predecessors are all placed, with the earliest on top
+        let mut ready: Vec<usize> = (0..rows.len()).rev().filter(|&i| waiting[i] == 0).collect();
+        let mut order = Vec::with_capacity(rows.len());
+
+        while let Some(i) = ready.pop() {
+            order.push(i);
+
+            for &j in successors[i].iter().rev() {
+                waiting[j] -= 1;
+
+                if waiting[j] == 0 {
+                    ready.push(j);
+                }
+            }
+        }
+
+        // Rows in a cycle, which summary rows can be in, keep their order at the end
+        if order.len() < rows.len() {
+            let mut is_placed = vec![false; rows.len()];
+
+            for &i in &order {
+                is_placed[i] = true;
+            }
+
+            order.extend((0..rows.len()).filter(|&i| !is_placed[i]));
+        }
+
+        order
+    }
+
+    // Put the rows in a new order, given as the index each row was at before
+    fn reorder_rows(
+        rows: &mut Vec<RowRenderData>,
+        data_columns: &mut [DataColumnRenderData],
+        order: &[usize],
+    ) {
+        let mut row_indices = vec![0; rows.len()];
+
+        for (new_index, &i) in order.iter().enumerate() {
+            row_indices[i] = new_index;
+        }
+
+        for data_column in data_columns.iter_mut() {
+            let mut cells: Vec<Option<String>> = mem::take(&mut data_column.cells)
+                .into_iter()
+                .map(Some)
+                .collect();
+
+            data_column.cells = order.iter().filter_map(|&i| cells[i].take()).collect();
+        }
+
+        let mut old_rows: Vec<Option<RowRenderData>> =
+            mem::take(rows).into_iter().map(Some).collect();
+
+        *rows = order.iter().filter_map(|&i| old_rows[i].take()).collect();
+
+        for row in rows.iter_mut() {
+            for predecessor in row.predecessors.iter_mut() {
+                *predecessor = row_indices[*predecessor];
+            }
+        }
+    }
+
+    // Collapse the rows with the same key into a summary row spanning them, in place of the first
+    // of them. Rows without a key are left as they are.
+    fn rollup_rows<'c>(
+        rows: &[RowRenderData<'c>],
+        keys: &[Option<&'c str>],
+        columns: &[DataColumn],
+        data_columns: &mut [DataColumnRenderData],
+        chart_data: &ChartData,
+        round_durations: Option<RoundDurations>,
+        aggregate: bool,
+    ) -> Vec<RowRenderData<'c>> {
+        let mut summary_indices: HashMap<&str, usize> = HashMap::new();
+        let mut row_indices = Vec::with_capacity(rows.len());
+        let mut members: Vec<Vec<usize>> = vec![];
+
+        for (i, key) in keys.iter().enumerate() {
+            let new_index = match key {
+                Some(key) => *summary_indices.entry(key).or_insert_with(|| {
+                    members.push(vec![]);
+                    members.len() - 1
+                }),
+                None => {
+                    members.push(vec![]);
+                    members.len() - 1
+                }
+            };
+
+            members[new_index].push(i);
+            row_indices.push(new_index);
+        }
+
+        let mut new_rows = Vec::with_capacity(members.len());
+        let mut new_cells: Vec<Vec<String>> = vec![vec![]; data_columns.len()];
+
+        for (new_index, members) in members.iter().enumerate() {
+            let first = &rows[members[0]];
+            let mut predecessors: Vec<usize> = members
+                .iter()
+                .flat_map(|&i| rows[i].predecessors.iter().map(|&j| row_indices[j]))
+                .filter(|&j| j != new_index)
+                .collect();
+
+            predecessors.sort_unstable();
+            predecessors.dedup();
+
+            let key = match keys[members[0]] {
+                Some(key) => key,
+                None => {
+                    for (cells, data_column) in new_cells.iter_mut().zip(data_columns.iter()) {
+                        cells.push(data_column.cells[members[0]].clone());
+                    }
+
+                    new_rows.push(RowRenderData {
+                        predecessors,
+                        ..first.clone()
+                    });
+                    continue;
+                }
+            };
+
+            let member_rows = || members.iter().map(|&i| &rows[i]);
+            let start_date = member_rows().map(|row| row.start_date).min();
+            let end_date = member_rows()
+                .map(|row| row.end_date.unwrap_or(row.start_date))
+                .max();
+            let (start_date, end_date) = match (start_date, end_date) {
+                (Some(start_date), Some(end_date)) => (start_date, end_date),
+                _ => continue,
+            };
+            let offset = member_rows()
+                .map(|row| row.offset)
+                .fold(f32::INFINITY, f32::min);
+            let end_offset = member_rows()
+                .map(|row| row.offset + row.length.unwrap_or(0.0))
+                .fold(f32::NEG_INFINITY, f32::max);
+            // Only a group of milestones on the same day is a milestone itself
+            let is_milestone =
+                member_rows().all(|row| row.length.is_none()) && start_date == end_date;
+            let resource_index = first.resource_index;
+            // The members' progress weighted by their durations, if any member has a percent complete. Members
+            // without one are complete if they ended by the marked date and aren't open, and not started otherwise.
+            let percent_complete = if member_rows().any(|row| row.percent_complete.is_some()) {
+                let (done_days, days) = member_rows()
+                    .filter_map(|row| {
+                        let end_date = row.end_date?;
+                        let days =
+                            (end_date - row.start_date).num_seconds() as f32 / SECONDS_PER_DAY;
+                        let ended = !row.open
+                            && chart_data
+                                .marked_date
+                                .is_some_and(|date| end_date <= date.and_time(NaiveTime::MIN));
+                        let percent_complete =
+                            row.percent_complete
+                                .unwrap_or(if ended { 100.0 } else { 0.0 });
+
+                        Some((days * percent_complete / 100.0, days))
+                    })
+                    .fold((0.0, 0.0), |(done_total, total), (done_days, days)| {
+                        (done_total + done_days, total + days)
+                    });
+
+                (days > 0.0).then(|| done_days / days * 100.0)
+            } else {
+                None
+            };
+
+            for (cells, column) in new_cells.iter_mut().zip(columns.iter()) {
+                cells.push(match column {
+                    DataColumn::Start => start_date.format("%Y-%m-%d").to_string(),
+                    DataColumn::End => (if is_milestone {
+                        end_date
+                    } else {
+                        end_date - Duration::seconds(1)
+                    })
+                    .format("%Y-%m-%d")
+                    .to_string(),
+                    // The calendar days spanned, as the tasks can overlap
+                    DataColumn::Duration => days_text(
+                        ((end_date - start_date).num_seconds() as f32 / SECONDS_PER_DAY * 100.0)
+                            .round()
+                            / 100.0,
+                        round_durations,
+                        7.0,
+                    ),
+                    DataColumn::Resource => {
+                        if member_rows().all(|row| row.resource_index == resource_index) {
+                            chart_data.resource_name(resource_index).to_owned()
+                        } else {
+                            String::new()
+                        }
+                    }
+                });
+            }
+
+            new_rows.push(RowRenderData {
+                title: Cow::Borrowed(key),
+                start_date,
+                end_date: if is_milestone { None } else { Some(end_date) },
+                resource_index,
+                color_index: first.color_index,
+                offset,
+                length: if is_milestone {
+                    None
+                } else {
+                    Some(end_offset - offset)
+                },
+                open: member_rows().all(|row| row.open),
+                predecessors,
+                estimate: None,
+                classes: &[],
+                percent_complete,
+                segments: if aggregate {
+                    let mut bars: Vec<(f32, f32)> =
+                        member_rows().flat_map(|row| row.bars()).collect();
+
+                    bars.sort_by(|a, b| a.0.total_cmp(&b.0));
+                    bars
+                } else {
+                    vec![]
+                },
+                baseline_offset: None,
+                slip_days: None,
+                actual: None,
+                forecast: None,
+                scenario: None,
+                note: None,
+                at_risk: member_rows().any(|row| row.at_risk),
+                aggregated: aggregate,
+                group: first
+                    .group
+                    .filter(|&group| member_rows().all(|row| row.group == Some(group))),
+                highlighted: member_rows().any(|row| row.highlighted),
+                selected: member_rows().any(|row| row.selected),
+            });
+        }
+
+        for (data_column, cells) in data_columns.iter_mut().zip(new_cells) {
+            data_column.cells = cells;
+        }
+
+        new_rows
+    }
+
+    // Keep the rows, given with their index, that are to be shown, with their data column cells and the
+    // dependencies between them
+    fn retain_rows(
+        rows: &mut Vec<RowRenderData>,
+        data_columns: &mut [DataColumnRenderData],
+        is_shown: impl Fn(usize, &RowRenderData) -> bool,
+    ) {
+        let mut row_indices = vec![None; rows.len()];
+        let mut num_rows = 0;
+
+        for (i, row) in rows.iter().enumerate() {
+            if is_shown(i, row) {
+                row_indices[i] = Some(num_rows);
+                num_rows += 1;
+            }
+        }
+
+        for data_column in data_columns.iter_mut() {
+            data_column.cells = mem::take(&mut data_column.cells)
+                .into_iter()
+                .zip(row_indices.iter())
+                .filter_map(|(cell, row_index)| row_index.map(|_| cell))
+                .collect();
+        }
+
+        let mut i = 0;
+
+        rows.retain(|_| {
+            i += 1;
+            row_indices[i - 1].is_some()
+        });
+
+        for row in rows.iter_mut() {
+            row.predecessors = row
+                .predecessors
+                .iter()
+                .filter_map(|&predecessor| row_indices[predecessor])
+                .collect();
+        }
+    }
+
+    // The working days of a task, and the person-days of its effort spread evenly over them
+    fn daily_effort(
+        chart_data: &ChartData,
+        item: &ItemData,
+        row: &RowRenderData,
+    ) -> Option<(Vec<NaiveDateTime>, f32)> {
+        let end_date = row.end_date?;
+        let mut working_dates = vec![];
+        let mut date = row.start_date;
+
+        while date < end_date {
+            if chart_data.is_working_day(date.date()) {
+                working_dates.push(date);
+            }
+
+            date += Duration::days(1);
+        }
+
+        if working_dates.is_empty() {
+            return None;
+        }
+
+        // Work shorter than a day is only part of its one working day
+        let effort = item.effort.map_or_else(
+            || (working_dates.len() as f32).min(item.duration.map_or(f32::MAX, clamp_days)),
+            clamp_days,
+        );
+        let daily_effort = effort / working_dates.len() as f32;
+
+        Some((working_dates, daily_effort))
+    }
+
+    // The tasks on at some time in each column, or the person-days of work planned in it
+    fn calc_totals<'r, 'c: 'r>(
+        chart_data: &ChartData,
+        items_rows: impl Iterator<Item = (&'r ItemData, &'r RowRenderData<'c>)>,
+        totals: Totals,
+        cols: &[ColumnRenderData],
+        col_start_dates: &[NaiveDateTime],
+        col_end_dates: &[NaiveDateTime],
+    ) -> Vec<f32> {
+        let mut cells = vec![0.0; cols.len()];
+
+        for (item, row) in items_rows {
+            match totals {
+                Totals::Tasks => {
+                    let end_date = match row.end_date {
+                        Some(end_date) => end_date,
+                        None => continue,
+                    };
+
+                    for (i, cell) in cells.iter_mut().enumerate() {
+                        if row.start_date < col_end_dates[i] && col_start_dates[i] < end_date {
+                            *cell += 1.0;
+                        }
+                    }
+                }
+                Totals::Effort => {
+                    if let Some((working_dates, daily_effort)) =
+                        Self::daily_effort(chart_data, item, row)
+                    {
+                        for date in working_dates {
+                            if let Some(i) = Self::col_index(col_start_dates, col_end_dates, date) {
+                                cells[i] += daily_effort;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Nothing is worked on in a break
+        for (cell, col) in cells.iter_mut().zip(cols) {
+            if col.is_break {
+                *cell = 0.0;
+            }
+        }
+
+        cells
+    }
+
+    // The index of the column a date falls in, or none if it is in a month left out of the chart
+    fn col_index(
+        col_start_dates: &[NaiveDateTime],
+        col_end_dates: &[NaiveDateTime],
+        date: NaiveDateTime,
+    ) -> Option<usize> {
+        let i = col_start_dates
+            .partition_point(|&start| start <= date)
+            .checked_sub(1)?;
+
+        (date < col_end_dates[i]).then_some(i)
+    }
+
+    // Work out the demand and capacity of each resource for each column. The effort
+    // of each task is spread evenly over its working days.
+    fn calc_capacity(
+        chart_data: &ChartData,
+        rows: &[RowRenderData],
+        col_start_dates: &[NaiveDateTime],
+        col_end_dates: &[NaiveDateTime],
+    ) -> Vec<CapacityRenderData> {
+        let is_working_day = |date: NaiveDateTime| chart_data.is_working_day(date.date());
+        let mut capacity: Vec<CapacityRenderData> = chart_data
+            .resources
+            .iter()
+            .enumerate()
+            .map(|(resource_index, resource)| {
+                let fte = resource.capacity.unwrap_or(1.0);
+                let cells = col_start_dates
+                    .iter()
+                    .zip(col_end_dates)
+                    .map(|(&col_start_date, &col_end_date)| {
+                        let mut working_days = 0;
+                        let mut date = col_start_date;
+
+                        while date < col_end_date {
+                            if is_working_day(date) {
+                                working_days += 1;
+                            }
+
+                            date += Duration::days(1);
+                        }
+
+                        (0.0, fte * working_days as f32)
+                    })
+                    .collect();
+
+                CapacityRenderData {
+                    resource_index,
+                    cells,
+                }
+            })
+            .collect();
+
+        for (item, row) in chart_data.items.iter().zip(rows.iter()) {
+            // No one's capacity is taken up by the items without a resource
+            let (Some((working_dates, daily_effort)), Some(resource_capacity)) = (
+                Self::daily_effort(chart_data, item, row),
+                capacity.get_mut(row.resource_index),
+            ) else {
+                continue;
+            };
+            let cells = &mut resource_capacity.cells;
+
+            for date in working_dates {
+                if let Some(i) = Self::col_index(col_start_dates, col_end_dates, date) {
+                    cells[i].0 += daily_effort;
+                }
+            }
+        }
+
+        capacity
+    }
+
+    // The working days of the tasks in each column, leaving out breaks
+    fn calc_heat<'r, 'c: 'r>(
+        chart_data: &ChartData,
+        rows: impl Iterator<Item = &'r RowRenderData<'c>>,
+        cols: &[ColumnRenderData],
+        col_start_dates: &[NaiveDateTime],
+        col_end_dates: &[NaiveDateTime],
+    ) -> Vec<u32> {
+        let mut heat = vec![0; cols.len()];
+
+        for row in rows {
+            let end_date = match row.end_date {
+                Some(end_date) => end_date,
+                None => continue,
+            };
+            let mut date = row.start_date;
+
+            while date < end_date {
+                if let Some(i) = Self::col_index(col_start_dates, col_end_dates, date) {
+                    if chart_data.is_working_day(date.date()) && !cols[i].is_break {
+                        heat[i] += 1;
+                    }
+                }
+
+                date += Duration::days(1);
+            }
+        }
+
+        heat
+    }
+
+    // Find a cycle of dependencies reachable from an item, as the items around it with the first
+    // repeated at the end
+    fn dependency_cycle(chart_data: &ChartData, index: usize) -> Option<Vec<usize>> {
+        let item_ids: HashMap<&str, usize> = chart_data
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| item.id.as_deref().map(|id| (id, i)))
+            .collect();
+        let mut done = vec![false; chart_data.items.len()];
+        // The path of items being followed, and how many of the dependencies of each have been
+        let mut path: Vec<(usize, usize)> = vec![(index, 0)];
+
+        while let Some(&(i, next)) = path.last() {
+            let depends_on = chart_data.items[i].depends_on.as_deref().unwrap_or(&[]);
+
+            match depends_on.get(next) {
+                Some(id) => {
+                    let last = path.len() - 1;
+
+                    path[last].1 += 1;
+
+                    let j = match item_ids.get(id.as_str()) {
+                        Some(&j) if !done[j] => j,
+                        _ => continue,
+                    };
+
+                    if let Some(start) = path.iter().position(|&(k, _)| k == j) {
+                        return Some(path[start..].iter().map(|&(k, _)| k).chain([j]).collect());
+                    }
+
+                    path.push((j, 0));
+                }
+                None => {
+                    done[i] = true;
+                    path.pop();
+                }
+            }
+        }
+
+        None
+    }
+
+    // Find the items that depend on nothing and that nothing depends on, in a chart with dependencies
+    fn unconnected_items(chart_data: &ChartData) -> Vec<usize> {
+        let items = &chart_data.items;
+
+        if items.iter().all(|item| item.depends_on.is_none()) {
+            return vec![];
+        }
+
+        let depended_on: HashSet<&str> = items
+            .iter()
+            .flat_map(|item| item.depends_on.iter().flatten())
+            .map(String::as_str)
+            .collect();
+        // Items without a start date or dependencies are scheduled after the previous item
+        let follows_previous =
+            |item: &ItemData| item.start().is_none() && item.depends_on.is_none();
+
+        (0..items.len())
+            .filter(|&i| {
+                let item = &items[i];
+
+                item.depends_on.as_ref().is_none_or(|ids| ids.is_empty())
+                    && (i == 0 || !follows_previous(item))
+                    && !item
+                        .id
+                        .as_deref()
+                        .is_some_and(|id| depended_on.contains(id))
+                    && !items.get(i + 1).is_some_and(follows_previous)
+            })
+            .collect()
+    }
+
+    // Find all the rows connected to a row through its predecessors or successors
+    // The `visited` flags are shared between calls to save reallocating them for every row, and are left all false
+    fn dependency_chain(
+        rows: &[RowRenderData],
+        successors: &[Vec<usize>],
+        index: usize,
+        visited: &mut [bool],
+    ) -> Vec<usize> {
+        let mut chain = vec![index];
+        let mut stack = vec![index];
+
+        visited[index] = true;
+
+        while let Some(i) = stack.pop() {
+            for &j in &rows[i].predecessors {
+                if !visited[j] {
+                    visited[j] = true;
+                    chain.push(j);
+                    stack.push(j);
+                }
+            }
+        }
+
+        stack.push(index);
+
+        while let Some(i) = stack.pop() {
+            for &j in &successors[i] {
+                if !visited[j] {
+                    visited[j] = true;
+                    chain.push(j);
+                    stack.push(j);
+                }
+            }
+        }
+
+        for &i in &chain {
+            visited[i] = false;
+        }
+
+        chain.sort_unstable();
+        chain
+    }
+
+    // Render a table across the chart below `top`, with the columns given shares of the width and
+    // a row of headings
+    fn render_table<const N: usize>(
+        rd: &RenderData,
+        text_measure: &TextMeasure,
+        top: f32,
+        shares: &[f32; N],
+        headings: &[&str; N],
+        rows: &[[&str; N]],
+    ) -> element::Group {
+        let mut table = element::Group::new();
+        let width = rd.width();
+        let table_width = width - rd.gutter.width();
+
+        for i in 0..=rows.len() {
+            let y = top + rd.resource_gutter.top + i as f32 * rd.row_height;
+            let mut x = rd.gutter.left;
+
+            for (j, share) in shares.iter().enumerate() {
+                let column_width = table_width * share;
+                let (class, text) = match i {
+                    0 => ("data-heading", headings[j]),
+                    _ => ("data-cell", rows[i - 1][j]),
+                };
+
+                table.append(
+                    element::Text::new(text_measure.truncate(
+                        text,
+                        DATA_FONT_SIZE,
+                        column_width - rd.row_gutter.width(),
+                    ))
+                    .set("class", class)
+                    .set("x", x + rd.row_gutter.left)
+                    .set("y", y + rd.row_height / 2.0),
+                );
+                x += column_width;
+            }
+
+            table.append(
+                element::Line::new()
+                    .set(
+                        "class",
+                        if i == rows.len() {
+                            "outer-lines"
+                        } else {
+                            "inner-lines"
+                        },
+                    )
+                    .set("x1", rd.gutter.left)
+                    .set("y1", y + rd.row_height)
+                    .set("x2", width - rd.gutter.right)
+                    .set("y2", y + rd.row_height),
+            );
+        }
+
+        table
+    }
+
+    // Render the chart as SVG into a string, for the formats that need all of it at once, along with the number of
+    // elements in it
+    fn render_chart(
+        &self,
+        options: &RenderOptions,
+        rd: &RenderData,
+    ) -> Result<(String, usize), GanttError> {
+        let mut buffer = vec![];
+        let elements = self.write_chart_svg(options, rd, None, &mut buffer)?;
+        let svg = String::from_utf8(buffer)
+            .map_err(|e| GanttError::IoError(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+
+        Ok((svg, elements))
+    }
+
+    // Write the chart as SVG a node at a time, or only the part of it between two X offsets if it is cropped,
+    // returning the number of elements written
+    fn write_chart_svg(
+        &self,
+        options: &RenderOptions,
+        rd: &RenderData,
+        crop: Option<(f32, f32)>,
+        writer: impl Write,
+    ) -> Result<usize, GanttError> {
+        let add_resource_table = options.add_resource_table;
+        let interactive = options.format == OutputFormat::Html;
+        // The index of an HTML chart links to where the phases and groups start
+        let (phase_ids, row_ids) = if interactive {
+            rd.section_ids()
+        } else {
+            (vec![None; rd.phases.len()], vec![None; rd.rows.len()])
+        };
+        let text_measure = TextMeasure::new(options.embed_font.as_deref());
+        let max_title_width = rd.titles_end() - rd.gutter.left - rd.row_gutter.width();
+        let width: f32 = rd.width();
+        let height = rd.height(add_resource_table);
+        let (left, right) = crop.unwrap_or((0.0, width));
+
+        let mut document = Document::new()
+            .set("viewBox", (left, 0, right - left, height))
+            .set("xmlns", "http://www.w3.org/2000/svg")
+            .set("style", "background-color: white;");
+
+        // Other formats need the size of the chart
+        if !(options.fragment && options.format == OutputFormat::Svg) {
+            document = document.set("width", right - left).set("height", height);
+        }
+
+        if let Some(ref preserve_aspect_ratio) = options.preserve_aspect_ratio {
+            document = document.set("preserveAspectRatio", preserve_aspect_ratio.as_str());
+        }
+
+        // Where the chart came from, so it can be traced back to the revision of the chart file that made it
+        let mut provenance = element::Element::new("gantt:provenance");
+        let mut metadata = element::Element::new("metadata");
+
+        provenance.assign("xmlns:gantt", PROVENANCE_NAMESPACE);
+        provenance.assign(
+            "generator",
+            format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        );
+        // Only when asked for, so that rendering a chart again gives the same output
+        if options.timestamp {
+            provenance.assign(
+                "generated",
+                generated_time().to_rfc3339_opts(SecondsFormat::Secs, true),
+            );
+        }
+
+        if let Some(source_hash) = rd.source_hash {
+            provenance.assign("source-hash", source_hash);
+        }
+
+        metadata.append(provenance);
+
+        let mut svg = SvgWriter::new(writer);
+
+        svg.start(document);
+        svg.write(metadata)?;
+
+        let style = element::Style::new(rd.styles.join("\n"));
+        let mut definitions = element::Definitions::new();
+
+        if let Some(arrows) = options.arrows {
+            let head: Box<dyn Node> = match arrows {
+                ArrowHead::Triangle => Box::new(
+                    element::Path::new()
+                        .set("class", "arrow-head")
+                        .set("d", "M0,0 L10,5 L0,10 z"),
+                ),
+                ArrowHead::Open => Box::new(
+                    element::Path::new()
+                        .set("class", "arrow-head-open")
+                        .set("d", "M1,1 L9,5 L1,9"),
+                ),
+                ArrowHead::Dot => Box::new(
+                    element::Circle::new()
+                        .set("class", "arrow-head")
+                        .set("cx", 5)
+                        .set("cy", 5)
+                        .set("r", 4),
+                ),
+            };
+
+            definitions.append(
+                element::Marker::new()
+                    .set("id", "arrow-head")
+                    .set("viewBox", "0 0 10 10")
+                    .set("refX", 9)
+                    .set("refY", 5)
+                    .set("markerWidth", 7)
+                    .set("markerHeight", 7)
+                    .set("markerUnits", "userSpaceOnUse")
+                    .set("orient", "auto")
+                    .add(head),
+            );
+        }
+
+        definitions.append(
+            element::Pattern::new()
+                .set("id", "absence-hatch")
+                .set("width", 6)
+                .set("height", 6)
+                .set("patternUnits", "userSpaceOnUse")
+                .set("patternTransform", "rotate(45)")
+                .add(
+                    element::Line::new()
+                        .set("class", "absence-hatch-line")
+                        .set("x1", 0)
+                        .set("y1", 0)
+                        .set("x2", 0)
+                        .set("y2", 6),
+                ),
+        );
+
+        if options.print_friendly {
+            for i in 0..rd.colors.len() {
+                definitions.append(print::pattern(rd.color_class, i));
+            }
+        }
+
+        if options.open_style == OpenStyle::Stripes {
+            for i in 0..rd.colors.len() {
+                definitions.append(stripe_pattern(
+                    &format!("{}-{}-stripes", rd.color_class, i),
+                    &format!("{}-{}-stripe", rd.color_class, i),
+                ));
+            }
+        }
+
+        svg.write(style)?;
+        svg.write(definitions)?;
+
+        let col_offsets = rd.col_offsets();
+        let milestone_half_width = (rd.row_height - rd.row_gutter.height()) / 2.0;
+        // Compact titles go above the start of their bar, moved left if need be to stay in the chart,
+        // given as the title and the X offsets of its ends
+        let compact_title = |row: &RowRenderData| -> (String, f32, f32) {
+            let font_size = COMPACT_FONT_SIZES.2;
+            let title = text_measure.truncate(&row.title, font_size, width - rd.gutter.width());
+            let title_width = text_measure.width(&title, font_size);
+            let start = match row.length {
+                Some(_) => row.offset,
+                None => row.offset - milestone_half_width,
+            };
+            let x = start
+                .min(width - rd.gutter.right - title_width)
+                .max(rd.gutter.left);
+
+            (title.into_owned(), x, x + title_width)
+        };
+        let mut successors: Vec<Vec<usize>> = vec![vec![]; rd.rows.len()];
+
+        for (i, row) in rd.rows.iter().enumerate() {
+            for &j in &row.predecessors {
+                successors[j].push(i);
+            }
+        }
+        // The number of each row's note, if it has one, worked out once rather than for every row
+        let mut note_numbers: Vec<Option<usize>> = vec![None; rd.rows.len()];
+
+        for (number, (i, _)) in rd.notes().enumerate() {
+            note_numbers[i] = Some(number);
+        }
+
+        // The open and closed bar classes of each color, made once rather than for every row
+        let bar_classes: Vec<[String; 2]> = (0..rd
+            .rows
+            .iter()
+            .map(|row| row.color_index + 1)
+            .max()
+            .unwrap_or(0))
+            .map(|i| {
+                [
+                    format!("{}-{}-open", rd.color_class, i),
+                    format!("{}-{}-closed", rd.color_class, i),
+                ]
+            })
+            .collect();
+
+        // Render all the charts columns
+        let mut columns = element::Group::new();
+
+        for (i, &x) in col_offsets.iter().enumerate() {
+            columns.append(
+                element::Line::new()
+                    .set("class", "inner-lines")
+                    .set("x1", x)
+                    .set("y1", rd.gutter.top)
+                    .set("x2", x)
+                    .set(
+                        "y2",
+                        rd.gutter.top
+                            + ((rd.rows.len() as f32) * rd.row_height)
+                            + rd.totals_height(),
+                    ),
+            );
+
+            // A pair of zigzags across the headings where empty columns were left out, or through breaks
+            let zigzag_x = match rd.cols.get(i) {
+                Some(col) if col.is_break => {
+                    columns.append(
+                        element::Rectangle::new()
+                            .set("class", "break")
+                            .set("x", x)
+                            .set("y", rd.gutter.top)
+                            .set("width", col.width)
+                            .set("height", (rd.rows.len() as f32) * rd.row_height),
+                    );
+
+                    Some(x + col.width / 2.0)
+                }
+                Some(col) if col.gap_before => Some(x),
+                _ => None,
+            };
+
+            if let Some(zigzag_x) = zigzag_x {
+                let top = rd.header_bottom() - rd.row_height;
+                let steps = 4;
+                let step = rd.row_height / steps as f32;
+
+                for side in [-ZIGZAG_GAP, ZIGZAG_GAP] {
+                    let left = zigzag_x + side - ZIGZAG_WIDTH / 2.0;
+                    let mut data = Data::new().move_to((left, top));
+
+                    for j in 1..=steps {
+                        let zig = if j % 2 == 1 { ZIGZAG_WIDTH } else { 0.0 };
+
+                        data = data.line_to((left + zig, top + j as f32 * step));
+                    }
+
+                    columns.append(
+                        element::Path::new()
+                            .set("class", "axis-break")
+                            .set("d", data),
+                    );
+                }
+            }
+
+            if i < rd.cols.len() {
+                let heading = element::Text::new(&rd.cols[i].name)
+                    .set("data-col", i)
+                    .set("x", x + rd.cols[i].width / 2.0);
+
+                columns.append(match rd.heading_angle {
+                    // Turned headings start from the middle of the bottom of their column's heading
+                    Some(angle) => {
+                        let y = rd.header_bottom()
+                            - rd.row_gutter.bottom
+                            - (rd.row_height - rd.row_gutter.height()) / 2.0
+                                * angle.to_radians().cos();
+
+                        heading
+                            .set("class", "heading turned-heading")
+                            .set("y", y)
+                            .set(
+                                "transform",
+                                format!("rotate({} {} {})", -angle, x + rd.cols[i].width / 2.0, y),
+                            )
+                    }
+                    None => heading.set("class", "heading").set(
+                        "y",
+                        // TODO(john): Use a more appropriate row height value here?
+                        rd.header_bottom() - rd.row_gutter.bottom - rd.row_height / 2.0,
+                    ),
+                });
+            }
+        }
+
+        let mut data_columns = element::Group::new();
+
+        for data_column in &rd.data_columns {
+            data_columns.append(
+                element::Text::new(data_column.heading)
+                    .set("class", "data-heading")
+                    .set("x", data_column.offset + rd.row_gutter.left)
+                    .set(
+                        "y",
+                        rd.header_bottom() - rd.row_gutter.bottom - rd.row_height / 2.0,
+                    ),
+            );
+            data_columns.append(
+                element::Line::new()
+                    .set("class", "inner-lines")
+                    .set("x1", data_column.offset)
+                    .set("y1", rd.header_bottom() - rd.row_height)
+                    .set("x2", data_column.offset)
+                    .set("y2", rd.gutter.top + (rd.rows.len() as f32) * rd.row_height),
+            );
+        }
+
+        // Compact charts don't have a title column to head
+        let tasks: Box<dyn Node> = if options.compact {
+            Box::new(element::Group::new())
+        } else {
+            Box::new(
+                element::Text::new(rd.labels.tasks.as_str())
+                    .set("class", "heading task-heading")
+                    .set("x", rd.gutter.left + rd.row_gutter.left)
+                    .set(
+                        "y",
+                        rd.header_bottom() - rd.row_gutter.bottom - rd.row_height / 2.0,
+                    ),
+            )
+        };
+
+        let title = element::Text::new(rd.title.as_ref())
+            .set("class", "title")
+            .set("x", rd.gutter.left)
+            // TODO(john): Use more appropriate row height value here?
+            .set("y", if options.compact { 18.0 } else { 25.0 });
+
+        let marker: Box<dyn Node> = if let Some(offset) = rd.marked_date_offset {
+            Box::new(
+                element::Line::new()
+                    .set("class", "marker")
+                    .set("x1", offset)
+                    .set("y1", rd.gutter.top - 5.0)
+                    .set("x2", offset)
+                    .set(
+                        "y2",
+                        rd.gutter.top + ((rd.rows.len() as f32) * rd.row_height) + 5.0,
+                    ),
+            )
+        } else {
+            Box::new(element::Group::new())
+        };
+
+        // The status line runs down the marked date, bending out to where the done part of each
+        // task reaches, so tasks that are behind pull it left and those that are ahead push it right
+        let status_line: Box<dyn Node> = match rd.marked_date_offset {
+            Some(offset) if options.status_line => {
+                let mut data = Data::new().move_to((offset, rd.gutter.top));
+
+                for (i, row) in rd.rows.iter().enumerate() {
+                    let percent_complete = match row.percent_complete {
+                        Some(percent_complete) if row.length.is_some() => percent_complete,
+                        _ => continue,
+                    };
+                    let y = rd.gutter.top + (i as f32 * rd.row_height);
+                    let progress_offset = row
+                        .progress_bars()
+                        .last()
+                        .map_or(row.offset, |&(offset, length)| offset + length);
+
+                    // Finished tasks can't be behind, and tasks yet to start can't be ahead
+                    if (progress_offset < offset && percent_complete < 100.0)
+                        || (progress_offset > offset && percent_complete > 0.0)
+                    {
+                        data = data
+                            .line_to((offset, y))
+                            .line_to((progress_offset, y + rd.row_height / 2.0))
+                            .line_to((offset, y + rd.row_height));
+                    }
+                }
+
+                Box::new(element::Path::new().set("class", "status-line").set(
+                    "d",
+                    data.line_to((
+                        offset,
+                        rd.gutter.top + (rd.rows.len() as f32) * rd.row_height,
+                    )),
+                ))
+            }
+            _ => Box::new(element::Group::new()),
+        };
+
+        // A strip under the headings with each column shaded by its share of the busiest column's working days
+        let mut heat = element::Group::new();
+        let max_heat = rd.heat.iter().copied().max().unwrap_or(0);
+
+        if !rd.heat.is_empty() {
+            heat.append(
+                element::Rectangle::new()
+                    .set("class", "heat-strip")
+                    .set("x", col_offsets[0])
+                    .set("y", rd.header_bottom())
+                    .set("width", col_offsets[rd.cols.len()] - col_offsets[0])
+                    .set("height", HEAT_STRIP_HEIGHT),
+            );
+        }
+
+        for (i, &days) in rd.heat.iter().enumerate().filter(|&(_, &days)| days > 0) {
+            heat.append(
+                element::Rectangle::new()
+                    .set("class", "heat")
+                    .set("x", col_offsets[i])
+                    .set("y", rd.header_bottom())
+                    .set("width", rd.cols[i].width)
+                    .set("height", HEAT_STRIP_HEIGHT)
+                    .set("fill-opacity", days as f32 / max_heat as f32),
+            );
+        }
+
+        // Shade the part of the chart that is already in the past
+        let elapsed: Box<dyn Node> = match rd.marked_date_offset {
+            Some(offset) if offset > rd.gutter.left + rd.title_width => Box::new(
+                element::Rectangle::new()
+                    .set("class", "elapsed")
+                    .set("x", rd.gutter.left + rd.title_width)
+                    .set("y", rd.gutter.top)
+                    .set("width", offset - rd.gutter.left - rd.title_width)
+                    .set("height", (rd.rows.len() as f32) * rd.row_height),
+            ),
+            _ => Box::new(element::Group::new()),
+        };
+
+        // Render the sprints as alternately shaded bands
+        let mut sprints = element::Group::new();
+
+        for (i, sprint) in rd.sprints.iter().enumerate() {
+            sprints.append(
+                element::Rectangle::new()
+                    .set("class", if i % 2 == 0 { "sprint" } else { "sprint-alt" })
+                    .set("x", sprint.offset)
+                    .set("y", rd.gutter.top)
+                    .set("width", sprint.width)
+                    .set("height", (rd.rows.len() as f32) * rd.row_height),
+            );
+            sprints.append(
+                element::Text::new(sprint.name.as_ref())
+                    .set("class", "sprint-name")
+                    .set("x", sprint.offset + sprint.width / 2.0)
+                    .set(
+                        "y",
+                        rd.header_bottom()
+                            - rd.heading_height
+                            - rd.row_gutter.bottom
+                            - rd.row_height / 2.0,
+                    ),
+            );
+        }
+
+        // Render the phases as tinted bands behind everything, with their names at the top
+        let mut phases = element::Group::new();
+        let phase_name_y = rd.header_bottom()
+            - rd.heading_height
+            - rd.row_gutter.bottom
+            - rd.row_height * (if rd.sprints.is_empty() { 0.5 } else { 1.5 });
+        let phase_top = phase_name_y - rd.row_height / 2.0;
+
+        for (i, phase) in rd.phases.iter().enumerate() {
+            let color = match phase.color {
+                Some(ref color) => color.to_string(),
+                None => PHASE_COLORS[i % PHASE_COLORS.len()].to_owned(),
+            };
+
+            let mut band = element::Rectangle::new()
+                .set("class", "phase")
+                .set("fill", color)
+                .set("x", phase.offset)
+                .set("y", phase_top)
+                .set("width", phase.width)
+                .set(
+                    "height",
+                    rd.gutter.top + (rd.rows.len() as f32) * rd.row_height - phase_top,
+                );
+
+            if let Some(ref id) = phase_ids[i] {
+                band = band.set("id", id.as_str());
+            }
+
+            phases.append(band);
+            phases.append(
+                element::Text::new(phase.name.as_ref())
+                    .set("class", "phase-name")
+                    .set("x", phase.offset + phase.width / 2.0)
+                    .set("y", phase_name_y),
+            );
+        }
+
+        // What goes under the rows is written before them, then the rows and the arrows between them as they're made
+        svg.start(element::Group::new().set("id", "chart"));
+        svg.write(title)?;
+        svg.write(phases)?;
+        svg.write(sprints)?;
+        svg.write(elapsed)?;
+        svg.write(columns)?;
+        svg.write(heat)?;
+        svg.write(tasks)?;
+        svg.write(data_columns)?;
+
+        // Render all the chart rows
+        svg.start(element::Group::new());
+
+        for i in 0..=rd.rows.len() {
+            let y = rd.gutter.top + (i as f32 * rd.row_height);
+
+            svg.write(if i == 0 || i == rd.rows.len() {
+                element::Line::new()
+                    .set("class", "outer-lines")
                     .set("x1", rd.gutter.left)
                     .set("y1", y)
                     .set("x2", width - rd.gutter.right)
                     .set("y2", y)
-            });
+            } else {
+                element::Line::new()
+                    .set("class", "inner-lines")
+                    .set("x1", rd.gutter.left)
+                    .set("y1", y)
+                    .set("x2", width - rd.gutter.right)
+                    .set("y2", y)
+            })?;
+
+            // Are we on one of the task rows?
+            if i < rd.rows.len() {
+                let row: &RowRenderData = &rd.rows[i];
+                // Fade out the rows outside the highlighted task's dependency chain
+                let row_class = match (&options.highlight, row.highlighted, row.selected) {
+                    (None, _, _) => "row",
+                    (Some(_), _, true) => "row highlighted selected",
+                    (Some(_), true, false) => "row highlighted",
+                    (Some(_), false, false) => "row dimmed",
+                };
+                let mut row_group = element::Group::new().set("class", row_class);
+
+                if interactive {
+                    // The column the row starts in, found from the inner column edges
+                    let col_index =
+                        col_offsets[1..rd.cols.len().max(1)].partition_point(|&x| x <= row.offset);
+                    // Only the rows next to this one, as the page script walks the rest of the chain
+                    let join = |rows: &[usize]| {
+                        rows.iter()
+                            .map(|j| j.to_string())
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    };
+
+                    if let Some(Some(id)) = row_ids.get(i) {
+                        row_group = row_group.set("id", id.as_str());
+                    }
+
+                    row_group = row_group
+                        .set("data-row", i)
+                        .set("data-col", col_index)
+                        .set("data-predecessors", join(&row.predecessors))
+                        .set(
+                            "data-successors",
+                            successors.get(i).map_or(String::new(), |s| join(s)),
+                        );
+                }
+
+                // The page script lights up any row, otherwise only the highlighted task has a background
+                if interactive || row.selected {
+                    row_group.append(
+                        element::Rectangle::new()
+                            .set("class", "row-highlight")
+                            .set("x", rd.gutter.left)
+                            .set("y", y)
+                            .set("width", width - rd.gutter.width())
+                            .set("height", rd.row_height),
+                    );
+                }
+
+                // Hatch out the times the resource is away
+                for &(offset, absence_width) in &rd.absences[row.resource_index] {
+                    row_group.append(
+                        element::Rectangle::new()
+                            .set("class", "absence")
+                            .set("x", offset)
+                            .set("y", y)
+                            .set("width", absence_width)
+                            .set("height", rd.row_height),
+                    );
+                }
+
+                // Extra classes go on the end of the ones the chart uses
+                let extra_classes: String = (row.resource_index == rd.unassigned_index)
+                    .then_some("unassigned")
+                    .into_iter()
+                    .chain(row.classes.iter().map(String::as_str))
+                    .map(|class| format!(" {}", class))
+                    .collect();
+
+                if options.compact {
+                    let (title, x, _) = compact_title(row);
+
+                    row_group.append(
+                        element::Text::new(title)
+                            .set("class", format!("item-above{}", extra_classes))
+                            .set("x", x)
+                            .set("y", y + rd.row_gutter.top - 2.0),
+                    );
+                } else {
+                    let x = rd.gutter.left + rd.row_gutter.left;
+                    let mid_y = y + rd.row_height / 2.0;
+
+                    match options.badges {
+                        Some(Badge::Dot) => row_group.append(
+                            element::Circle::new()
+                                .set("class", format!("badge-{}", row.status()))
+                                .set("cx", x + BADGE_DOT_SIZE / 2.0)
+                                .set("cy", mid_y)
+                                .set("r", BADGE_DOT_SIZE / 2.0),
+                        ),
+                        Some(Badge::Percent) => {
+                            let percent_complete = row
+                                .percent_complete
+                                .unwrap_or(if row.status() == 2 { 100.0 } else { 0.0 });
+                            let chip_width = rd.badge_width - BADGE_GAP;
+                            let chip_height = BADGE_FONT_SIZE * 2.0;
+
+                            row_group.append(
+                                element::Rectangle::new()
+                                    .set("class", format!("badge-{}", row.status()))
+                                    .set("x", x)
+                                    .set("y", mid_y - chip_height / 2.0)
+                                    .set("rx", chip_height / 2.0)
+                                    .set("ry", chip_height / 2.0)
+                                    .set("width", chip_width)
+                                    .set("height", chip_height),
+                            );
+                            row_group.append(
+                                element::Text::new(format!("{:.0}%", percent_complete))
+                                    .set("class", "badge-text")
+                                    .set("x", x + chip_width / 2.0)
+                                    .set("y", mid_y),
+                            );
+                        }
+                        None => (),
+                    }
+
+                    row_group.append(
+                        element::Text::new(text_measure.truncate(
+                            &row.title,
+                            ITEM_FONT_SIZE,
+                            max_title_width - rd.badge_width,
+                        ))
+                        .set("class", format!("item{}", extra_classes))
+                        .set("x", x + rd.badge_width)
+                        .set("y", y + rd.row_gutter.top + rd.row_height / 2.0),
+                    );
+                }
+
+                for data_column in &rd.data_columns {
+                    row_group.append(
+                        element::Text::new(data_column.cells[i].as_str())
+                            .set("class", "data-cell")
+                            .set("x", data_column.offset + rd.row_gutter.left)
+                            .set("y", y + rd.row_gutter.top + rd.row_height / 2.0),
+                    );
+                }
+
+                if let Some(assignee) = rd.assignees.get(row.resource_index) {
+                    row_group.append(
+                        element::Text::new(assignee.as_str())
+                            .set("class", "assignee")
+                            .set("x", rd.titles_end() + rd.row_gutter.left)
+                            .set("y", y + rd.row_gutter.top + rd.row_height / 2.0),
+                    );
+                }
+
+                let [open_class, closed_class] = &bar_classes[row.color_index];
+                let bar_class = if row.open { open_class } else { closed_class };
+
+                // The forecast is a lighter bar or milestone behind the planned one
+                if let Some((forecast_offset, forecast_length)) = row.forecast {
+                    if row.length.is_some() {
+                        row_group.append(
+                            element::Rectangle::new()
+                                .set("class", format!("{} forecast", closed_class))
+                                .set("x", forecast_offset)
+                                .set("y", y + rd.row_gutter.top)
+                                .set("rx", rd.rect_corner_radius)
+                                .set("ry", rd.rect_corner_radius)
+                                .set("width", forecast_length)
+                                .set("height", rd.row_height - rd.row_gutter.height()),
+                        );
+                    } else {
+                        let n = (rd.row_height - rd.row_gutter.height()) / 2.0;
+
+                        row_group.append(
+                            element::Path::new().set("class", "milestone forecast").set(
+                                "d",
+                                Data::new()
+                                    .move_to((forecast_offset - n, y + rd.row_gutter.top + n))
+                                    .line_by((n, -n))
+                                    .line_by((n, n))
+                                    .line_by((-n, n))
+                                    .line_by((-n, -n)),
+                            ),
+                        );
+                    }
+                }
+
+                // Is this a task or a milestone?
+                if row.length.is_some() {
+                    let bars = row.bars();
+
+                    // A thin line joins the parts of a split task across the gaps
+                    for (&(offset, length), &(next_offset, _)) in bars
+                        .iter()
+                        .zip(bars.iter().skip(1))
+                        .filter(|_| !row.aggregated)
+                    {
+                        row_group.append(
+                            element::Line::new()
+                                .set("class", format!("{} segment-gap", open_class))
+                                .set("x1", offset + length)
+                                .set("y1", y + rd.row_gutter.top + rd.row_height / 2.0)
+                                .set("x2", next_offset)
+                                .set("y2", y + rd.row_gutter.top + rd.row_height / 2.0),
+                        );
+                    }
+
+                    for &(offset, length) in &bars {
+                        row_group.append(
+                            element::Rectangle::new()
+                                .set("class", format!("{}{}", bar_class, extra_classes))
+                                .set("x", offset)
+                                .set("y", y + rd.row_gutter.top)
+                                .set("rx", rd.rect_corner_radius)
+                                .set("ry", rd.rect_corner_radius)
+                                .set("width", length)
+                                .set("height", rd.row_height - rd.row_gutter.height()),
+                        );
+                    }
+
+                    // The done part of the bars is shaded
+                    for (offset, length) in row.progress_bars() {
+                        row_group.append(
+                            element::Rectangle::new()
+                                .set("class", "progress")
+                                .set("x", offset)
+                                .set("y", y + rd.row_gutter.top)
+                                .set("rx", rd.rect_corner_radius)
+                                .set("ry", rd.rect_corner_radius)
+                                .set("width", length)
+                                .set("height", rd.row_height - rd.row_gutter.height()),
+                        );
+                    }
+                } else {
+                    let n = (rd.row_height - rd.row_gutter.height()) / 2.0;
+                    row_group.append(
+                        element::Path::new()
+                            .set("class", format!("milestone{}", extra_classes))
+                            .set(
+                                "d",
+                                Data::new()
+                                    .move_to((row.offset - n, y + rd.row_gutter.top + n))
+                                    .line_by((n, -n))
+                                    .line_by((n, n))
+                                    .line_by((-n, n))
+                                    .line_by((-n, -n)),
+                            ),
+                    );
+                }
+
+                // The scenario is a dashed outline over the plan, so it shows wherever it is
+                if let Some((scenario_offset, scenario_length)) = row.scenario {
+                    if row.length.is_some() {
+                        row_group.append(
+                            element::Rectangle::new()
+                                .set("class", "scenario")
+                                .set("x", scenario_offset)
+                                .set("y", y + rd.row_gutter.top)
+                                .set("rx", rd.rect_corner_radius)
+                                .set("ry", rd.rect_corner_radius)
+                                .set("width", scenario_length)
+                                .set("height", rd.row_height - rd.row_gutter.height()),
+                        );
+                    } else {
+                        let n = (rd.row_height - rd.row_gutter.height()) / 2.0;
+
+                        row_group.append(
+                            element::Path::new().set("class", "scenario").set(
+                                "d",
+                                Data::new()
+                                    .move_to((scenario_offset - n, y + rd.row_gutter.top + n))
+                                    .line_by((n, -n))
+                                    .line_by((n, n))
+                                    .line_by((-n, n))
+                                    .line_by((-n, -n)),
+                            ),
+                        );
+                    }
+                }
+
+                if let Some((optimistic, pessimistic)) = row.estimate {
+                    let mid_y = y + rd.row_height / 2.0;
+                    let n = (rd.row_height - rd.row_gutter.height()) / 4.0;
+
+                    row_group.append(
+                        element::Path::new().set("class", "estimate").set(
+                            "d",
+                            Data::new()
+                                .move_to((optimistic, mid_y - n))
+                                .line_by((0, 2.0 * n))
+                                .move_to((optimistic, mid_y))
+                                .line_to((pessimistic, mid_y))
+                                .move_to((pessimistic, mid_y - n))
+                                .line_by((0, 2.0 * n)),
+                        ),
+                    );
+                }
+
+                // When the work was really done is a thin bar across the middle of the planned one
+                if let Some((actual_offset, actual_length)) = row.actual {
+                    let height = (rd.row_height - rd.row_gutter.height()) / 4.0;
+
+                    row_group.append(
+                        element::Rectangle::new()
+                            .set("class", "actual")
+                            .set("x", actual_offset)
+                            .set("y", y + (rd.row_height - height) / 2.0)
+                            .set("width", actual_length)
+                            .set("height", height),
+                    );
+                }
+
+                // Slipped tasks get an arrow from their baseline finish to their finish now
+                if let (Some(baseline_offset), Some(slip_days)) =
+                    (row.baseline_offset, row.slip_days)
+                {
+                    // Stopping at the side of a milestone's diamond
+                    let finish_offset = match row.length {
+                        Some(length) => row.offset + length,
+                        None => row.offset - (rd.row_height - rd.row_gutter.height()) / 2.0,
+                    };
+
+                    if slip_days > 0.0 && finish_offset > baseline_offset {
+                        let mid_y = y + rd.row_height / 2.0;
+                        let n = (rd.row_height - rd.row_gutter.height()) / 4.0;
+                        let head = n.min(finish_offset - baseline_offset);
+                        let magnitude = SLIP_DAYS
+                            .iter()
+                            .find(|&&(days, _)| slip_days <= days)
+                            .map_or("major", |&(_, magnitude)| magnitude);
+
+                        row_group.append(
+                            element::Path::new()
+                                .set("class", format!("slip slip-{}", magnitude))
+                                .set(
+                                    "d",
+                                    Data::new()
+                                        .move_to((baseline_offset, mid_y))
+                                        .line_to((finish_offset - head, mid_y))
+                                        .move_to((finish_offset - head, mid_y - head))
+                                        .line_to((finish_offset, mid_y))
+                                        .line_to((finish_offset - head, mid_y + head))
+                                        .close(),
+                                ),
+                        );
+                    }
+                }
+
+                // A warning sign just before the start of the bar or milestone of rows at risk
+                if row.at_risk {
+                    let x = match row.length {
+                        Some(_) => row.offset,
+                        None => row.offset - (rd.row_height - rd.row_gutter.height()) / 2.0,
+                    } - RISK_SIGN_SIZE / 2.0
+                        - 3.0;
+                    let mid_y = y + rd.row_height / 2.0;
+
+                    row_group.append(
+                        element::Path::new().set("class", "risk").set(
+                            "d",
+                            Data::new()
+                                .move_to((x, mid_y - RISK_SIGN_SIZE / 2.0))
+                                .line_to((x + RISK_SIGN_SIZE / 2.0, mid_y + RISK_SIGN_SIZE / 2.0))
+                                .line_to((x - RISK_SIGN_SIZE / 2.0, mid_y + RISK_SIGN_SIZE / 2.0))
+                                .close(),
+                        ),
+                    );
+                    row_group.append(
+                        element::Text::new("!")
+                            .set("class", "risk-mark")
+                            .set("x", x)
+                            .set("y", mid_y + 2.0),
+                    );
+                }
+
+                // The note's number goes just after the end of the bar or milestone
+                if let Some(&Some(number)) = note_numbers.get(i) {
+                    let end_offset = match row.length {
+                        Some(length) => row.offset + length,
+                        None => row.offset + (rd.row_height - rd.row_gutter.height()) / 2.0,
+                    };
+
+                    row_group.append(
+                        element::Text::new((number + 1).to_string())
+                            .set("class", "note-ref")
+                            .set("x", end_offset + 2.0)
+                            .set("y", y + rd.row_gutter.top + 4.0),
+                    );
+                }
+
+                svg.write(row_group)?;
+            }
+        }
+
+        svg.end()?;
+
+        // Render the dependency arrows, keeping them off the bars, milestones and compact titles
+        svg.start(element::Group::new());
+
+        if options.arrows.is_some() {
+            let row_spans: Vec<Vec<(f32, f32)>> = rd
+                .rows
+                .iter()
+                .map(|row| {
+                    let mut spans: Vec<(f32, f32)> = match row.length {
+                        Some(_) => row
+                            .bars()
+                            .into_iter()
+                            .map(|(offset, length)| (offset, offset + length))
+                            .collect(),
+                        None => vec![(
+                            row.offset - milestone_half_width,
+                            row.offset + milestone_half_width,
+                        )],
+                    };
+
+                    if options.compact {
+                        let (_, left, right) = compact_title(row);
+
+                        spans.push((left, right));
+                    }
+
+                    spans
+                })
+                .collect();
+            // Arrows go from the end of a task, or the point of a milestone, to the start of the next
+            let ends = |row: &RowRenderData| match row.length {
+                Some(length) => (row.offset, row.offset + length),
+                None => (
+                    row.offset - milestone_half_width,
+                    row.offset + milestone_half_width,
+                ),
+            };
+
+            for (i, row) in rd.rows.iter().enumerate() {
+                for &j in row.predecessors.iter().filter(|&&j| j != i) {
+                    let points = routing::route_arrow(
+                        (ends(&rd.rows[j]).1, j),
+                        (ends(row).0, i),
+                        &row_spans,
+                        rd.gutter.top,
+                        rd.row_height,
+                    );
+                    let mut data = Data::new().move_to(points[0]);
+
+                    for &point in &points[1..] {
+                        data = data.line_to(point);
+                    }
+
+                    let dimmed =
+                        options.highlight.is_some() && !(row.highlighted && rd.rows[j].highlighted);
+                    let mut path = element::Path::new()
+                        .set(
+                            "class",
+                            if dimmed {
+                                "dependency dimmed"
+                            } else {
+                                "dependency"
+                            },
+                        )
+                        .set("marker-end", "url(#arrow-head)")
+                        .set("d", data);
+
+                    // So the page script can fade it out with the rows
+                    if interactive {
+                        path = path.set("data-rows", format!("{} {}", j, i));
+                    }
+
+                    svg.write(path)?;
+                }
+            }
+        }
+
+        svg.end()?;
+
+        // Render the total of each column in a row under the tasks, leaving out breaks and empty columns
+        let mut totals = element::Group::new();
+
+        if !rd.totals.is_empty() {
+            let y = rd.gutter.top + ((rd.rows.len() as f32) * rd.row_height);
+
+            // Compact charts have no title column to name the row in
+            if !options.compact {
+                totals.append(
+                    element::Text::new(text_measure.truncate(
+                        rd.totals_label,
+                        ITEM_FONT_SIZE,
+                        max_title_width,
+                    ))
+                    .set("class", "item")
+                    .set("x", rd.gutter.left + rd.row_gutter.left)
+                    .set("y", y + rd.row_height / 2.0),
+                );
+            }
+
+            for (i, &total) in rd
+                .totals
+                .iter()
+                .enumerate()
+                .filter(|&(_, &total)| total > 0.0)
+            {
+                totals.append(
+                    element::Text::new(format!("{}", (total * 10.0).round() / 10.0))
+                        .set("class", "total")
+                        .set("x", col_offsets[i] + rd.cols[i].width / 2.0)
+                        .set("y", y + rd.row_height / 2.0),
+                );
+            }
+
+            totals.append(
+                element::Line::new()
+                    .set("class", "outer-lines")
+                    .set("x1", rd.gutter.left)
+                    .set("y1", y + rd.row_height)
+                    .set("x2", width - rd.gutter.right)
+                    .set("y2", y + rd.row_height),
+            );
+        }
 
-            // Are we on one of the task rows?
-            if i < rd.rows.len() {
-                let row: &RowRenderData = &rd.rows[i];
+        // Render the demand against the capacity of each resource, with a bar
+        // filled in proportion to how much of the capacity is used
+        let mut capacity = element::Group::new();
+        let capacity_top = rd.gutter.top
+            + ((rd.rows.len() as f32) * rd.row_height)
+            + rd.totals_height()
+            + rd.resource_gutter.top;
 
-                rows.append(
-                    element::Text::new(&row.title)
-                        .set("class", "item")
-                        .set("x", rd.gutter.left + rd.row_gutter.left)
-                        .set("y", y + rd.row_gutter.top + rd.row_height / 2.0),
-                );
+        for (i, resource_capacity) in rd.capacity.iter().enumerate() {
+            let y = capacity_top + (i as f32) * rd.row_height;
+            let bar_height = rd.row_height - rd.row_gutter.height();
+            let mut x = rd.gutter.left + rd.title_width;
 
-                // Is this a task or a milestone?
-                if let Some(length) = row.length {
-                    rows.append(
+            capacity.append(
+                element::Text::new(text_measure.truncate(
+                    &rd.resources[resource_capacity.resource_index],
+                    ITEM_FONT_SIZE,
+                    max_title_width,
+                ))
+                .set("class", "item")
+                .set("x", rd.gutter.left + rd.row_gutter.left)
+                .set("y", y + rd.row_height / 2.0),
+            );
+
+            for (col, &(demand, available)) in rd.cols.iter().zip(resource_capacity.cells.iter()) {
+                // Breaks are too narrow for their demand to be shown
+                if col.is_break {
+                    x += col.width;
+                    continue;
+                }
+
+                let ratio = if available > 0.0 {
+                    demand / available
+                } else if demand > 0.0 {
+                    f32::INFINITY
+                } else {
+                    0.0
+                };
+                let over = ratio > 1.0;
+
+                if demand > 0.0 {
+                    let fill_height = bar_height * ratio.min(1.0);
+
+                    capacity.append(
                         element::Rectangle::new()
                             .set(
                                 "class",
-                                format!(
-                                    "resource-{}{}",
-                                    row.resource_index,
-                                    if row.open { "-open" } else { "-closed" }
-                                ),
+                                if over {
+                                    "capacity-over"
+                                } else {
+                                    "capacity-used"
+                                },
                             )
-                            .set("x", row.offset)
-                            .set("y", y + rd.row_gutter.top)
-                            .set("rx", rd.rect_corner_radius)
-                            .set("ry", rd.rect_corner_radius)
-                            .set("width", length)
-                            .set("height", rd.row_height - rd.row_gutter.height()),
+                            .set("x", x + rd.row_gutter.left)
+                            .set("y", y + rd.row_gutter.top + bar_height - fill_height)
+                            .set("width", (col.width - rd.row_gutter.width()).max(0.0))
+                            .set("height", fill_height),
                     );
-                } else {
-                    let n = (rd.row_height - rd.row_gutter.height()) / 2.0;
-                    rows.append(
-                        element::Path::new().set("class", "milestone").set(
-                            "d",
-                            Data::new()
-                                .move_to((row.offset - n, y + rd.row_gutter.top + n))
-                                .line_by((n, -n))
-                                .line_by((n, n))
-                                .line_by((-n, n))
-                                .line_by((-n, -n)),
-                        ),
+                    capacity.append(
+                        element::Text::new(if ratio.is_finite() {
+                            format!("{:.0}%", ratio * 100.0)
+                        } else {
+                            "!".to_owned()
+                        })
+                        .set(
+                            "class",
+                            if over {
+                                "capacity-label capacity-label-over"
+                            } else {
+                                "capacity-label"
+                            },
+                        )
+                        .set("x", x + col.width / 2.0)
+                        .set("y", y + rd.row_height / 2.0),
                     );
                 }
-            }
-        }
 
-        // Render all the charts columns
-        let mut columns = element::Group::new();
+                x += col.width;
+            }
 
-        for i in 0..=rd.cols.len() {
-            let x: f32 = rd.gutter.left
-                + rd.title_width
-                + rd.cols.iter().take(i).map(|col| col.width).sum::<f32>();
-            columns.append(
+            capacity.append(
                 element::Line::new()
-                    .set("class", "inner-lines")
-                    .set("x1", x)
-                    .set("y1", rd.gutter.top)
-                    .set("x2", x)
                     .set(
-                        "y2",
-                        rd.gutter.top + ((rd.rows.len() as f32) * rd.row_height),
-                    ),
+                        "class",
+                        if i + 1 == rd.capacity.len() {
+                            "outer-lines"
+                        } else {
+                            "inner-lines"
+                        },
+                    )
+                    .set("x1", rd.gutter.left)
+                    .set("y1", y + rd.row_height)
+                    .set("x2", width - rd.gutter.right)
+                    .set("y2", y + rd.row_height),
             );
-
-            if i < rd.cols.len() {
-                columns.append(
-                    element::Text::new(&rd.cols[i].month_name)
-                        .set("class", "heading")
-                        .set("x", x + rd.max_month_width / 2.0)
-                        .set(
-                            "y",
-                            // TODO(john): Use a more appropriate row height value here?
-                            rd.gutter.top - rd.row_gutter.bottom - rd.row_height / 2.0,
-                        ),
-                );
-            }
         }
 
-        let tasks = element::Text::new("Tasks")
-            .set("class", "heading task-heading")
-            .set("x", rd.gutter.left + rd.row_gutter.left)
-            .set(
-                "y",
-                rd.gutter.top - rd.row_gutter.bottom - rd.row_height / 2.0,
-            );
-
-        let title = element::Text::new(&rd.title)
-            .set("class", "title")
-            .set("x", rd.gutter.left)
-            // TODO(john): Use more appropriate row height value here?
-            .set("y", 25.0);
-
-        let marker: Box<dyn Node> = if let Some(offset) = rd.marked_date_offset {
-            Box::new(
-                element::Line::new()
-                    .set("class", "marker")
-                    .set("x1", offset)
-                    .set("y1", rd.gutter.top - 5.0)
-                    .set("x2", offset)
-                    .set(
-                        "y2",
-                        rd.gutter.top + ((rd.rows.len() as f32) * rd.row_height) + 5.0,
-                    ),
-            )
-        } else {
-            Box::new(element::Group::new())
-        };
-
         let mut resources = element::Group::new();
+        let mut x = rd.resource_gutter.left;
 
-        for i in 0..rd.resources.len() {
+        // The table shows the names of what the bars are colored by, which are usually the resources
+        for i in 0..rd.color_names.len() {
             if add_resource_table {
-                let y = rd.gutter.top + ((rd.rows.len() as f32) * rd.row_height);
+                let y = rd.gutter.top
+                    + ((rd.rows.len() as f32) * rd.row_height)
+                    + rd.totals_height()
+                    + rd.capacity_height();
                 let block_width = rd.resource_height - rd.resource_gutter.height();
 
+                // Long names get the room they need before their color block
+                x += text_measure
+                    .width(&rd.color_names[i], ITEM_FONT_SIZE)
+                    .max(70.0)
+                    + 30.0;
+
                 resources.append(
-                    element::Text::new(&rd.resources[i])
+                    element::Text::new(rd.color_names[i].as_ref())
                         .set("class", "resource")
-                        .set(
-                            "x",
-                            rd.resource_gutter.left + ((i + 1) as f32) * 100.0 - 5.0,
-                        )
+                        .set("x", x - 5.0)
                         .set("y", y + rd.resource_height / 2.0),
                 );
                 resources.append(
                     element::Rectangle::new()
-                        .set("class", format!("resource-{}-closed", i))
-                        .set(
-                            "x",
-                            rd.resource_gutter.left + ((i + 1) as f32) * 100.0 + 5.0,
-                        )
+                        .set("class", format!("{}-{}-closed", rd.color_class, i))
+                        .set("x", x + 5.0)
                         .set("y", y + rd.resource_gutter.top)
                         .set("rx", rd.rect_corner_radius)
                         .set("ry", rd.rect_corner_radius)
@@ -622,14 +6512,762 @@ impl<'a> GanttChartTool<'a> {
             }
         }
 
-        document.append(style);
-        document.append(title);
-        document.append(columns);
-        document.append(tasks);
-        document.append(rows);
-        document.append(marker);
-        document.append(resources);
+        let mut risks = element::Group::new();
+        let risks_top = rd.gutter.top
+            + ((rd.rows.len() as f32) * rd.row_height)
+            + rd.totals_height()
+            + rd.capacity_height()
+            + if add_resource_table {
+                rd.resource_gutter.height() + rd.resource_height
+            } else {
+                0.0
+            };
+
+        if !rd.risks.is_empty() {
+            let rows: Vec<[&str; 4]> = rd.risks.iter().map(RiskRenderData::cells).collect();
+
+            risks = Self::render_table(
+                rd,
+                &text_measure,
+                risks_top,
+                &RISK_COLUMN_SHARES,
+                &[
+                    &rd.labels.risk,
+                    &rd.labels.tasks,
+                    &rd.labels.owner,
+                    &rd.labels.mitigation,
+                ],
+                &rows,
+            );
+        }
+
+        let mut notes = element::Group::new();
+        let notes_top = risks_top + rd.table_height(rd.risks.len()) + rd.resource_gutter.top;
+
+        for (number, (_, note)) in rd.notes().enumerate() {
+            let text = format!("{}. {}", number + 1, note);
+
+            notes.append(
+                element::Text::new(text_measure.truncate(
+                    &text,
+                    NOTE_FONT_SIZE,
+                    width - rd.gutter.width(),
+                ))
+                .set("class", "note")
+                .set("x", rd.gutter.left)
+                .set("y", notes_top + (number as f32 + 0.5) * NOTE_LINE_HEIGHT),
+            );
+        }
+
+        let mut sign_off = element::Group::new();
+
+        if !rd.sign_off.is_empty() {
+            let sign_off_top = notes_top - rd.resource_gutter.top + rd.notes_height();
+            let dates: Vec<String> = rd
+                .sign_off
+                .iter()
+                .map(|signer| {
+                    signer
+                        .date
+                        .map_or(String::new(), |date| date.format("%Y-%m-%d").to_string())
+                })
+                .collect();
+            let rows: Vec<[&str; 4]> = rd
+                .sign_off
+                .iter()
+                .zip(dates.iter())
+                .map(|(signer, date)| [signer.name.as_str(), signer.role.as_str(), date, ""])
+                .collect();
+
+            sign_off = Self::render_table(
+                rd,
+                &text_measure,
+                sign_off_top,
+                &SIGN_OFF_COLUMN_SHARES,
+                &[
+                    &rd.labels.name,
+                    &rd.labels.role,
+                    &rd.labels.date,
+                    &rd.labels.signature,
+                ],
+                &rows,
+            );
+
+            // Lines to sign on, and to write the date on when it isn't given
+            let table_width = width - rd.gutter.width();
+            let date_left = rd.gutter.left
+                + table_width * (SIGN_OFF_COLUMN_SHARES[0] + SIGN_OFF_COLUMN_SHARES[1]);
+            let signature_left = date_left + table_width * SIGN_OFF_COLUMN_SHARES[2];
+
+            for (i, signer) in rd.sign_off.iter().enumerate() {
+                let y = sign_off_top + rd.resource_gutter.top + (i + 2) as f32 * rd.row_height
+                    - rd.row_gutter.bottom;
+                let mut lines = vec![(signature_left, width - rd.gutter.right)];
+
+                if signer.date.is_none() {
+                    lines.push((date_left, signature_left));
+                }
+
+                for (left, right) in lines {
+                    sign_off.append(
+                        element::Line::new()
+                            .set("class", "signature-line")
+                            .set("x1", left + rd.row_gutter.left)
+                            .set("y1", y)
+                            .set("x2", right - rd.row_gutter.right)
+                            .set("y2", y),
+                    );
+                }
+            }
+        }
+
+        svg.write(marker)?;
+        svg.write(status_line)?;
+        svg.write(totals)?;
+        svg.write(capacity)?;
+        svg.write(resources)?;
+        svg.write(risks)?;
+        svg.write(notes)?;
+        svg.write(sign_off)?;
+        svg.end()?;
+
+        if interactive {
+            // Copies of the header, the title column and the corner where they meet, which the page
+            // script moves to stay in view as the chart scrolls
+            let titles_right = rd.gutter.left + rd.title_width;
+
+            for (class, clip_id, clip_width, clip_height) in [
+                ("sticky sticky-top", "header-clip", width, rd.gutter.top),
+                ("sticky sticky-left", "titles-clip", titles_right, height),
+                (
+                    "sticky sticky-top sticky-left",
+                    "corner-clip",
+                    titles_right,
+                    rd.gutter.top,
+                ),
+            ] {
+                svg.write(
+                    element::Group::new()
+                        .set("class", class)
+                        .add(
+                            element::ClipPath::new().set("id", clip_id).add(
+                                element::Rectangle::new()
+                                    .set("width", clip_width)
+                                    .set("height", clip_height),
+                            ),
+                        )
+                        .add(
+                            element::Rectangle::new()
+                                .set("class", "sticky-background")
+                                .set("width", clip_width)
+                                .set("height", clip_height),
+                        )
+                        .add(
+                            element::Use::new()
+                                .set("href", "#chart")
+                                .set("clip-path", format!("url(#{})", clip_id)),
+                        ),
+                )?;
+            }
+        }
+
+        // Over everything else, running up from the bottom left corner to the top right
+        if let Some(ref watermark) = options.watermark {
+            let diagonal = width.hypot(height);
+            let font_size = (diagonal * WATERMARK_SHARE
+                / text_measure.width(watermark, 1.0).max(0.01))
+            .min(WATERMARK_MAX_FONT_SIZE);
+            let angle = -(height / width).atan().to_degrees();
+
+            svg.write(
+                element::Text::new(watermark.as_str())
+                    .set("class", "watermark")
+                    .set("x", width / 2.0)
+                    .set("y", height / 2.0)
+                    .set("font-size", format!("{}pt", font_size))
+                    .set(
+                        "transform",
+                        format!("rotate({} {} {})", angle, width / 2.0, height / 2.0),
+                    ),
+            )?;
+        }
+
+        svg.end()?;
+
+        Ok(svg.finish()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    struct TestLog;
+
+    impl GanttChartLog for TestLog {
+        fn output(&self, _args: Arguments) {}
+        fn warning(&self, _args: Arguments) {}
+        fn error(&self, _args: Arguments) {}
+    }
+
+    fn chart(json: &str) -> ChartData {
+        json5::from_str(json).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_time(NaiveTime::MIN)
+    }
+
+    // The start and end dates each item is scheduled for
+    fn schedule(chart_data: &ChartData) -> Vec<(NaiveDateTime, Option<NaiveDateTime>)> {
+        let log = TestLog;
+        let rd = GanttChartTool::new(&log)
+            .process_chart_data(&RenderOptions::default(), chart_data)
+            .unwrap();
+
+        rd.rows
+            .iter()
+            .map(|row| (row.start_date, row.end_date))
+            .collect()
+    }
+
+    fn validation_error(chart_data: &ChartData) -> String {
+        let log = TestLog;
+
+        match GanttChartTool::new(&log).process_chart_data(&RenderOptions::default(), chart_data) {
+            Ok(_) => panic!("The chart should not be valid"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn two_items_depending_on_each_other_are_a_cycle() {
+        let chart_data = chart(
+            r#"{title: "Cycle", items: [
+                {title: "A", id: "a", startDate: "2024-03-04", duration: 1, dependsOn: ["b"]},
+                {title: "B", id: "b", duration: 1, dependsOn: ["a"]},
+            ]}"#,
+        );
+
+        assert_eq!(
+            GanttChartTool::dependency_cycle(&chart_data, 0),
+            Some(vec![0, 1, 0])
+        );
+        assert!(validation_error(&chart_data).contains("'A' -> 'B' -> 'A'"));
+    }
+
+    #[test]
+    fn an_item_depending_on_itself_is_a_cycle() {
+        let chart_data = chart(
+            r#"{title: "Cycle", items: [
+                {title: "A", id: "a", startDate: "2024-03-04", duration: 1},
+                {title: "B", id: "b", duration: 1, dependsOn: ["b"]},
+            ]}"#,
+        );
+
+        assert_eq!(
+            GanttChartTool::dependency_cycle(&chart_data, 1),
+            Some(vec![1, 1])
+        );
+        assert!(validation_error(&chart_data).contains("'B' -> 'B'"));
+    }
+
+    #[test]
+    fn a_missing_dependency_is_not_a_cycle() {
+        let chart_data = chart(
+            r#"{title: "Missing", items: [
+                {title: "A", id: "a", startDate: "2024-03-04", duration: 1, dependsOn: ["x"]},
+            ]}"#,
+        );
+
+        assert_eq!(GanttChartTool::dependency_cycle(&chart_data, 0), None);
+        assert!(validation_error(&chart_data).contains("not the id of an earlier item"));
+    }
+
+    #[test]
+    fn an_unconnected_item_in_a_connected_plan_is_found() {
+        let chart_data = chart(
+            r#"{title: "Plan", items: [
+                {title: "Design", id: "design", startDate: "2024-03-04", duration: 2},
+                {title: "Build", id: "build", duration: 3, dependsOn: ["design"]},
+                {title: "Stray", startDate: "2024-03-05", duration: 1},
+                {title: "Test", id: "test", duration: 1, dependsOn: ["build"]},
+                {title: "Launch", milestone: true, dependsOn: ["test"]},
+            ]}"#,
+        );
+
+        assert_eq!(GanttChartTool::unconnected_items(&chart_data), vec![2]);
+    }
+
+    #[test]
+    fn items_following_the_previous_item_are_connected() {
+        let chart_data = chart(
+            r#"{title: "Plan", items: [
+                {title: "Design", id: "design", startDate: "2024-03-04", duration: 2},
+                {title: "Build", duration: 3, dependsOn: ["design"]},
+                {title: "Docs", startDate: "2024-03-05", duration: 1},
+                {title: "Review", duration: 1},
+            ]}"#,
+        );
+
+        assert!(GanttChartTool::unconnected_items(&chart_data).is_empty());
+    }
+
+    #[test]
+    fn plans_without_dependencies_have_no_unconnected_items() {
+        let chart_data = chart(
+            r#"{title: "Plan", items: [
+                {title: "A", startDate: "2024-03-04", duration: 2},
+                {title: "B", startDate: "2024-03-11", duration: 2},
+            ]}"#,
+        );
+
+        assert!(GanttChartTool::unconnected_items(&chart_data).is_empty());
+    }
+
+    #[test]
+    fn rolled_up_progress_counts_tasks_without_one_by_the_marked_date() {
+        let chart = |marked_date: &str| {
+            chart(&format!(
+                r#"{{title: "Rollup", {} items: [
+                    {{title: "A", startDate: "2024-03-04", duration: 2, percentComplete: 50, group: "Build"}},
+                    {{title: "B", duration: 2, group: "Build"}},
+                    {{title: "C", duration: 4, group: "Build"}},
+                ]}}"#,
+                marked_date
+            ))
+        };
+        let options = RenderOptions {
+            rollup: Some(Rollup::Group),
+            ..RenderOptions::default()
+        };
+        let log = TestLog;
+        let tool = GanttChartTool::new(&log);
+        let percent_complete = |chart_data: &ChartData| {
+            let rd = tool.process_chart_data(&options, chart_data).unwrap();
+
+            assert_eq!(rd.rows.len(), 1);
+            rd.rows[0].percent_complete
+        };
+
+        // B has ended by the marked date so is done, and C hasn't so is not started
+        assert_eq!(
+            percent_complete(&chart(r#"markedDate: "2024-03-08","#)),
+            Some(37.5)
+        );
+        // Without a marked date only the progress given counts
+        assert_eq!(percent_complete(&chart("")), Some(12.5));
+    }
+
+    #[test]
+    fn binary_formats_cant_be_rendered_to_a_string() {
+        let chart_data =
+            chart(r#"{title: "PNG", items: [{title: "A", startDate: "2024-03-04", duration: 1}]}"#);
+        let options = RenderOptions {
+            format: OutputFormat::Png,
+            ..RenderOptions::default()
+        };
+        let log = TestLog;
+
+        match GanttChartTool::new(&log).render_to_string(&chart_data, &options) {
+            Ok(_) => panic!("A PNG should not be rendered to a string"),
+            Err(e) => assert_eq!(e.exit_code(), 64),
+        }
+    }
+
+    #[test]
+    fn dates_in_left_out_months_are_in_no_column() {
+        // January and March are shown, with February left out between them
+        let col_start_dates = [date(2024, 1, 1), date(2024, 3, 1)];
+        let col_end_dates = [date(2024, 2, 1), date(2024, 4, 1)];
+        let col_index = |date| GanttChartTool::col_index(&col_start_dates, &col_end_dates, date);
+
+        assert_eq!(col_index(date(2023, 12, 31)), None);
+        assert_eq!(col_index(date(2024, 1, 31)), Some(0));
+        assert_eq!(col_index(date(2024, 2, 15)), None);
+        assert_eq!(col_index(date(2024, 3, 1)), Some(1));
+        assert_eq!(col_index(date(2024, 4, 1)), None);
+    }
+
+    #[test]
+    fn items_ending_on_a_weekend_end_on_the_next_working_day() {
+        let chart_data = chart(
+            r#"{title: "Weekend", items: [
+                {title: "A", startDate: "2024-03-07", duration: 3},
+                {title: "B", duration: 1},
+            ]}"#,
+        );
+
+        assert_eq!(
+            schedule(&chart_data),
+            vec![
+                (date(2024, 3, 7), Some(date(2024, 3, 11))),
+                (date(2024, 3, 11), Some(date(2024, 3, 12))),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_weekend_can_be_other_days() {
+        let chart_data = chart(
+            r#"{title: "Weekend", weekend: ["Fri", "Sat"], items: [
+                {title: "A", startDate: "2024-03-06", duration: 2},
+            ]}"#,
+        );
+
+        assert_eq!(
+            schedule(&chart_data),
+            vec![(date(2024, 3, 6), Some(date(2024, 3, 10)))]
+        );
+    }
+
+    #[test]
+    fn items_ending_on_a_holiday_end_after_it() {
+        let chart_data = chart(
+            r#"{title: "Holiday", holidays: ["2024-03-06"], items: [
+                {title: "A", startDate: "2024-03-04", duration: 2},
+                {title: "B", duration: 1},
+            ]}"#,
+        );
+
+        assert_eq!(
+            schedule(&chart_data),
+            vec![
+                (date(2024, 3, 4), Some(date(2024, 3, 7))),
+                (date(2024, 3, 7), Some(date(2024, 3, 8))),
+            ]
+        );
+    }
+
+    #[test]
+    fn items_are_extended_by_the_days_their_resource_is_away() {
+        let chart_data = chart(
+            r#"{title: "Absence", resources: [
+                {name: "Sam", absences: [{start: "2024-03-05", end: "2024-03-06"}]},
+            ], items: [
+                {title: "A", startDate: "2024-03-04", duration: 2, resource: 0},
+            ]}"#,
+        );
+
+        assert_eq!(
+            schedule(&chart_data),
+            vec![(date(2024, 3, 4), Some(date(2024, 3, 8)))]
+        );
+    }
+
+    #[test]
+    fn items_starting_while_their_resource_is_away_start_after() {
+        let chart_data = chart(
+            r#"{title: "Absence", resources: [
+                {name: "Sam", absences: [{start: "2024-03-05", end: "2024-03-06"}]},
+                "Alex",
+            ], items: [
+                {title: "A", startDate: "2024-03-05", duration: 1, resource: 0},
+                {title: "B", startDate: "2024-03-05", duration: 1, resource: 1},
+            ]}"#,
+        );
+
+        assert_eq!(
+            schedule(&chart_data),
+            vec![
+                (date(2024, 3, 7), Some(date(2024, 3, 8))),
+                (date(2024, 3, 5), Some(date(2024, 3, 6))),
+            ]
+        );
+    }
+
+    #[test]
+    fn absences_are_drawn_in_their_resources_rows() {
+        let chart_data = chart(
+            r#"{title: "Absence", resources: [
+                {name: "Sam", absences: [{start: "2024-03-05", end: "2024-03-06"}]},
+                "Alex",
+            ], items: [
+                {title: "A", startDate: "2024-03-04", duration: 10, resource: 0},
+                {title: "B", startDate: "2024-03-04", duration: 10, resource: 1},
+            ]}"#,
+        );
+        let log = TestLog;
+        let rd = GanttChartTool::new(&log)
+            .process_chart_data(&RenderOptions::default(), &chart_data)
+            .unwrap();
+        // Alex's task isn't moved by Sam's absence, so measures the days from the start
+        let day_width = rd.rows[1].length.unwrap() / 10.0;
+
+        assert_eq!(rd.absences[0].len(), 1);
+        assert!((rd.absences[0][0].0 - (rd.rows[1].offset + day_width)).abs() < 0.01);
+        assert!((rd.absences[0][0].1 - 2.0 * day_width).abs() < 0.01);
+        assert!(rd.absences[1].is_empty());
+    }
+
+    #[test]
+    fn json_lines_charts_are_read_a_line_at_a_time() {
+        let content = concat!(
+            "{\"title\": \"Lines\", \"resources\": [\"Sam\"]}\n",
+            "{\"title\": \"A\", \"startDate\": \"2024-03-04\", \"duration\": 2, \"resource\": 0}\n",
+            "\n",
+            "{\"title\": \"B\", \"duration\": 1}\n",
+        );
+        let len = Some(content.len() as u64);
+        let chart_data =
+            GanttChartTool::read_chart_file(Box::new(Cursor::new(content)), len).unwrap();
+
+        assert_eq!(chart_data.title, "Lines");
+        assert_eq!(
+            chart_data
+                .items
+                .iter()
+                .map(|item| item.title.as_str())
+                .collect::<Vec<_>>(),
+            ["A", "B"]
+        );
+        assert_eq!(chart_data.items[0].resource_index, Some(0));
+        assert_eq!(
+            chart_data.source_hash,
+            Some(source_hash(content.as_bytes()))
+        );
+    }
+
+    #[test]
+    fn json_lines_from_standard_input_get_the_git_object_hash() {
+        let content = "{\"title\": \"Lines\"}\n{\"title\": \"A\", \"startDate\": \"2024-03-04\"}\n";
+        let chart_data =
+            GanttChartTool::read_chart_file(Box::new(Cursor::new(content)), None).unwrap();
+        let changed = GanttChartTool::read_chart_file(
+            Box::new(Cursor::new(content)),
+            Some(content.len() as u64 + 1),
+        )
+        .unwrap();
+
+        assert_eq!(
+            chart_data.source_hash,
+            Some(source_hash(content.as_bytes()))
+        );
+        // A file that changed while it was read has no hash rather than a wrong one
+        assert_eq!(changed.source_hash, None);
+    }
+
+    #[test]
+    fn json_lines_errors_give_the_line() {
+        let content =
+            "{\"title\": \"Lines\"}\n{\"title\": \"A\", \"duration\": 1}\n{\"title\": }\n";
+
+        match GanttChartTool::read_chart_file(Box::new(Cursor::new(content)), None) {
+            Ok(_) => panic!("The chart should not be read"),
+            Err(e) => assert!(e.to_string().contains("line 3")),
+        }
+    }
+
+    #[test]
+    fn json5_charts_get_the_git_object_hash() {
+        let content =
+            "{title: \"Plain\", items: [{title: \"A\", startDate: \"2024-03-04\", duration: 1}]}\n";
+        let chart_data =
+            GanttChartTool::read_chart_file(Box::new(Cursor::new(content)), None).unwrap();
+
+        assert_eq!(chart_data.items.len(), 1);
+        assert_eq!(
+            chart_data.source_hash,
+            Some(source_hash(content.as_bytes()))
+        );
+    }
+
+    #[test]
+    fn rolled_up_groups_are_a_summary_bar_spanning_their_tasks() {
+        let chart_data = chart(
+            r#"{title: "Rollup", items: [
+                {title: "Design", startDate: "2024-03-04", duration: 2, group: "Build"},
+                {title: "Launch", startDate: "2024-03-06", duration: 1},
+                {title: "Code", startDate: "2024-03-11", duration: 3, group: "Build"},
+            ]}"#,
+        );
+        let log = TestLog;
+        let tool = GanttChartTool::new(&log);
+        let rd = tool
+            .process_chart_data(&RenderOptions::default(), &chart_data)
+            .unwrap();
+        let rolled_up = tool
+            .process_chart_data(
+                &RenderOptions {
+                    rollup: Some(Rollup::Group),
+                    ..RenderOptions::default()
+                },
+                &chart_data,
+            )
+            .unwrap();
+        let code_end = rd.rows[2].offset + rd.rows[2].length.unwrap();
+
+        // The summary takes the place of the group's first task, and tasks without a group are left as they are
+        assert_eq!(
+            rolled_up
+                .rows
+                .iter()
+                .map(|row| row.title.as_ref())
+                .collect::<Vec<_>>(),
+            ["Build", "Launch"]
+        );
+        assert_eq!(rolled_up.rows[0].start_date, date(2024, 3, 4));
+        assert_eq!(rolled_up.rows[0].end_date, Some(date(2024, 3, 14)));
+        assert!((rolled_up.rows[0].offset - rd.rows[0].offset).abs() < 0.01);
+        assert!(
+            (rolled_up.rows[0].offset + rolled_up.rows[0].length.unwrap() - code_end).abs() < 0.01
+        );
+        assert!((rolled_up.rows[1].offset - rd.rows[1].offset).abs() < 0.01);
+    }
+
+    #[test]
+    fn forecasts_move_the_tasks_depending_on_late_ones() {
+        let chart_data = chart(
+            r#"{title: "Forecast", markedDate: "2024-03-06", items: [
+                {title: "Design", id: "design", startDate: "2024-03-04", duration: 3, percentComplete: 50, remainingDuration: 3},
+                {title: "Build", id: "build", duration: 2, dependsOn: ["design"]},
+                {title: "Docs", startDate: "2024-03-04", duration: 1, percentComplete: 100},
+            ]}"#,
+        );
+        let options = RenderOptions {
+            forecast: true,
+            ..RenderOptions::default()
+        };
+        let log = TestLog;
+        let tool = GanttChartTool::new(&log);
+        let rd = tool.process_chart_data(&options, &chart_data).unwrap();
+        let (design_offset, design_length) = rd.rows[0].forecast.unwrap();
+        let (build_offset, build_length) = rd.rows[1].forecast.unwrap();
+        let day_width = rd.rows[2].length.unwrap();
+
+        // Design has 3 days left at the marked date so is forecast to end on the 9th, a Saturday, and so the 11th
+        assert!((design_offset - rd.rows[0].offset).abs() < 0.01);
+        assert!((design_length - 7.0 * day_width).abs() < 0.01);
+        // Build moves to start when Design is forecast to end, keeping its planned length
+        assert!((build_offset - (design_offset + design_length)).abs() < 0.01);
+        assert!((build_length - rd.rows[1].length.unwrap()).abs() < 0.01);
+        // Done tasks stay on plan
+        assert!(rd.rows[2].forecast.is_none());
+
+        let unmarked = ChartData {
+            marked_date: None,
+            ..chart_data
+        };
+
+        assert!(tool.process_chart_data(&options, &unmarked).is_err());
+    }
+
+    #[test]
+    fn months_without_bars_can_be_left_out() {
+        let chart_data = chart(
+            r#"{title: "Pause", items: [
+                {title: "Design", startDate: "2024-01-08", duration: 5},
+                {title: "Build", startDate: "2024-04-08", duration: 5},
+            ]}"#,
+        );
+        let log = TestLog;
+        let tool = GanttChartTool::new(&log);
+        let rd = tool
+            .process_chart_data(&RenderOptions::default(), &chart_data)
+            .unwrap();
+        let skipped = tool
+            .process_chart_data(
+                &RenderOptions {
+                    skip_empty_months: true,
+                    ..RenderOptions::default()
+                },
+                &chart_data,
+            )
+            .unwrap();
+
+        assert_eq!(rd.cols.len(), 4);
+        assert!(rd.cols.iter().all(|col| !col.gap_before));
+        // February and March are left out, with a gap before April
+        assert_eq!(
+            skipped
+                .cols
+                .iter()
+                .map(|col| col.name.as_str())
+                .collect::<Vec<_>>(),
+            [rd.cols[0].name.as_str(), rd.cols[3].name.as_str()]
+        );
+        assert!(!skipped.cols[0].gap_before);
+        assert!(skipped.cols[1].gap_before);
+        assert!((skipped.rows[0].offset - rd.rows[0].offset).abs() < 0.01);
+        assert!(
+            (skipped.rows[1].offset - (rd.rows[1].offset - rd.cols[1].width - rd.cols[2].width))
+                .abs()
+                < 0.01
+        );
+    }
+
+    #[test]
+    fn breaks_are_squeezed_into_narrow_columns() {
+        let chart = |breaks: &str| {
+            chart(&format!(
+                r#"{{title: "Shutdown", breaks: [{}], items: [
+                    {{title: "A", startDate: "2024-03-04", duration: 2}},
+                ]}}"#,
+                breaks
+            ))
+        };
+        let broken = chart(r#"{start: "2024-03-11", end: "2024-03-15"}"#);
+        let unbroken = chart("");
+        let log = TestLog;
+        let tool = GanttChartTool::new(&log);
+        let rd = tool
+            .process_chart_data(&RenderOptions::default(), &broken)
+            .unwrap();
+        let whole = tool
+            .process_chart_data(&RenderOptions::default(), &unbroken)
+            .unwrap();
+
+        // March is cut in two around the break, with its name on the first part
+        assert_eq!(
+            rd.cols
+                .iter()
+                .map(|col| (col.name.is_empty(), col.is_break))
+                .collect::<Vec<_>>(),
+            [(false, false), (true, true), (true, false)]
+        );
+        assert_eq!(rd.cols[1].width, BREAK_WIDTH);
+        assert!(
+            (rd.cols[0].width + rd.cols[2].width - whole.cols[0].width * 26.0 / 31.0).abs() < 0.01
+        );
 
-        Ok(document)
+        let overlapping = chart(
+            r#"{start: "2024-03-11", end: "2024-03-15"}, {start: "2024-03-14", end: "2024-03-20"}"#,
+        );
+
+        assert!(validation_error(&overlapping).contains("overlaps the next break"));
+    }
+
+    #[test]
+    fn topological_sorts_put_tasks_straight_after_their_predecessors() {
+        let chart_data = chart(
+            r#"{title: "Sort", items: [
+                {title: "Design", id: "design", startDate: "2024-03-04", duration: 2},
+                {title: "Docs", id: "docs", startDate: "2024-03-04", duration: 3},
+                {title: "Build", id: "build", startDate: "2024-03-06", duration: 2, dependsOn: ["design"]},
+                {title: "Launch", startDate: "2024-03-08", duration: 1, dependsOn: ["build", "docs"]},
+            ]}"#,
+        );
+        let options = RenderOptions {
+            sort: Some(Sort::Topological),
+            ..RenderOptions::default()
+        };
+        let log = TestLog;
+        let rd = GanttChartTool::new(&log)
+            .process_chart_data(&options, &chart_data)
+            .unwrap();
+
+        assert_eq!(
+            rd.rows
+                .iter()
+                .map(|row| row.title.as_ref())
+                .collect::<Vec<_>>(),
+            ["Design", "Build", "Docs", "Launch"]
+        );
+        // The predecessors are the rows' new indices
+        assert_eq!(rd.rows[1].predecessors, [0]);
+        assert_eq!(rd.rows[3].predecessors, [1, 2]);
     }
 }
@@ -25,4 +25,31 @@ macro_rules! error {
   ($log: expr, $fmt: expr, $($args: tt)+) => {
     $log.error(format_args!($fmt, $($args)+))
   };
-}
\ No newline at end of file
+}
+#[macro_export]
+macro_rules! info {
+  ($log: expr, $fmt: expr) => {
+    $log.info(format_args!($fmt))
+  };
+  ($log: expr, $fmt: expr, $($args: tt)+) => {
+    $log.info(format_args!($fmt, $($args)+))
+  };
+}
+#[macro_export]
+macro_rules! debug {
+  ($log: expr, $fmt: expr) => {
+    $log.debug(format_args!($fmt))
+  };
+  ($log: expr, $fmt: expr, $($args: tt)+) => {
+    $log.debug(format_args!($fmt, $($args)+))
+  };
+}
+#[macro_export]
+macro_rules! trace {
+  ($log: expr, $fmt: expr) => {
+    $log.trace(format_args!($fmt))
+  };
+  ($log: expr, $fmt: expr, $($args: tt)+) => {
+    $log.trace(format_args!($fmt, $($args)+))
+  };
+}
@@ -0,0 +1,132 @@
+// Shrink the SVG text of a chart without changing how it looks
+
+// Combine rules with the same declarations, so ".a{fill:red;}.b{fill:red;}" becomes ".a,.b{fill:red;}"
+fn merge_style_rules(style: &str) -> String {
+    let mut rules: Vec<(String, &str)> = vec![];
+
+    for (selector, declarations) in style.split('}').filter_map(|rule| rule.split_once('{')) {
+        let selector = selector.trim();
+
+        // At-rules like @font-face can't share a block
+        if selector.starts_with('@') {
+            rules.push((selector.to_owned(), declarations));
+            continue;
+        }
+
+        match rules
+            .iter_mut()
+            .find(|(s, d)| !s.starts_with('@') && *d == declarations)
+        {
+            Some((selectors, _)) => {
+                if !selectors.split(',').any(|s| s == selector) {
+                    selectors.push(',');
+                    selectors.push_str(selector);
+                }
+            }
+            None => rules.push((selector.to_owned(), declarations)),
+        }
+    }
+
+    rules
+        .iter()
+        .map(|(selectors, declarations)| format!("{}{{{}}}", selectors, declarations))
+        .collect()
+}
+
+// Round a number to the given decimal places, dropping any trailing zeros
+fn round_number(number: &str, precision: usize) -> String {
+    match number.parse::<f64>() {
+        Ok(value) => {
+            let rounded = format!("{:.*}", precision, value);
+
+            if rounded.contains('.') {
+                rounded
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .to_owned()
+            } else {
+                rounded
+            }
+        }
+        Err(_) => number.to_owned(),
+    }
+}
+
+/// Merge identical style rules, drop empty groups and round the numbers in attributes to the given decimal places
+pub(crate) fn optimize_svg(svg: &str, precision: usize) -> String {
+    let svg = match (svg.find("<style>"), svg.find("</style>")) {
+        (Some(start), Some(end)) if start < end => format!(
+            "{}<style>{}</style>{}",
+            &svg[..start],
+            merge_style_rules(&svg[start + "<style>".len()..end]),
+            &svg[end + "</style>".len()..]
+        ),
+        _ => svg.to_owned(),
+    };
+    let mut optimized = String::with_capacity(svg.len());
+    let mut chars = svg.chars().peekable();
+    let mut in_tag = false;
+    let mut quote: Option<char> = None;
+
+    while let Some(c) = chars.next() {
+        match (in_tag, quote, c) {
+            (false, _, '<') => in_tag = true,
+            (true, None, '>') => in_tag = false,
+            (true, None, '"' | '\'') => quote = Some(c),
+            (true, Some(q), _) if c == q => quote = None,
+            (true, Some(_), '0'..='9') => {
+                let mut number = String::from(c);
+
+                while let Some(&next) = chars.peek() {
+                    // A dot is only a decimal point if a digit follows, unlike in "www.w3.org"
+                    let decimal_point = next == '.'
+                        && !number.contains('.')
+                        && chars.clone().nth(1).is_some_and(|c| c.is_ascii_digit());
+
+                    if next.is_ascii_digit() || decimal_point {
+                        number.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                // A second dot makes it a version like "3.1.0" rather than a number
+                if chars.peek() == Some(&'.') {
+                    while let Some(&next) = chars.peek() {
+                        if next.is_ascii_digit() || next == '.' {
+                            number.push(next);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+
+                    optimized.push_str(&number);
+                } else if number.contains('.') {
+                    let rounded = round_number(&number, precision);
+
+                    // Don't leave a negative zero
+                    if rounded == "0" && optimized.ends_with('-') {
+                        optimized.pop();
+                    }
+
+                    optimized.push_str(&rounded);
+                } else {
+                    optimized.push_str(&number);
+                }
+
+                continue;
+            }
+            _ => (),
+        }
+
+        optimized.push(c);
+    }
+
+    optimized
+        .lines()
+        .filter(|line| line.trim() != "<g/>")
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,94 @@
+// An outline of the chart as text, for pasting into the notes of meetings about it
+use crate::{GanttError, RenderData, RowRenderData};
+use chrono::Duration;
+use std::io::Write;
+
+// Backslash the characters that would otherwise be taken as Markdown formatting
+fn escape_markdown(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#') {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+// The dates of a task, or the date of a milestone, and who it's assigned to
+fn describe_row(rd: &RenderData, row: &RowRenderData) -> String {
+    let dates = match row.end_date {
+        Some(end_date) => format!(
+            "{} – {}",
+            row.start_date.format("%Y-%m-%d"),
+            (end_date - Duration::seconds(1)).format("%Y-%m-%d")
+        ),
+        None => row.start_date.format("%Y-%m-%d").to_string(),
+    };
+
+    if row.resource_index == rd.unassigned_index {
+        dates
+    } else {
+        format!("{} ({})", dates, rd.resources[row.resource_index])
+    }
+}
+
+/// Write the chart's groups and tasks as an outline, as Markdown or as plain text
+///
+/// The tasks without a group come first, then the tasks of each group under its name, in the order the groups are
+/// first found in the chart.
+pub(crate) fn write_outline_file(
+    mut writer: impl Write,
+    rd: &RenderData,
+    markdown: bool,
+) -> Result<(), GanttError> {
+    let mut groups: Vec<(Option<&str>, Vec<&RowRenderData>)> = vec![(None, vec![])];
+
+    for row in &rd.rows {
+        match groups.iter_mut().find(|(group, _)| *group == row.group) {
+            Some((_, rows)) => rows.push(row),
+            None => groups.push((row.group, vec![row])),
+        }
+    }
+
+    if markdown {
+        writeln!(writer, "# {}", escape_markdown(&rd.title))?;
+    } else {
+        writeln!(writer, "{}", rd.title)?;
+    }
+
+    for (group, rows) in groups.iter().filter(|(_, rows)| !rows.is_empty()) {
+        writeln!(writer)?;
+
+        match (group, markdown) {
+            (Some(group), true) => writeln!(writer, "## {}\n", escape_markdown(group))?,
+            (Some(group), false) => writeln!(writer, "{}", group)?,
+            (None, _) => {}
+        }
+
+        for row in rows {
+            if markdown {
+                writeln!(
+                    writer,
+                    "- **{}**: {}",
+                    escape_markdown(&row.title),
+                    describe_row(rd, row)
+                )?;
+            } else {
+                // Tasks are indented under the name of their group
+                writeln!(
+                    writer,
+                    "{}{}: {}",
+                    if group.is_some() { "  " } else { "" },
+                    row.title,
+                    describe_row(rd, row)
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PhaseData {
+    pub name: String,
+    pub start: NaiveDate,
+    // The last day of the phase
+    pub end: NaiveDate,
+    // Any CSS color, otherwise one is picked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
@@ -0,0 +1,106 @@
+use svg::node::element;
+
+// The shades bars are filled with, dark enough to tell apart once photocopied
+static SHADES: [u32; 3] = [0xd0d0d0, 0xa0a0a0, 0xe8e8e8];
+// The color of the pattern lines and dots drawn over the shades
+static PATTERN_COLOR: &str = "#333333";
+
+// The luma of a color as a gray of the same lightness
+pub(crate) fn grayscale(rgb: u32) -> u32 {
+    let r = (rgb >> 16 & 0xff) as f32;
+    let g = (rgb >> 8 & 0xff) as f32;
+    let b = (rgb & 0xff) as f32;
+    let y = (0.299 * r + 0.587 * g + 0.114 * b).round().min(255.0) as u32;
+
+    y << 16 | y << 8 | y
+}
+
+// Replace each `#rrggbb` color in some CSS with its gray
+pub(crate) fn grayscale_css(css: &str) -> String {
+    let mut s = String::with_capacity(css.len());
+    let mut rest = css;
+
+    while let Some(i) = rest.find('#') {
+        s.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        let hex = rest.get(1..7).filter(|hex| {
+            hex.chars().all(|c| c.is_ascii_hexdigit())
+                && !rest[7..].starts_with(|c: char| c.is_ascii_alphanumeric())
+        });
+
+        match hex.and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+            Some(rgb) => {
+                s.push_str(&format!("#{:06x}", grayscale(rgb)));
+                rest = &rest[7..];
+            }
+            None => {
+                s.push('#');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    s.push_str(rest);
+    s
+}
+
+// The shade of gray the bars with this color index are filled with
+pub(crate) fn shade(index: usize) -> u32 {
+    SHADES[(index / 5) % SHADES.len()]
+}
+
+/// A pattern telling the bars with this color index apart without color: solid, diagonal
+/// stripes, horizontal stripes, cross-hatching or dots, over a shade of gray
+pub(crate) fn pattern(color_class: &str, index: usize) -> element::Pattern {
+    let size = 8;
+    let line = |x1: i32, y1: i32, x2: i32, y2: i32| {
+        element::Line::new()
+            .set("x1", x1)
+            .set("y1", y1)
+            .set("x2", x2)
+            .set("y2", y2)
+            .set("stroke", PATTERN_COLOR)
+            .set("stroke-width", 1.5)
+    };
+    let mut pattern = element::Pattern::new()
+        .set("id", format!("{}-{}-pattern", color_class, index))
+        .set("width", size)
+        .set("height", size)
+        .set("patternUnits", "userSpaceOnUse")
+        .add(
+            element::Rectangle::new()
+                .set("width", size)
+                .set("height", size)
+                .set("fill", format!("#{:06x}", shade(index))),
+        );
+
+    match index % 5 {
+        1 => {
+            pattern = pattern
+                .set("patternTransform", "rotate(45)")
+                .add(line(0, 0, 0, size));
+        }
+        2 => {
+            pattern = pattern.add(line(0, size / 2, size, size / 2));
+        }
+        3 => {
+            pattern = pattern
+                .set("patternTransform", "rotate(45)")
+                .add(line(0, 0, 0, size))
+                .add(line(0, 0, size, 0));
+        }
+        4 => {
+            pattern = pattern.add(
+                element::Circle::new()
+                    .set("cx", size / 2)
+                    .set("cy", size / 2)
+                    .set("r", 1.5)
+                    .set("fill", PATTERN_COLOR),
+            );
+        }
+        _ => (),
+    }
+
+    pattern
+}
@@ -0,0 +1,153 @@
+use crate::GanttError;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use clap::ValueEnum;
+use serde_json::json;
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+// The most a Teams webhook takes in a message, leaving room for the rest of the message around the chart
+static TEAMS_IMAGE_LIMIT: usize = 27 * 1024;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum WebhookService {
+    /// A Slack incoming webhook
+    Slack,
+    /// A Microsoft Teams incoming webhook
+    Teams,
+}
+
+impl WebhookService {
+    // Slack's webhooks are all on its own host, so anything else is taken to be Teams
+    pub(crate) fn from_url(url: &str) -> WebhookService {
+        if url.contains("hooks.slack.com") {
+            WebhookService::Slack
+        } else {
+            WebhookService::Teams
+        }
+    }
+}
+
+/// A PNG as a data URL, to send it in a Teams message
+///
+/// Teams webhooks only take messages of up to 28 KB, so bigger charts have to be published elsewhere.
+pub(crate) fn png_data_url(png: &[u8]) -> Result<String, GanttError> {
+    let url = format!("data:image/png;base64,{}", STANDARD.encode(png));
+
+    if url.len() > TEAMS_IMAGE_LIMIT {
+        return Err(GanttError::UsageError(format!(
+            "The chart is {} KB as a PNG, too big for a Teams webhook message, so publish it elsewhere and give --image-url",
+            png.len().div_ceil(1024)
+        )));
+    }
+
+    Ok(url)
+}
+
+/// The message posting a chart to a webhook, showing the chart from `image_url` if given
+///
+/// Slack webhooks can't take images, so the chart is only shown there if it is published elsewhere, but Teams can
+/// show it from a data URL.
+pub(crate) fn webhook_message(
+    service: WebhookService,
+    text: &str,
+    image_url: Option<&str>,
+) -> serde_json::Value {
+    match service {
+        WebhookService::Slack => {
+            let mut blocks = vec![json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": text },
+            })];
+
+            if let Some(image_url) = image_url {
+                blocks.push(json!({
+                    "type": "image",
+                    "image_url": image_url,
+                    "alt_text": text,
+                }));
+            }
+
+            json!({ "text": text, "blocks": blocks })
+        }
+        WebhookService::Teams => {
+            let mut body = vec![
+                json!({ "type": "TextBlock", "text": text, "wrap": true, "weight": "Bolder" }),
+            ];
+
+            if let Some(image_url) = image_url {
+                body.push(json!({ "type": "Image", "url": image_url, "altText": text }));
+            }
+
+            json!({
+                "type": "message",
+                "attachments": [{
+                    "contentType": "application/vnd.microsoft.card.adaptive",
+                    "content": {
+                        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                        "type": "AdaptiveCard",
+                        "version": "1.4",
+                        "body": body,
+                    },
+                }],
+            })
+        }
+    }
+}
+
+// A value quoted for a curl config file
+fn curl_config_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Post a message to a webhook
+///
+/// Webhooks are HTTPS, so the message is sent with `curl` rather than a TLS stack of our own.  The webhook URL is a
+/// secret, so it is given to `curl` in a config on its standard input, along with the message, rather than in its
+/// arguments where other users could see it.
+pub(crate) fn post_webhook(url: &str, message: &serde_json::Value) -> Result<(), GanttError> {
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--header",
+            "Content-Type: application/json",
+            "--config",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => GanttError::UsageError(
+                "Publishing needs curl to post to the webhook, install it or put it on the PATH"
+                    .to_owned(),
+            ),
+            _ => GanttError::IoError(io::Error::new(
+                e.kind(),
+                format!("Unable to run curl to post to the webhook: {}", e),
+            )),
+        })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        write!(
+            stdin,
+            "url = {}\ndata-binary = {}\n",
+            curl_config_value(url),
+            curl_config_value(&message.to_string())
+        )?;
+    }
+
+    let status = child.wait()?;
+
+    if !status.success() {
+        return Err(GanttError::IoError(io::Error::other(format!(
+            "Posting to the webhook failed with curl {}",
+            status
+        ))));
+    }
+
+    Ok(())
+}
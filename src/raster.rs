@@ -0,0 +1,80 @@
+use crate::{GanttError, OutputFormat, RenderOptions};
+use image::{codecs::jpeg::JpegEncoder, ExtendedColorType};
+use resvg::{
+    tiny_skia::{Color, Pixmap, Transform},
+    usvg::{Options, Tree},
+};
+use std::io::Write;
+
+/// Parse the chart into a tree of paths, with system fonts and any embedded font available for the text
+pub(crate) fn parse_tree(svg: &str, embed_font: Option<&[u8]>) -> Result<Tree, GanttError> {
+    let mut options = Options::default();
+
+    options.fontdb_mut().load_system_fonts();
+
+    // The renderer doesn't read fonts from the style data URI
+    if let Some(data) = embed_font {
+        options.fontdb_mut().load_font_data(data.to_vec());
+    }
+
+    Tree::from_str(svg, &options).map_err(|e| GanttError::LayoutError(e.to_string()))
+}
+
+/// Rasterize the chart and write it as a PNG, WebP or JPEG image
+pub(crate) fn write_raster_file(
+    mut writer: impl Write,
+    svg: &str,
+    options: &RenderOptions,
+) -> Result<(), GanttError> {
+    let tree = parse_tree(svg, options.embed_font.as_deref())?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = match Pixmap::new(size.width(), size.height()) {
+        Some(pixmap) => pixmap,
+        None => {
+            return Err(GanttError::LayoutError(
+                "Chart is too small or too large to rasterize".to_owned(),
+            ))
+        }
+    };
+
+    // The SVG background color is a style that is not rendered, so fill it in here
+    pixmap.fill(Color::WHITE);
+    resvg::render(&tree, Transform::default(), &mut pixmap.as_mut());
+
+    match options.format {
+        OutputFormat::Png => writer.write_all(
+            &pixmap
+                .encode_png()
+                .map_err(|e| GanttError::LayoutError(e.to_string()))?,
+        )?,
+        OutputFormat::Webp => writer.write_all(
+            &webp::Encoder::from_rgba(pixmap.data(), pixmap.width(), pixmap.height())
+                .encode(options.quality as f32),
+        )?,
+        OutputFormat::Jpeg => {
+            // The pixmap is opaque so the alpha channel can simply be dropped
+            let rgb: Vec<u8> = pixmap
+                .data()
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+                .collect();
+
+            JpegEncoder::new_with_quality(&mut writer, options.quality)
+                .encode(
+                    &rgb,
+                    pixmap.width(),
+                    pixmap.height(),
+                    ExtendedColorType::Rgb8,
+                )
+                .map_err(|e| GanttError::LayoutError(e.to_string()))?;
+        }
+        _ => {
+            return Err(GanttError::LayoutError(format!(
+                "{:?} is not a raster format",
+                options.format
+            )))
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use crate::{days_text, html::escape_html, ChartData, GanttError, RenderData, RoundDurations};
+use chrono::{Duration, NaiveDateTime};
+use std::io::Write;
+
+// Styles are given on each element, as Confluence and email clients drop style sheets
+static BODY_STYLE: &str = "font-family:Arial,sans-serif;font-size:10pt;color:#222222;";
+static TABLE_STYLE: &str = "border-collapse:collapse;margin-bottom:16px;";
+static HEADING_CELL_STYLE: &str =
+    "border:1px solid #cccccc;padding:4px 8px;background-color:#f0f0f0;text-align:left;";
+static CELL_STYLE: &str = "border:1px solid #cccccc;padding:4px 8px;";
+static NUMBER_CELL_STYLE: &str = "border:1px solid #cccccc;padding:4px 8px;text-align:right;";
+
+// The working days from a start date up to an exclusive end date
+fn working_days(chart_data: &ChartData, start_date: NaiveDateTime, end_date: NaiveDateTime) -> u32 {
+    let mut days = 0;
+    let mut date = start_date;
+
+    while date < end_date {
+        if chart_data.is_working_day(date.date()) {
+            days += 1;
+        }
+
+        date += Duration::days(1);
+    }
+
+    days
+}
+
+fn table(headings: &[&str], rows: Vec<Vec<(String, bool)>>) -> String {
+    let mut html = format!("<table style=\"{}\">\n<tr>", TABLE_STYLE);
+
+    for heading in headings {
+        html.push_str(&format!(
+            "<th style=\"{}\">{}</th>",
+            HEADING_CELL_STYLE,
+            escape_html(heading)
+        ));
+    }
+
+    html.push_str("</tr>\n");
+
+    for row in rows {
+        html.push_str("<tr>");
+
+        // Numbers are lined up on the right
+        for (cell, is_number) in row {
+            html.push_str(&format!(
+                "<td style=\"{}\">{}</td>",
+                if is_number {
+                    NUMBER_CELL_STYLE
+                } else {
+                    CELL_STYLE
+                },
+                escape_html(&cell)
+            ));
+        }
+
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>\n");
+    html
+}
+
+/// Write a report of the chart as a single HTML page: the chart itself, then tables of the tasks, how much of
+/// their time the resources are working on them and the milestones
+///
+/// The durations are in working days, rounded as given by `round_durations`.
+pub(crate) fn write_report_html(
+    mut writer: impl Write,
+    chart_data: &ChartData,
+    rd: &RenderData,
+    svg: &str,
+    round_durations: Option<RoundDurations>,
+) -> Result<(), GanttError> {
+    let labels = rd.labels;
+    let duration = |days: u32| {
+        days_text(
+            days as f32,
+            round_durations,
+            chart_data.working_days_per_week(),
+        )
+    };
+    // The percent complete says more than whether a task is open, when given
+    let status = |open: bool, percent_complete: Option<f32>| match percent_complete {
+        Some(percent_complete) if percent_complete >= 100.0 => labels.done.clone(),
+        Some(percent_complete) if percent_complete > 0.0 => labels.in_progress.clone(),
+        Some(_) => labels.open.clone(),
+        None if open => labels.open.clone(),
+        None => labels.done.clone(),
+    };
+    let format_date = |date: NaiveDateTime| date.format("%Y-%m-%d").to_string();
+    let tasks = table(
+        &[
+            &labels.tasks,
+            &labels.resource,
+            &labels.start,
+            &labels.end,
+            &labels.duration,
+            &labels.complete,
+            &labels.status,
+        ],
+        rd.rows
+            .iter()
+            .filter_map(|row| {
+                let end_date = row.end_date?;
+
+                Some(vec![
+                    (row.title.to_string(), false),
+                    (rd.resources[row.resource_index].to_string(), false),
+                    (format_date(row.start_date), false),
+                    (format_date(end_date - Duration::seconds(1)), false),
+                    (
+                        duration(working_days(chart_data, row.start_date, end_date)),
+                        true,
+                    ),
+                    (
+                        format!(
+                            "{:.0}%",
+                            row.percent_complete
+                                .unwrap_or(if row.open { 0.0 } else { 100.0 })
+                        ),
+                        true,
+                    ),
+                    (status(row.open, row.percent_complete), false),
+                ])
+            })
+            .collect(),
+    );
+    // The share of the working days of the chart each resource has tasks on, which is over 100% for
+    // resources with tasks at the same time
+    let chart_days = working_days(chart_data, rd.start_date, rd.end_date + Duration::days(1));
+    let utilization = table(
+        &[
+            &labels.resource,
+            &labels.tasks,
+            &labels.duration,
+            &labels.utilization,
+        ],
+        rd.resources
+            .iter()
+            .enumerate()
+            .map(|(i, resource)| {
+                let (count, days) = rd
+                    .rows
+                    .iter()
+                    .filter(|row| row.resource_index == i)
+                    .filter_map(|row| Some(working_days(chart_data, row.start_date, row.end_date?)))
+                    .fold((0, 0), |(count, total), days| (count + 1, total + days));
+
+                vec![
+                    (resource.to_string(), false),
+                    (count.to_string(), true),
+                    (duration(days), true),
+                    (
+                        format!("{:.0}%", days as f32 * 100.0 / chart_days.max(1) as f32),
+                        true,
+                    ),
+                ]
+            })
+            .collect(),
+    );
+    let milestones: Vec<Vec<(String, bool)>> = rd
+        .rows
+        .iter()
+        .filter(|row| row.end_date.is_none())
+        .map(|row| {
+            vec![
+                (row.title.to_string(), false),
+                (format_date(row.start_date), false),
+                (status(row.open, row.percent_complete), false),
+            ]
+        })
+        .collect();
+    let milestones = if milestones.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<h2>{}</h2>\n{}",
+            escape_html(&labels.milestones),
+            table(&[&labels.tasks, &labels.date, &labels.status], milestones)
+        )
+    };
+    let status_date = rd.marked_date.map_or(String::new(), |date| {
+        format!("<p>{}</p>\n", format_date(date))
+    });
+
+    write!(
+        writer,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body style=\"{}\">\n<h1>{title}</h1>\n{}<div>\n{}\n</div>\n<h2>{}</h2>\n{}<h2>{}</h2>\n{}{}</body>\n</html>\n",
+        BODY_STYLE,
+        status_date,
+        svg,
+        escape_html(&labels.tasks),
+        tasks,
+        escape_html(&labels.utilization),
+        utilization,
+        milestones,
+        title = escape_html(&rd.title),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AbsenceData {
+    pub start: NaiveDate,
+    // The last day of the absence
+    pub end: NaiveDate,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(from = "ResourceDataRepr")]
+pub struct ResourceData {
+    pub name: String,
+    // Full time equivalents available, e.g. 0.5 for half time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub absences: Vec<AbsenceData>,
+}
+
+// A resource can be given as just its name
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ResourceDataRepr {
+    Name(String),
+    Full {
+        name: String,
+        capacity: Option<f32>,
+        #[serde(default)]
+        absences: Vec<AbsenceData>,
+    },
+}
+
+// The resource of the items without one
+pub(crate) static UNNAMED_RESOURCE: ResourceData = ResourceData {
+    name: String::new(),
+    capacity: None,
+    absences: Vec::new(),
+};
+
+impl ResourceData {
+    pub fn is_absent(&self, date: NaiveDate) -> bool {
+        self.absences
+            .iter()
+            .any(|absence| absence.start <= date && date <= absence.end)
+    }
+
+    // The first letter of each word of the name, e.g. "JD" for "Jane Doe"
+    pub(crate) fn initials(&self) -> String {
+        self.name
+            .split_whitespace()
+            .filter_map(|word| word.chars().next())
+            .flat_map(char::to_uppercase)
+            .collect()
+    }
+}
+
+impl From<ResourceDataRepr> for ResourceData {
+    fn from(repr: ResourceDataRepr) -> Self {
+        match repr {
+            ResourceDataRepr::Name(name) => ResourceData {
+                name,
+                capacity: None,
+                absences: vec![],
+            },
+            ResourceDataRepr::Full {
+                name,
+                capacity,
+                absences,
+            } => ResourceData {
+                name,
+                capacity,
+                absences,
+            },
+        }
+    }
+}
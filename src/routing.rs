@@ -0,0 +1,70 @@
+// Route dependency arrows along the gaps between the bars, with only horizontal and vertical lines
+
+// How far an arrow runs straight out of a bar and into the next, and the room left around what it goes by
+static STUB: f32 = 8.0;
+static CLEARANCE: f32 = 3.0;
+
+// The first X from a start that isn't within any of the spans, which are left and right X offsets
+fn clear_x(spans: &[(f32, f32)], start: f32) -> f32 {
+    let mut spans: Vec<(f32, f32)> = spans
+        .iter()
+        .map(|&(left, right)| (left - CLEARANCE, right + CLEARANCE))
+        .collect();
+    let mut x = start;
+
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    for (left, right) in spans {
+        if left <= x && x < right {
+            x = right;
+        }
+    }
+
+    x
+}
+
+/// The points of an arrow from the end of something in one row to the start of something in another
+///
+/// The vertical lines go where nothing in the rows they cross is in the way, given as the spans taken up in each row,
+/// and the horizontal lines go through the middle of the end rows or along the line between two rows.  Arrows that
+/// have to go back to the left go along the line next to the row they end in.
+pub(crate) fn route_arrow(
+    from: (f32, usize),
+    to: (f32, usize),
+    row_spans: &[Vec<(f32, f32)>],
+    top: f32,
+    row_height: f32,
+) -> Vec<(f32, f32)> {
+    let ((from_x, from_row), (to_x, to_row)) = (from, to);
+    let row_middle = |row: usize| top + (row as f32 + 0.5) * row_height;
+    let crossed: Vec<(f32, f32)> = (from_row.min(to_row) + 1..from_row.max(to_row))
+        .flat_map(|row| row_spans[row].iter().copied())
+        .collect();
+    let x = clear_x(&crossed, from_x + STUB);
+
+    if x <= to_x - STUB {
+        vec![
+            (from_x, row_middle(from_row)),
+            (x, row_middle(from_row)),
+            (x, row_middle(to_row)),
+            (to_x, row_middle(to_row)),
+        ]
+    } else {
+        // The line between the rows on the side of the row it ends in that it comes from
+        let y = if to_row > from_row {
+            top + to_row as f32 * row_height
+        } else {
+            top + (to_row + 1) as f32 * row_height
+        };
+        let back_x = to_x - STUB;
+
+        vec![
+            (from_x, row_middle(from_row)),
+            (x, row_middle(from_row)),
+            (x, y),
+            (back_x, y),
+            (back_x, row_middle(to_row)),
+            (to_x, row_middle(to_row)),
+        ]
+    }
+}
@@ -0,0 +1,64 @@
+use crate::item_data::{deserialize_date_or_date_time, deserialize_days};
+use crate::{ChartData, GanttError};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// Another option for the plan, changing some of its items
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ScenarioData {
+    pub items: Vec<ScenarioItemData>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ScenarioItemData {
+    // The ID or title of the item in the chart to change
+    pub item: String,
+
+    // Days, which can be fractional
+    #[serde(
+        default,
+        deserialize_with = "deserialize_days",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration: Option<f32>,
+
+    #[serde(
+        rename = "startDate",
+        default,
+        deserialize_with = "deserialize_date_or_date_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub start_date: Option<NaiveDateTime>,
+}
+
+impl ScenarioData {
+    // A copy of the chart with the scenario's changes made to its items
+    pub(crate) fn apply(&self, chart_data: &ChartData) -> Result<ChartData, GanttError> {
+        let mut scenario_chart = chart_data.clone();
+
+        for scenario_item in &self.items {
+            let i = chart_data.find_item(&scenario_item.item).ok_or_else(|| {
+                GanttError::validation(
+                    None,
+                    "scenario",
+                    format!(
+                        "There is no item with the ID or title '{}' to change",
+                        scenario_item.item
+                    ),
+                )
+            })?;
+            let item = &mut scenario_chart.items[i];
+
+            if scenario_item.duration.is_some() {
+                item.duration = scenario_item.duration;
+            }
+
+            if scenario_item.start_date.is_some() {
+                item.start_date = scenario_item.start_date;
+                item.start_ms = None;
+            }
+        }
+
+        Ok(scenario_chart)
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{html::escape_html, info, output, warning, GanttChartLog, GanttError};
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    thread,
+    time::{Duration, UNIX_EPOCH},
+};
+
+// Enough to render for a few browser tabs at once without a thread for every connection
+static SERVE_THREADS: usize = 4;
+
+// How long a connection can take to send its request before it is dropped, so it doesn't hold up a thread
+static REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// More request headers than a browser sends, so a connection can't keep a thread reading them for ever
+static MAX_REQUEST_LINES: usize = 100;
+
+// Reloads the page when the chart file's modification time changes
+static LIVE_RELOAD_SCRIPT: &str = r#"<script>
+(function () {
+  let modified = null;
+  setInterval(async function () {
+    try {
+      const text = await (await fetch("/modified", { cache: "no-store" })).text();
+      if (modified !== null && text !== modified) {
+        location.reload();
+      }
+      modified = text;
+    } catch (e) {}
+  }, 1000);
+})();
+</script>
+"#;
+
+fn respond(mut stream: &TcpStream, status: &str, content_type: &str, body: &str) {
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+}
+
+/// Serve the HTML page made by `render` on a local port until the process is stopped
+///
+/// The page is asked for from `render` for every request, so reloading the browser picks up changes to the chart file.
+/// Requests are handled a few at a time, and rendering errors are shown in the browser rather than stopping the server.
+/// Given a `live_reload` file, the page reloads itself whenever the file is changed.
+pub(crate) fn serve_html(
+    log: &dyn GanttChartLog,
+    port: u16,
+    live_reload: Option<&Path>,
+    render: impl Fn() -> Result<String, GanttError> + Sync,
+) -> Result<(), GanttError> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+
+    output!(log, "Serving the chart at http://127.0.0.1:{}/", port);
+
+    // A few threads of their own each taking connections in turn, as blocking on connections would starve a
+    // rayon pool
+    thread::scope(|scope| {
+        for _ in 0..SERVE_THREADS {
+            let (listener, render) = (&listener, &render);
+
+            scope.spawn(move || {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(stream) => handle_request(log, stream, live_reload, render),
+                        Err(e) => warning!(log, "Unable to accept a connection: {}", e),
+                    }
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+// The modification time of a file, as text that changes whenever the file does
+fn modified_text(path: &Path) -> String {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or("".to_owned(), |duration| duration.as_nanos().to_string())
+}
+
+fn handle_request(
+    log: &dyn GanttChartLog,
+    stream: TcpStream,
+    live_reload: Option<&Path>,
+    render: &(impl Fn() -> Result<String, GanttError> + Sync),
+) {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    let mut header = String::new();
+
+    if stream.set_read_timeout(Some(REQUEST_TIMEOUT)).is_err()
+        || reader.read_line(&mut request_line).is_err()
+    {
+        return;
+    }
+
+    // Every path gets the chart, so the headers are only read to the blank line after them, as closing the
+    // connection with them unread can reset it before the browser has the response
+    for _ in 0..MAX_REQUEST_LINES {
+        header.clear();
+
+        match reader.read_line(&mut header) {
+            Ok(0) | Err(_) => return,
+            Ok(_) if header.trim_end().is_empty() => break,
+            Ok(_) => (),
+        }
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+
+    if let Some(live_reload) = live_reload {
+        // Polled by the page, so not worth logging
+        if path == "/modified" {
+            respond(&stream, "200 OK", "text/plain", &modified_text(live_reload));
+            return;
+        }
+    }
+
+    info!(log, "{}", request_line.trim_end());
+
+    match (render(), live_reload) {
+        (Ok(html), None) => respond(&stream, "200 OK", "text/html", &html),
+        (Ok(html), Some(_)) => respond(
+            &stream,
+            "200 OK",
+            "text/html",
+            &html.replacen("</body>", &format!("{}</body>", LIVE_RELOAD_SCRIPT), 1),
+        ),
+        (Err(e), None) => {
+            warning!(log, "{}", e);
+            respond(
+                &stream,
+                "500 Internal Server Error",
+                "text/plain",
+                &e.to_string(),
+            );
+        }
+        // The error is shown in a page of its own, which reloads once the chart file is fixed
+        (Err(e), Some(_)) => {
+            warning!(log, "{}", e);
+            respond(
+                &stream,
+                "500 Internal Server Error",
+                "text/html",
+                &format!(
+                    "<!DOCTYPE html>\n<html>\n<body>\n<pre>{}</pre>\n{}</body>\n</html>\n",
+                    escape_html(&e.to_string()),
+                    LIVE_RELOAD_SCRIPT
+                ),
+            );
+        }
+    }
+}
@@ -0,0 +1,125 @@
+// Try out delays to tasks to see how they move the rest of the plan
+use crate::{days_duration, ChartData, GanttError, RenderData, SECONDS_PER_DAY};
+use chrono::{Duration, NaiveDateTime};
+
+/// A task's ID or title and the days it finishes late by, or early by if negative
+#[derive(Clone, Debug)]
+pub(crate) struct Delay {
+    task: String,
+    days: f32,
+}
+
+/// Read a delay given as a task and a number of days, such as "design:+5d" or "Visual design:2.5"
+///
+/// The task is everything before the last colon, so titles can have colons in them.
+pub(crate) fn parse_delay(s: &str) -> Result<Delay, String> {
+    let (task, days) = s.rsplit_once(':').ok_or_else(|| {
+        format!(
+            "'{}' must be a task and the days to delay it by, such as 'design:+5d'",
+            s
+        )
+    })?;
+    let days = days
+        .trim()
+        .trim_end_matches('d')
+        .trim_end()
+        .parse::<f32>()
+        .ok()
+        .filter(|days| days.is_finite())
+        .ok_or_else(|| format!("invalid number of days '{}'", days))?;
+
+    Ok(Delay {
+        task: task.trim().to_owned(),
+        days,
+    })
+}
+
+/// A copy of the chart with the delays made, given the dates the items were scheduled on before
+///
+/// Tasks are made longer so they finish later, moving the items that depend on them.  Milestones have no duration,
+/// so they are moved from the dates they were scheduled on instead.
+pub(crate) fn apply_delays(
+    chart_data: &ChartData,
+    delays: &[Delay],
+    start_dates: &[NaiveDateTime],
+) -> Result<ChartData, GanttError> {
+    let mut delayed = chart_data.clone();
+
+    for delay in delays {
+        let i = chart_data.find_item(&delay.task).ok_or_else(|| {
+            GanttError::UsageError(format!(
+                "There is no item with the ID or title '{}' to delay",
+                delay.task
+            ))
+        })?;
+        let item = &mut delayed.items[i];
+
+        if let Some(segment) = item.segments.last_mut() {
+            segment.duration = (segment.duration + delay.days).max(0.0);
+        } else if let Some(duration) = item.duration {
+            item.duration = Some((duration + delay.days).max(0.0));
+        } else {
+            item.start_date = Some(start_dates[i] + days_duration(delay.days));
+            item.start_ms = None;
+        }
+    }
+
+    Ok(delayed)
+}
+
+/// Set the baseline finish of the items that moved to where they finished before, so the chart shows how far
+///
+/// Both sets of render data must have a row for each item.
+pub(crate) fn mark_moves(delayed: &mut ChartData, before: &RenderData, after: &RenderData) {
+    for ((item, row_before), row_after) in delayed
+        .items
+        .iter_mut()
+        .zip(before.rows.iter())
+        .zip(after.rows.iter())
+    {
+        if (row_before.start_date, row_before.end_date)
+            != (row_after.start_date, row_after.end_date)
+        {
+            // The baseline finish of a task is its last day, where the end date is the day after
+            item.baseline_finish = Some(match row_before.end_date {
+                Some(end_date) => end_date - Duration::days(1),
+                None => row_before.start_date,
+            });
+        }
+    }
+}
+
+/// A line for each milestone that moved, saying by how many days and from and to which dates
+///
+/// Both sets of render data must have a row for each item.
+pub(crate) fn describe_moves(
+    chart_data: &ChartData,
+    before: &RenderData,
+    after: &RenderData,
+) -> Vec<String> {
+    chart_data
+        .items
+        .iter()
+        .zip(before.rows.iter().zip(after.rows.iter()))
+        .filter(|(_, (row_before, row_after))| {
+            row_before.length.is_none()
+                && row_after.length.is_none()
+                && row_before.start_date != row_after.start_date
+        })
+        .map(|(item, (row_before, row_after))| {
+            let days = (row_after.start_date - row_before.start_date).num_seconds() as f32
+                / SECONDS_PER_DAY;
+            let rounded_days = (days.abs() * 10.0).round() / 10.0;
+
+            format!(
+                "Milestone '{}' moves {} {} {}, from {} to {}",
+                item.title,
+                rounded_days,
+                if rounded_days == 1.0 { "day" } else { "days" },
+                if days > 0.0 { "later" } else { "earlier" },
+                row_before.start_date.date(),
+                row_after.start_date.date()
+            )
+        })
+        .collect()
+}
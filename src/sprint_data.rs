@@ -0,0 +1,10 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SprintData {
+    pub name: String,
+    pub start: NaiveDate,
+    // The last day of the sprint
+    pub end: NaiveDate,
+}
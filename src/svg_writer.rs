@@ -0,0 +1,108 @@
+// Write SVG a node at a time, so large charts never have to be held in memory all at once
+use std::{
+    fmt::Display,
+    io::{self, BufWriter, Write},
+};
+use svg::{node::element::Element, Node};
+
+// An element that has been started, whose start tag is held back until something is written in it, as elements
+// with nothing in them are written as empty-element tags
+struct OpenElement {
+    name: String,
+    empty_tag: String,
+    written: bool,
+}
+
+/// Writes SVG formatted the same as the `svg` crate formats a whole document, with each element on a line of its
+/// own
+pub(crate) struct SvgWriter<W: Write> {
+    writer: BufWriter<W>,
+    open: Vec<OpenElement>,
+    elements: usize,
+}
+
+impl<W: Write> SvgWriter<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        SvgWriter {
+            writer: BufWriter::new(writer),
+            open: vec![],
+            elements: 0,
+        }
+    }
+
+    // Write the start tags of the started elements that haven't been written yet, before something goes in them
+    fn write_start_tags(&mut self) -> io::Result<()> {
+        for (i, element) in self.open.iter_mut().enumerate() {
+            if !element.written {
+                if i > 0 {
+                    writeln!(self.writer)?;
+                }
+
+                let empty_tag = &element.empty_tag;
+
+                write!(self.writer, "{}>", &empty_tag[..empty_tag.len() - 2])?;
+                element.written = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Start an element with the attributes of the one given, which the nodes written until it is ended go in
+    pub(crate) fn start(&mut self, element: impl Node) {
+        let mut empty = Element::new(element.get_name());
+
+        for (name, value) in element.get_attributes().into_iter().flatten() {
+            empty.assign(name.as_str(), value.clone());
+        }
+
+        self.open.push(OpenElement {
+            name: element.get_name().to_owned(),
+            empty_tag: empty.to_string(),
+            written: false,
+        });
+        self.elements += 1;
+    }
+
+    /// Write a whole node in the element last started
+    pub(crate) fn write(&mut self, node: impl Display) -> io::Result<()> {
+        let node = node.to_string();
+
+        self.write_start_tags()?;
+
+        if !self.open.is_empty() {
+            writeln!(self.writer)?;
+        }
+
+        // Text has any < in it escaped, so each one followed by a name starts an element
+        self.elements += node
+            .as_bytes()
+            .windows(2)
+            .filter(|pair| pair[0] == b'<' && pair[1].is_ascii_alphabetic())
+            .count();
+        self.writer.write_all(node.as_bytes())
+    }
+
+    /// End the element last started
+    pub(crate) fn end(&mut self) -> io::Result<()> {
+        let element = self
+            .open
+            .pop()
+            .expect("An element must be started before it is ended");
+
+        if element.written {
+            write!(self.writer, "\n</{}>", element.name)
+        } else {
+            // The element was counted when it was started
+            self.elements -= 1;
+            self.write(element.empty_tag)
+        }
+    }
+
+    /// Write out anything still buffered, returning the number of elements written
+    pub(crate) fn finish(mut self) -> io::Result<usize> {
+        self.writer.flush()?;
+
+        Ok(self.elements)
+    }
+}
@@ -0,0 +1,113 @@
+use resvg::usvg::fontdb::{Database, Family, Query};
+use std::{borrow::Cow, sync::OnceLock};
+use ttf_parser::Face;
+
+static PIXELS_PER_POINT: f32 = 4.0 / 3.0;
+// Rough widths in ems for characters that no font is available for
+static NARROW_CHAR_WIDTH: f32 = 0.55;
+static WIDE_CHAR_WIDTH: f32 = 1.0;
+
+static SYSTEM_FONT: OnceLock<Option<(Vec<u8>, u32)>> = OnceLock::new();
+
+// The installed font that the chart's Arial text will most likely be shown in, loaded once
+fn system_font() -> Option<&'static (Vec<u8>, u32)> {
+    SYSTEM_FONT
+        .get_or_init(|| {
+            let mut fontdb = Database::new();
+
+            fontdb.load_system_fonts();
+
+            let id = fontdb.query(&Query {
+                families: &[Family::Name("Arial"), Family::SansSerif],
+                ..Query::default()
+            })?;
+
+            fontdb.with_face_data(id, |data, index| (data.to_vec(), index))
+        })
+        .as_ref()
+}
+
+// CJK characters and emoji take up a full em in most fonts
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD)
+}
+
+/// Measures text using the glyph widths of the embedded font or the installed Arial
+pub(crate) struct TextMeasure<'a> {
+    face: Option<Face<'a>>,
+}
+
+impl<'a> TextMeasure<'a> {
+    pub(crate) fn new(embed_font: Option<&'a [u8]>) -> Self {
+        // Fonts that can't be read here, like WOFF fonts, are measured as the installed font
+        let face = embed_font
+            .and_then(|data| Face::parse(data, 0).ok())
+            .or_else(|| system_font().and_then(|(data, index)| Face::parse(data, *index).ok()));
+
+        TextMeasure { face }
+    }
+
+    // The width of a character in ems
+    fn char_width(&self, c: char) -> f32 {
+        self.face
+            .as_ref()
+            .and_then(|face| {
+                let advance = face.glyph_hor_advance(face.glyph_index(c)?)?;
+
+                Some(advance as f32 / face.units_per_em() as f32)
+            })
+            .unwrap_or(if is_wide(c) {
+                WIDE_CHAR_WIDTH
+            } else {
+                NARROW_CHAR_WIDTH
+            })
+    }
+
+    /// The width in pixels of the text at a font size in points
+    pub(crate) fn width(&self, text: &str, font_size: f32) -> f32 {
+        text.chars().map(|c| self.char_width(c)).sum::<f32>() * font_size * PIXELS_PER_POINT
+    }
+
+    /// The height in pixels of a line of text at a font size in points
+    pub(crate) fn height(&self, font_size: f32) -> f32 {
+        font_size * PIXELS_PER_POINT
+    }
+
+    /// The text, cut short with an ellipsis if it is wider than the maximum width
+    pub(crate) fn truncate<'t>(
+        &self,
+        text: &'t str,
+        font_size: f32,
+        max_width: f32,
+    ) -> Cow<'t, str> {
+        if self.width(text, font_size) <= max_width {
+            return Cow::Borrowed(text);
+        }
+
+        let max_ems = (max_width / (font_size * PIXELS_PER_POINT)) - self.char_width('…');
+        let mut ems = 0.0;
+        let mut end = 0;
+
+        for (i, c) in text.char_indices() {
+            ems += self.char_width(c);
+
+            if ems > max_ems {
+                break;
+            }
+
+            end = i + c.len_utf8();
+        }
+
+        Cow::Owned(format!("{}…", text[..end].trim_end()))
+    }
+}
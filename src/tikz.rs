@@ -0,0 +1,111 @@
+use crate::{GanttError, RenderData};
+use chrono::{Duration, NaiveDateTime};
+use std::{fmt::Write as _, io::Write};
+
+pub(crate) fn escape_latex(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn iso_date(date: NaiveDateTime) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Write the chart as a `ganttchart` environment from the LaTeX pgfgantt package
+pub(crate) fn write_tikz_file(mut writer: impl Write, rd: &RenderData) -> Result<(), GanttError> {
+    let mut tex = String::new();
+    let num_days = (rd.end_date - rd.start_date).num_days() + 1;
+
+    let _ = writeln!(
+        tex,
+        "% Generated by gantt-chart {}",
+        env!("CARGO_PKG_VERSION")
+    );
+    let _ = writeln!(tex, "% Requires \\usepackage{{pgfgantt}} in the preamble");
+
+    for (i, rgb) in rd.colors.iter().enumerate() {
+        let _ = writeln!(
+            tex,
+            "\\definecolor{{{}{}}}{{HTML}}{{{:06X}}}",
+            rd.color_class, i, rgb
+        );
+    }
+
+    let _ = writeln!(
+        tex,
+        "\\begin{{ganttchart}}[time slot format=isodate, x unit=1.5mm, y unit chart=6mm, hgrid, title label font=\\small, bar label font=\\small, milestone label font=\\small, bar height=0.6, milestone/.append style={{fill=black}}]{{{}}}{{{}}}",
+        iso_date(rd.start_date),
+        iso_date(rd.end_date)
+    );
+    let _ = writeln!(
+        tex,
+        "\\gantttitle{{{}}}{{{}}} \\\\",
+        escape_latex(&rd.title),
+        num_days
+    );
+    let _ = writeln!(tex, "\\gantttitlecalendar{{month=shortname}} \\\\");
+
+    for (i, row) in rd.rows.iter().enumerate() {
+        if i > 0 {
+            tex.push_str(" \\\\\n");
+        }
+
+        match row.end_date {
+            Some(end_date) => {
+                // pgfgantt bar end dates are inclusive
+                let _ = write!(
+                    tex,
+                    "\\ganttbar[bar/.append style={{{}draw={}{}}}]{{{}}}{{{}}}{{{}}}",
+                    if row.open {
+                        String::new()
+                    } else {
+                        format!("fill={}{}, ", rd.color_class, row.color_index)
+                    },
+                    rd.color_class,
+                    row.color_index,
+                    escape_latex(&row.title),
+                    iso_date(row.start_date),
+                    iso_date(end_date - Duration::seconds(1))
+                );
+            }
+            None => {
+                let _ = write!(
+                    tex,
+                    "\\ganttmilestone{{{}}}{{{}}}",
+                    escape_latex(&row.title),
+                    iso_date(row.start_date)
+                );
+            }
+        }
+    }
+
+    tex.push('\n');
+
+    if let Some(marked_date) = rd.marked_date {
+        let _ = writeln!(
+            tex,
+            "\\ganttvrule[vrule/.append style={{dashed, draw=gray}}]{{}}{{{}}}",
+            iso_date(marked_date - Duration::days(1))
+        );
+    }
+
+    let _ = writeln!(tex, "\\end{{ganttchart}}");
+
+    writer.write_all(tex.as_bytes())?;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use crate::GanttChartLog;
+use core::fmt::Arguments;
+
+/// Forwards chart diagnostics to the [tracing](https://docs.rs/tracing) ecosystem
+///
+/// Messages are emitted as events with the `gantt_chart` target, so an embedding application can filter and
+/// format them with whatever subscriber it already uses.
+pub struct TracingLog;
+
+impl GanttChartLog for TracingLog {
+    fn output(&self, args: Arguments) {
+        tracing::info!(target: "gantt_chart", "{}", args);
+    }
+
+    fn warning(&self, args: Arguments) {
+        tracing::warn!(target: "gantt_chart", "{}", args);
+    }
+
+    fn error(&self, args: Arguments) {
+        tracing::error!(target: "gantt_chart", "{}", args);
+    }
+
+    fn info(&self, args: Arguments) {
+        tracing::info!(target: "gantt_chart", "{}", args);
+    }
+
+    fn debug(&self, args: Arguments) {
+        tracing::debug!(target: "gantt_chart", "{}", args);
+    }
+
+    fn trace(&self, args: Arguments) {
+        tracing::trace!(target: "gantt_chart", "{}", args);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{GanttError, RenderData};
+use std::{fmt::Write as _, io::Write};
+
+fn escape_typst_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Write the chart as Typst drawing commands, using the fonts of the surrounding document.
+///
+/// All positions are multiples of `u` so the chart can be scaled by changing that one value.
+pub(crate) fn write_typst_file(
+    mut writer: impl Write,
+    add_resource_table: bool,
+    rd: &RenderData,
+) -> Result<(), GanttError> {
+    let mut typ = String::new();
+    let width = rd.width();
+    let rows_height = rd.rows.len() as f32 * rd.row_height;
+    let height = rd.gutter.top
+        + rows_height
+        + rd.capacity_height()
+        + (if add_resource_table {
+            rd.resource_gutter.height() + rd.resource_height
+        } else {
+            0.0
+        })
+        + rd.gutter.bottom;
+    let heading_y = rd.header_bottom() - rd.row_gutter.bottom - rd.row_height;
+
+    let _ = writeln!(
+        typ,
+        "// Generated by gantt-chart {}",
+        env!("CARGO_PKG_VERSION")
+    );
+    let _ = writeln!(typ, "#let u = 0.75pt");
+    let _ = writeln!(
+        typ,
+        "#let outer-lines = (paint: rgb(\"#aaaaaa\"), thickness: 3 * u)"
+    );
+    let _ = writeln!(
+        typ,
+        "#let inner-lines = (paint: rgb(\"#dddddd\"), thickness: 2 * u)"
+    );
+
+    for (i, rgb) in rd.colors.iter().enumerate() {
+        let _ = writeln!(typ, "#let {}-{} = rgb(\"#{:06x}\")", rd.color_class, i, rgb);
+    }
+
+    let _ = writeln!(typ, "#block(width: {} * u, height: {} * u)[", width, height);
+    let _ = writeln!(
+        typ,
+        "  #place(dx: {} * u, dy: 0 * u, box(height: {} * u, align(horizon, text(size: 18pt, \"{}\"))))",
+        rd.gutter.left,
+        rd.gutter.top / 2.0,
+        escape_typst_string(&rd.title)
+    );
+    let _ = writeln!(
+        typ,
+        "  #place(dx: {} * u, dy: {} * u, box(height: {} * u, align(horizon, text(size: 16pt, \"{}\"))))",
+        rd.gutter.left + rd.row_gutter.left,
+        heading_y,
+        rd.row_height,
+        escape_typst_string(&rd.labels.tasks)
+    );
+
+    for data_column in rd.data_columns.iter() {
+        let _ = writeln!(
+            typ,
+            "  #place(dx: {} * u, dy: {} * u, box(height: {} * u, align(horizon, text(size: 10pt, weight: \"bold\", \"{}\"))))",
+            data_column.offset + rd.row_gutter.left,
+            heading_y,
+            rd.row_height,
+            escape_typst_string(data_column.heading)
+        );
+    }
+
+    let mut x = rd.gutter.left + rd.title_width;
+
+    for col in rd.cols.iter() {
+        let _ = writeln!(
+            typ,
+            "  #place(dx: {} * u, dy: {} * u, line(start: (0pt, 0pt), end: (0pt, {} * u), stroke: inner-lines))",
+            x, rd.gutter.top, rows_height
+        );
+        let _ = writeln!(
+            typ,
+            "  #place(dx: {} * u, dy: {} * u, box(width: {} * u, height: {} * u, align(center + horizon, text(size: 16pt, \"{}\"))))",
+            x, heading_y, col.width, rd.row_height, escape_typst_string(&col.name)
+        );
+        x += col.width;
+    }
+
+    let _ = writeln!(
+        typ,
+        "  #place(dx: {} * u, dy: {} * u, line(start: (0pt, 0pt), end: (0pt, {} * u), stroke: inner-lines))",
+        x, rd.gutter.top, rows_height
+    );
+
+    for i in 0..=rd.rows.len() {
+        let y = rd.gutter.top + (i as f32 * rd.row_height);
+
+        let _ = writeln!(
+            typ,
+            "  #place(dx: {} * u, dy: {} * u, line(start: (0pt, 0pt), end: ({} * u, 0pt), stroke: {}))",
+            rd.gutter.left,
+            y,
+            width - rd.gutter.width(),
+            if i == 0 || i == rd.rows.len() {
+                "outer-lines"
+            } else {
+                "inner-lines"
+            }
+        );
+
+        if i == rd.rows.len() {
+            break;
+        }
+
+        let row = &rd.rows[i];
+        let bar_height = rd.row_height - rd.row_gutter.height();
+
+        let _ = writeln!(
+            typ,
+            "  #place(dx: {} * u, dy: {} * u, box(height: {} * u, align(horizon, text(size: 12pt, \"{}\"))))",
+            rd.gutter.left + rd.row_gutter.left,
+            y,
+            rd.row_height,
+            escape_typst_string(&row.title)
+        );
+
+        for data_column in rd.data_columns.iter() {
+            let _ = writeln!(
+                typ,
+                "  #place(dx: {} * u, dy: {} * u, box(height: {} * u, align(horizon, text(size: 10pt, fill: rgb(\"#444444\"), \"{}\"))))",
+                data_column.offset + rd.row_gutter.left,
+                y,
+                rd.row_height,
+                escape_typst_string(&data_column.cells[i])
+            );
+        }
+
+        if let Some(assignee) = rd.assignees.get(row.resource_index) {
+            let _ = writeln!(
+                typ,
+                "  #place(dx: {} * u, dy: {} * u, box(height: {} * u, align(horizon, text(size: 10pt, fill: rgb(\"#666666\"), \"{}\"))))",
+                rd.titles_end() + rd.row_gutter.left,
+                y,
+                rd.row_height,
+                escape_typst_string(assignee)
+            );
+        }
+
+        if let Some(length) = row.length {
+            let _ = writeln!(
+                typ,
+                "  #place(dx: {} * u, dy: {} * u, rect(width: {} * u, height: {} * u, radius: {} * u, {}))",
+                row.offset,
+                y + rd.row_gutter.top,
+                length,
+                bar_height,
+                rd.rect_corner_radius,
+                if row.open {
+                    format!("stroke: 2 * u + {}-{}", rd.color_class, row.color_index)
+                } else {
+                    format!("fill: {}-{}", rd.color_class, row.color_index)
+                }
+            );
+        } else {
+            let n = bar_height / 2.0;
+
+            let _ = writeln!(
+                typ,
+                "  #place(dx: {} * u, dy: {} * u, polygon(fill: black, (0pt, {} * u), ({} * u, 0pt), ({} * u, {} * u), ({} * u, {} * u)))",
+                row.offset - n,
+                y + rd.row_gutter.top,
+                n,
+                n,
+                2.0 * n,
+                n,
+                n,
+                2.0 * n
+            );
+        }
+    }
+
+    if let Some(offset) = rd.marked_date_offset {
+        let _ = writeln!(
+            typ,
+            "  #place(dx: {} * u, dy: {} * u, line(start: (0pt, 0pt), end: (0pt, {} * u), stroke: (paint: rgb(\"#888888\"), thickness: 2 * u, dash: \"dashed\")))",
+            offset,
+            rd.gutter.top - 5.0,
+            rows_height + 10.0
+        );
+    }
+
+    if add_resource_table {
+        let y = rd.gutter.top + rows_height + rd.capacity_height();
+        let block_width = rd.resource_height - rd.resource_gutter.height();
+
+        for (i, name) in rd.color_names.iter().enumerate() {
+            let x = rd.resource_gutter.left + ((i + 1) as f32) * 100.0;
+
+            let _ = writeln!(
+                typ,
+                "  #place(dx: {} * u, dy: {} * u, box(width: 100 * u, height: {} * u, align(right + horizon, text(size: 12pt, \"{}\"))))",
+                x - 105.0,
+                y,
+                rd.resource_height,
+                escape_typst_string(name)
+            );
+            let _ = writeln!(
+                typ,
+                "  #place(dx: {} * u, dy: {} * u, rect(width: {} * u, height: {} * u, radius: {} * u, fill: {}-{}))",
+                x + 5.0,
+                y + rd.resource_gutter.top,
+                block_width,
+                block_width,
+                rd.rect_corner_radius,
+                rd.color_class,
+                i
+            );
+        }
+    }
+
+    let _ = writeln!(typ, "]");
+
+    writer.write_all(typ.as_bytes())?;
+
+    Ok(())
+}
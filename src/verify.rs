@@ -0,0 +1,122 @@
+use crate::{GanttError, RenderData};
+
+// Allow for rounding in the layout
+static TOLERANCE: f32 = 0.01;
+
+/// Check that a chart's layout makes sense, with finite offsets, room for the bars in the rows and bars inside the chart
+///
+/// All the problems found are given in the error.
+pub fn verify_layout(rd: &RenderData) -> Result<(), GanttError> {
+    let mut problems = vec![];
+    let left = rd.gutter.left + rd.title_width;
+    let right = left + rd.cols.iter().map(|col| col.width).sum::<f32>();
+    let span_problem = |what: String, offset: f32, width: f32| -> Option<String> {
+        if !offset.is_finite() || !width.is_finite() {
+            Some(format!(
+                "{} has an offset or width that isn't a number",
+                what
+            ))
+        } else if width < -TOLERANCE {
+            Some(format!("{} has a negative width of {}", what, width))
+        } else if offset < left - TOLERANCE || offset + width > right + TOLERANCE {
+            Some(format!(
+                "{} at {} to {} is outside the chart area from {} to {}",
+                what,
+                offset,
+                offset + width,
+                left,
+                right
+            ))
+        } else {
+            None
+        }
+    };
+
+    for (i, col) in rd.cols.iter().enumerate() {
+        if !col.width.is_finite() || col.width <= 0.0 {
+            problems.push(format!("Column {} has a width of {}", i, col.width));
+        }
+    }
+
+    for (i, row) in rd.rows.iter().enumerate() {
+        problems.extend(span_problem(
+            format!("Row {} '{}'", i, row.title),
+            row.offset,
+            row.length.unwrap_or(0.0),
+        ));
+
+        for (j, &(offset, length)) in row.segments.iter().enumerate() {
+            problems.extend(span_problem(
+                format!("Segment {} of row {} '{}'", j, i, row.title),
+                offset,
+                length,
+            ));
+        }
+
+        if let Some((offset, length)) = row.forecast {
+            problems.extend(span_problem(
+                format!("The forecast of row {} '{}'", i, row.title),
+                offset,
+                length,
+            ));
+        }
+
+        if let Some((offset, length)) = row.actual {
+            problems.extend(span_problem(
+                format!("The actual dates of row {} '{}'", i, row.title),
+                offset,
+                length,
+            ));
+        }
+
+        if let Some((optimistic, pessimistic)) = row.estimate {
+            problems.extend(span_problem(
+                format!("The estimate of row {} '{}'", i, row.title),
+                optimistic,
+                pessimistic - optimistic,
+            ));
+        }
+    }
+
+    for band in rd.sprints.iter().chain(rd.phases.iter()) {
+        problems.extend(span_problem(
+            format!("The band '{}'", band.name),
+            band.offset,
+            band.width,
+        ));
+    }
+
+    for (i, absences) in rd.absences.iter().enumerate() {
+        for &(offset, width) in absences {
+            problems.extend(span_problem(
+                format!("An absence of resource {}", i),
+                offset,
+                width,
+            ));
+        }
+    }
+
+    // The marked date can be before or after the tasks, so it only has to be a number
+    if let Some(offset) = rd.marked_date_offset {
+        if !offset.is_finite() {
+            problems.push("The marked date has an offset that isn't a number".to_owned());
+        }
+    }
+
+    // Rows are stacked at the row height, so the bars need room between the row gutters
+    if !rd.row_height.is_finite() || rd.row_height <= rd.row_gutter.height() {
+        problems.push(format!(
+            "The row height of {} leaves no room for bars between the row gutters",
+            rd.row_height
+        ));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(GanttError::LayoutError(format!(
+            "The chart layout is invalid: {}",
+            problems.join("; ")
+        )))
+    }
+}